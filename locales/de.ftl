@@ -0,0 +1,487 @@
+# Deutsch. Fehlende Meldungen kommen aus dem englischen Katalog.
+
+language-name = Deutsch
+
+## Settings values
+
+easing-cubic-out = Ausklingend
+easing-linear = Linear
+easing-spring = Federnd
+fit-card = Karte
+fit-window = An Fenster anpassen
+fit-fill = Fenster füllen
+fit-actual = Originalgröße
+sort-mode-move = In Kategorieordner verschieben
+sort-mode-tag = In XMP-Filialdateien verschlagworten
+sort-mode-symlink = Symbolisch in Kategorieordner verlinken
+sort-mode-hardlink = Fest in Kategorieordner verlinken
+collision-rename = Beide behalten (umbenennen)
+collision-overwrite = Überschreiben
+collision-skip = Überspringen
+collision-ask = Nachfragen
+theme-system = Wie das System
+theme-dark = Dunkel
+theme-light = Hell
+theme-custom = Eigener Hintergrund
+layout-cross = Kreuz
+layout-corners = Ecken
+layout-bottom-row = Reihe am unteren Rand
+layout-column = Spalte
+layout-halves = Zwei Hälften
+layout-grid = Raster
+
+## Key bindings
+
+action-category = Kategorie { $number }
+action-undo = Letzte Verschiebung rückgängig machen
+action-redo = Zuletzt Rückgängiggemachtes wiederholen
+action-skip = Zum nächsten Bild springen
+action-previous = Zurück zum vorigen Bild
+action-delete = Bild in den Papierkorb verschieben
+action-keep = Bild an seinem Platz behalten
+action-help = Tastenkürzel ein- oder ausblenden
+action-chord = Kategorie per Buchstabe wählen
+action-new-category = Neue Kategorie hinzufügen
+action-pause = Sortieren anhalten oder fortsetzen
+action-fit = Bildanpassung wechseln
+action-history = Verlauf ein- oder ausblenden
+action-favorite = Bild mit Stern markieren oder Markierung entfernen
+action-previous-page = Vorige Seite eines mehrseitigen Bildes
+action-next-page = Nächste Seite eines mehrseitigen Bildes
+action-stats = Ladestatistik ein- oder ausblenden
+action-filter = Filterleiste ein- oder ausblenden
+action-compare = Halten zum Vergleichen mit dem vorigen Bild
+action-fullscreen = Vollbild ein- oder ausschalten
+mouse-back = Maus zurück
+mouse-forward = Maus vor
+wheel-up = Mausrad hoch
+wheel-down = Mausrad runter
+
+## Loading
+
+stage-read = Lesen
+stage-decode = Dekodieren
+stage-resize = Skalieren
+stage-convert = Umwandeln
+stage-upload = Hochladen
+image-name = Bild { $name }, { $position } von { $total }
+ffmpeg-missing = ffmpeg wurde nicht gefunden, daher zeigen Videos einen Platzhalter. Installieren Sie ffmpeg, um ihre Bilder zu sehen
+decode-failed = { $path } konnte nicht dekodiert werden
+skipped-hidden = { $count } versteckte
+skipped-empty = { $count } leere
+skipped-missing = { $count } fehlende
+skipped-not-images = { $count } andere
+skipped-one = { $kinds } Dateien
+skipped-many = { $rest } und { $last } Dateien
+
+## Category names
+
+category-empty = Kategorienamen dürfen nicht leer sein
+category-dots = „{ $name }“ kann nicht als Kategorie verwendet werden
+category-control = „{ $name }“ enthält ein Steuerzeichen ({ $character })
+category-forbidden = „{ $name }“ enthält „{ $character }“, was in Ordnernamen nicht erlaubt ist
+category-reserved = „{ $name }“ ist unter Windows ein reservierter Name
+category-trailing-dot = „{ $name }“ darf nicht mit einem Punkt enden
+category-none = Geben Sie mindestens eine Kategorie ein
+category-blank = Kategorie { $number } ist leer; entfernen Sie das überzählige Komma
+category-duplicate = „{ $name }“ ist derselbe Ordner wie „{ $earlier }“
+
+## Notifications
+
+notifications = Benachrichtigungen
+notification-history = Benachrichtigungsverlauf
+click-to-dismiss = Zum Schließen klicken
+nothing-to-report = Nichts zu melden
+clear = Leeren
+seconds-ago = vor { $seconds } s
+cache-entry-corrupt = Beschädigter Cache-Eintrag { $path } wird verworfen: { $error }
+cache-encode-failed = Cache-Eintrag für { $path } konnte nicht kodiert werden: { $error }
+cache-write-failed = Cache-Eintrag für { $path } konnte nicht geschrieben werden: { $error }
+
+## Sorting
+
+kept = Behalten
+direction-left = Linke Kategorie
+direction-right = Rechte Kategorie
+direction-up = Obere Kategorie
+direction-down = Untere Kategorie
+bucket-summary = { $count ->
+    [one] 1 Datei, { $size }
+   *[other] { $count } Dateien, { $size }
+}
+tags-update-failed = Schlagwörter von { $name } konnten nicht aktualisiert werden: { $error }
+op-move = { $from } nach { $to } verschieben
+op-link = { $from } von { $to } aus verlinken
+op-unlink = Verknüpfung { $link } entfernen
+op-replace-link = Verknüpfung { $link } durch { $original } ersetzen
+op-tag = { $path } als { $category } verschlagworten
+op-untag = Schlagwort { $category } von { $path } entfernen
+op-rate = { $path } mit { $rating ->
+    [one] 1 Stern
+   *[other] { $rating } Sternen
+} bewerten
+op-unrate = Bewertung von { $path } entfernen
+op-failed = Fehlgeschlagen: { $operation }: { $error }
+disk-full = Fehlgeschlagen: { $operation }: Der Datenträger ist voll. Das Sortieren ist pausiert; schaffen Sie Platz, um weiterzumachen
+low-space = Nur { $free } frei für { $name }, wohin gleich { $needed } kommen
+folder-unreadable = { $path } kann nicht gelesen werden: { $error }
+scan-skipped = { $count ->
+    [one] 1 Bild
+   *[other] { $count } Bilder
+} gefunden; übersprungen: { $skipped }
+revert-blocked = { $path } kann nicht zurückgesetzt werden: { $existing } existiert bereits
+undo-deleted = Verschieben von { $path } kann nicht rückgängig gemacht werden: Die Datei wurde außerhalb von LeftRight aus { $category } gelöscht
+batch-stopped = Stapel nach { $count ->
+    [one] 1 Bild
+   *[other] { $count } Bildern
+} angehalten, weil eine Dateioperation fehlgeschlagen ist
+still-moving = { $name } wird noch verschoben; machen Sie es rückgängig, sobald das erledigt ist
+grouped-similar = { $count } ähnliche Bilder in { $groups ->
+    [one] 1 Gruppe
+   *[other] { $groups } Gruppen
+} zusammengestellt
+folder-create-failed = Ordner { $path } konnte nicht angelegt werden: { $error }
+sorted-by-date = { $count ->
+    [one] 1 Bild
+   *[other] { $count } Bilder
+} nach Datum sortiert ({ $by_file_date } nach Dateidatum, ohne EXIF)
+planned-applied = { $applied } von { $count } geplanten Änderungen ausgeführt
+tags-exported = Schlagwörter nach { $path } exportiert
+tags-export-failed = Schlagwörter konnten nicht exportiert werden: { $error }
+copy-favorites-to = Favoriten kopieren nach
+copy-failed = { $path } konnte nicht kopiert werden: { $error }
+favorites-copied = { $count ->
+    [one] 1 Favorit
+   *[other] { $count } Favoriten
+} nach { $path } kopiert
+session-save-failed = Sitzung konnte nicht gespeichert werden: { $error }
+settings-save-failed = Einstellungen konnten nicht gespeichert werden: { $error }
+export-report-title = Bericht exportieren
+report-exported = Bericht nach { $path } exportiert
+report-export-failed = Bericht konnte nicht exportiert werden: { $error }
+export-board-title = Ansicht als Bild exportieren
+export-board-width = Breite
+board-footer = { $moved } verschoben · { $kept } behalten · { $left } übrig · { $time }
+board-exported = Ansicht als Bild in { $path } gespeichert
+board-export-failed = Ansicht konnte nicht als Bild exportiert werden: { $error }
+open-folder-title = Ordner öffnen
+open-folder-busy = Warten Sie, bis die Dateioperationen abgeschlossen sind, und übernehmen oder verwerfen Sie geplante Änderungen, bevor Sie einen anderen Ordner öffnen
+folder-open-failed = { $path } kann nicht geöffnet werden: { $error }
+file-disappeared = { $name } ist nicht mehr vorhanden und wurde aus der Warteschlange genommen
+drop-hint = Einen Ordner ablegen, um ihn zu öffnen, oder Bilder, um sie einzureihen
+dropped-images = { $count ->
+    [one] 1 abgelegtes Bild
+   *[other] { $count } abgelegte Bilder
+} zur Warteschlange hinzugefügt
+dropped-nothing = Keine der abgelegten Dateien ist ein Bild
+page-decode-failed = Seite { $page } von { $name } konnte nicht dekodiert werden
+trash-failed = { $path } konnte nicht in den Papierkorb verschoben werden: { $error }
+category-folder-failed = Kategorieordner { $name } konnte nicht angelegt werden: { $error }
+category-exists = Kategorie { $name } gibt es bereits
+category-limit = { $name } kann nicht hinzugefügt werden: höchstens { $max } Kategorien sind möglich
+updated-externally = von außen geändert
+more-cards = +{ $count } weitere
+moving = wird verschoben…
+bucket-preview = { $category } — { $count ->
+    [one] 1 Datei
+   *[other] { $count } Dateien
+}
+overview = Übersicht
+overview-hint = Klicken zum Auswählen, Umschalt-Klick wählt einen Bereich, Doppelklick öffnet
+overview-selected = { $count } ausgewählt — Kategorietaste drücken, um sie einzusortieren
+select-none = Auswahl aufheben
+review-bucket = { $category } durchsehen
+left-in-place = { $path } bleibt, wo es ist: { $existing } existiert bereits
+unlink-failed = Verknüpfung { $link } konnte nicht entfernt werden: { $error }
+return-failed = { $path } konnte nicht zurückgelegt werden: { $error }
+folder-remove-failed = Ordner { $path } konnte nicht entfernt werden: { $error }
+already-exists = { $path } existiert bereits
+unknown-category = Es gibt keine Kategorie namens { $name }
+nothing-to-sort = Es ist kein Bild mehr zu sortieren
+category-rename-failed = { $old } konnte nicht in { $new } umbenannt werden: { $error }
+folder-recreate-failed = Ordner { $name } konnte nicht wieder angelegt werden: { $error }
+collision-skipped = { $path } übersprungen: { $existing } existiert bereits
+move-failed = { $path } konnte nicht verschoben werden: { $error }
+oversized-skipped = { $name } übersprungen: Bild mit { $megapixels } MP
+return-to-queue = Zurück in die Warteschlange
+return-blocked = { $path } kann nicht zurückgelegt werden: { $existing } existiert bereits
+loading-images = Bilder werden geladen… ({ $loaded }/{ $total })
+page-of = Seite { $page } von { $pages }
+comparing-previous = Vorheriges: { $name }
+group-position = { $position } von { $count } in dieser Gruppe
+star-image = Bild mit Stern markieren
+unstar-image = Sternmarkierung entfernen
+oversized = Übersprungen: Bild mit { $megapixels } MP
+decode-anyway = Trotzdem dekodieren
+decode-anyway-hint = Das kann viel Speicher brauchen
+undecodable = Dieses Bild konnte nicht dekodiert werden
+decode-again = Erneut versuchen
+loading-this-image = Dieses Bild wird geladen… { $seconds } s
+paused-banner = Angehalten — { $key } drücken, um fortzufahren
+batch-banner = Noch { $count } nach { $category } — Esc bricht ab
+repeat-banner = ×{ $count } — Kategorietaste drücken
+filter-no-matches = Keine Dateien in der Warteschlange passen zum Filter ({ $hidden } ausgeblendet)
+all-done = Alles erledigt!
+    { $moved } verschoben, { $kept } behalten
+export-report = Bericht exportieren…
+export-board = Ansicht als Bild exportieren…
+export-tags = Schlagwörter als CSV exportieren
+commit-links = Verknüpfungen in Verschiebungen umwandeln
+commit-links-hint = Jedes verknüpfte Original anstelle der Verknüpfung in seinen Kategorieordner verschieben
+committing-links = { $done } von { $count } Verknüpfungen ersetzt
+links-committed = { $applied } von { $count } Verknüpfungen durch ihre Dateien ersetzt
+symlink-not-permitted = Windows erlaubt symbolische Verknüpfungen nur im Entwicklermodus oder als Administrator; versuche den Hardlink-Modus
+favorites-heading = ★ { $count ->
+    [one] 1 Favorit
+   *[other] { $count } Favoriten
+}
+copy-list = Liste in die Zwischenablage kopieren
+copy-files = Dateien in Ordner kopieren…
+bucket-layout = Anordnung der Ablagen
+open-folder = Ordner öffnen…
+auto-sort-by-date = Automatisch nach Datum sortieren
+auto-sort-by-date-hint = Jedes Bild der Warteschlange nach seinem Aufnahmedatum in einen Ordner { $layout } einsortieren
+group-similar = Ähnliche Bilder gruppieren
+grouping-similar = Ähnliche Bilder werden gruppiert…
+group-similar-hint = Warteschlange so umordnen, dass Serien ähnlicher Aufnahmen direkt aufeinander folgen
+move-all-remaining-to = Alle übrigen verschieben nach
+include-undone = Rückgängig gemachte Verschiebungen einschließen
+title-loading = Laden { $percent } % — LeftRight
+title-sorting = { $name } — { $count } übrig — LeftRight
+finishing-operations = { $count ->
+    [one] 1 Dateioperation wird
+   *[other] { $count } Dateioperationen werden
+} abgeschlossen…
+planned-uncommitted = { $count ->
+    [one] 1 Probelauf-Änderung wurde
+   *[other] { $count } Probelauf-Änderungen wurden
+} noch nicht übernommen
+commit-them = Übernehmen
+quit-anyway = Trotzdem beenden
+moves-recovered = { $count ->
+    [one] 1 beim letzten Mal abgebrochene Verschiebung abgeschlossen
+   *[other] { $count } beim letzten Mal abgebrochene Verschiebungen abgeschlossen
+}
+move-recovery-failed = { $path } konnte nicht nach { $to } verschoben werden: { $error }
+planned-menu = 📝 { $count } geplant
+planned-empty = Probelauf: Sortierte Bilder werden nur vorgemerkt
+commit = Übernehmen
+load-statistics = Ladestatistik
+images-per-second = Bilder/Sekunde
+stage-average = Durchschnitt { $stage }
+waiting-to-decode = Warten aufs Dekodieren
+waiting-to-upload = Warten aufs Hochladen
+texture-memory = Texturspeicher
+failed-decodes = Fehlgeschlagene Dekodierungen
+failed-to-load = { $count ->
+    [one] 1 Bild in der Warteschlange konnte nicht geladen werden
+   *[other] { $count } Bilder in der Warteschlange konnten nicht geladen werden
+}
+skip-failures = Alle Fehlschläge überspringen
+skip-failures-hint = Die Bilder, die nicht dekodiert werden konnten, aus der Warteschlange nehmen. Die Dateien bleiben, wo sie sind
+skipped-failures = { $count ->
+    [one] 1 nicht ladbares Bild aus der Warteschlange genommen
+   *[other] { $count } nicht ladbare Bilder aus der Warteschlange genommen
+}
+history = Verlauf
+nothing-sorted = Noch nichts sortiert
+revert = Zurücksetzen
+revert-hint = Nur diese Verschiebung rückgängig machen
+images-left = { $count ->
+    [one] 1 Bild übrig
+   *[other] { $count } Bilder übrig
+}
+scanning = wird durchsucht…
+loading-paused = Laden pausiert
+resume-loading = ▶ Laden fortsetzen
+pause-loading = ⏸ Laden pausieren
+pause-loading-hint = Bilder nicht mehr im Hintergrund dekodieren, um Akku zu sparen. Bereits geladene Bilder bleiben
+new-category = Neue Kategorie
+add = Hinzufügen
+cancel = Abbrechen
+then-a-letter = { $key }, dann ein Buchstabe
+edit-categories = Kategorien bearbeiten
+dry-run-blocks = Erst übernehmen oder den Probelauf ausschalten
+save = Speichern
+category-files = { $category } ({ $count ->
+    [one] 1 Datei
+   *[other] { $count } Dateien
+})
+rename = Umbenennen
+remove = Entfernen
+remove-not-empty = Hier lassen sich nur leere Kategorien entfernen, und { $category } enthält noch { $count ->
+    [one] 1 Datei
+   *[other] { $count } Dateien
+}. Führen Sie sie zuerst unter Einstellungen → Kategorien mit einer anderen zusammen.
+renaming = Wird umbenannt…
+new-category-key = Eine neue Kategorie bekommt { $key }
+cannot-read-folder = Ordner kann nicht gelesen werden
+try-again = Erneut versuchen
+move-all-remaining = Alle übrigen verschieben
+move-all-confirm = { $count ->
+    [one] Das 1 übrige Bild
+   *[other] Alle { $count } übrigen Bilder
+} nach { $category } verschieben?
+move-all-undo = Ein einziges Rückgängig holt sie alle zurück.
+confirm-move-title = Dateien verschieben?
+confirm-move-explain = Eine Kategorie zu wählen verschiebt die Datei auf der Festplatte. Dieses Bild kommt nach { $category } ({ $folder }), ebenso jedes weitere so sortierte Bild.
+confirm-move-undo = { $undo } holt eine verschobene Datei zurück. Die anderen Modi und der Probelauf unter Einstellungen → Sortieren lassen Dateien, wo sie sind.
+dont-show-again = Nicht mehr anzeigen
+confirm-first-move = Die erste Verschiebung einer Sitzung bestätigen
+move = Verschieben
+file-exists = Datei existiert bereits
+file-exists-in = In { $category } gibt es schon eine Datei namens { $name }
+moving-file = Wird verschoben
+already-there = Schon vorhanden
+more-waiting = { $count } weitere warten
+apply-to-all = Für alle weiteren Namenskonflikte verwenden
+move-to = Verschieben nach…
+chord-search-hint = Eingabe wählt den ersten Treffer, Escape bricht ab
+chord-hint = Buchstaben drücken, / zum Suchen, Escape zum Abbrechen
+palette-hint = Tippen grenzt ein, ↑ ↓ wählt, Eingabe verschiebt, Escape bricht ab
+keyboard-shortcuts = Tastenkürzel
+unused-category = { $label } (nicht belegt)
+help-close = ? oder Escape schließt
+
+## Settings
+
+settings = Einstellungen
+settings-sorting = Sortieren
+choosing-a-category = Eine Kategorie wählen
+slideshow = Diashow
+slideshow-hint = Zum nächsten Bild weitergehen, wenn eine Weile nichts passiert
+auto-pause = Automatisch anhalten
+auto-pause-hint = Sortieren anhalten, wenn eine Weile keine Eingabe kommt
+stick-sort = Mit dem Stick sortieren
+stick-sort-hint = Den linken Stick des Controllers nach links, rechts, oben oder unten schnippen sortiert das Bild wie das Steuerkreuz. Vor dem nächsten Schnipsen muss der Stick zurück in die Mitte
+rename-on-move = Beim Verschieben umbenennen
+rename-tokens = Platzhalter: { $tokens }
+when-name-taken = Wenn der Name vergeben ist
+date-folders = Datumsordner
+rate-with-numbers = Mit Zifferntasten bewerten
+rate-with-numbers-hint = 1-5 setzt eine Sternbewertung in der XMP-Filialdatei und geht weiter; 0 entfernt sie
+dry-run = Probelauf
+dry-run-hint = Wie gewohnt sortieren, aber die Dateien erst beim Übernehmen anfassen
+settings-appearance = Darstellung
+language = Sprache
+language-system = Wie das System
+theme = Farbschema
+background-color = Hintergrundfarbe
+image-fit = Bildanpassung
+checkerboard = Schachbrett hinter transparenten Bildern
+large-text = Große Schrift
+large-text-hint = Größere Schrift, Beschriftungen und Schaltflächen überall
+settings-loading = Laden
+preload-ahead = Im Voraus laden
+keep-behind = Zurück behalten
+preview-size = Vorschaugröße
+preview-size-hint = Längste Seite, auf die Bilder zur Anzeige verkleinert werden. Größer ist schärfer, aber langsamer und braucht mehr Speicher
+resize-filter = Skalierungsfilter
+resize-nearest = Nächster Nachbar (am schnellsten)
+resize-triangle = Dreieck
+resize-lanczos3 = Lanczos3 (am schärfsten)
+texture-memory-usage = Texturspeicher: { $size } für { $count } Bilder und ihre Vorschaubilder
+reload-visible = Sichtbare Bilder neu laden
+reload-visible-hint = Die Bilder um das aktuelle herum mit den obigen Einstellungen neu dekodieren; sonst gelten sie erst für ab jetzt geladene Bilder
+settings-key-bindings = Tastenbelegung
+press-a-key = Taste oder Maustaste drücken…
+settings-animation = Animation
+reduce-motion = Bewegung reduzieren
+reduce-motion-hint = Die Flug-Animation in die Ablage ganz weglassen
+duration = Dauer
+crossfade = Überblendung zwischen Bildern
+easing = Verlauf
+settings-sound = Ton
+sound-enabled = Töne abspielen
+volume = Lautstärke
+sound-sort = Sortieren
+sound-undo = Rückgängig
+sound-error = Fehlgeschlagenes Verschieben
+sound-done = Warteschlange fertig
+sound-built-in = Eingebaut
+sound-file-hint = Pfad zu einer WAV- oder Ogg-Vorbis-Datei, die stattdessen abgespielt wird; leer lassen für den eingebauten Ton
+sound-test = Abspielen
+category-keys = Kategorien:
+arrows = Pfeiltasten
+reset-all = Alle zurücksetzen
+settings-categories = Kategorien
+merge-into = Zusammenführen mit…
+remove-category-hint = Ihre Dateien zurück in die Warteschlange legen und den Ordner löschen
+settings-this-folder = Dieser Ordner
+kept-in-place = { $count ->
+    [one] 1 Bild an seinem Platz behalten
+   *[other] { $count } Bilder an ihrem Platz behalten
+}
+reset-kept = Behaltene zurücksetzen
+image-extensions = Bilddateiendungen
+image-extensions-hint = Gilt beim nächsten Durchsuchen des Ordners
+detect-by-content = Bilder am Inhalt erkennen
+detect-by-content-hint = Auch die ersten Bytes von Dateien mit anderer oder ohne Endung prüfen
+max-megapixels = Größtes zu dekodierendes Bild
+max-megapixels-hint = Größere Bilder werden übersprungen, mit einer Schaltfläche, um sie trotzdem zu dekodieren
+big-folder-images = Nachfragen ab so vielen Bildern
+big-file-size = Nachfragen bei Bildern größer als
+big-folder-hint = Schützt davor, versehentlich einen riesigen Ordner wie das Home-Verzeichnis einzulesen
+low-space-margin = Warnen, wenn auf einem Ziellaufwerk weniger frei bliebe als
+low-space-margin-hint = Geprüft für Kategorieordner auf einem anderen Laufwerk, beim Start und bevor eine große Datei dorthin verschoben wird
+big-folder-title = Das ist ein großer Ordner
+big-folder-summary = { $path } enthält { $count } Bilder, zusammen { $size }. Fortfahren?
+big-folder-counting = Zähle noch…
+big-folder-largest = Das größte, { $name }, hat { $size }.
+continue = Fortfahren
+narrow-with-filters = Mit Filtern eingrenzen
+narrow-with-filters-hint = Fortfahren, aber Dateien über der Größengrenze weglassen; die Filterleiste kann weiter eingrenzen
+occupied-title = Kategorieordner sind nicht leer
+occupied-summary = Diese Kategorieordner enthalten schon Dateien, vielleicht aus einer früheren Sitzung. Sie würden neben dem, was du jetzt sortierst, auf den Stapeln landen.
+occupied-folder = { $name } — { $count ->
+    [one] 1 Eintrag
+   *[other] { $count } Einträge
+}
+occupied-merge = Fortfahren
+occupied-merge-hint = Die Dateien behalten und dazusortieren
+occupied-rename = Andere Namen wählen
+occupied-empty = Vorher leeren
+occupied-empty-hint = Alles in diesen Ordnern in den Papierkorb verschieben, dann beginnen
+emptying-folders = Verschiebe in den Papierkorb…
+empty-folders-failed = Die Kategorieordner konnten nicht geleert werden: { $error }
+settings-cache = Vorschaubild-Cache
+cache-size-limit = Größenlimit
+cache-usage = Belegt: { $size }
+clear-cache = Cache leeren
+no-cache = Auf diesem System gibt es kein Cache-Verzeichnis
+
+## Top bar
+
+menu-file = Datei
+settings-button = ⚙ Einstellungen
+pause-button = 🔒 Anhalten
+resume-button = 🔓 Fortsetzen
+pause-hint = Sortiereingaben bis zum Fortsetzen ignorieren ({ $key })
+overview-button = ▦ Übersicht
+categories-button = ✏ Kategorien
+filter-hint = Filter, z. B. IMG_*
+filter-tooltip = Nur Dateien sortieren, deren Name dies enthält oder dem Muster entspricht
+filter-button = 🔍 Filter
+filter-matching = { $count } von { $total } passen zum Filter
+filter-modified = Geändert
+filter-from = von
+filter-to = bis
+filter-date-hint = Ein Tag als JJJJ-MM-TT; jedes Ende kann leer bleiben
+filter-size = Größe
+filter-min = min
+filter-max = max
+filter-clear = Zurücksetzen
+
+## Setup
+
+setup-title = Kategorien einrichten
+setup-heading = Kategorien eingeben
+setup-hint = Durch Kommas trennen (1-{ $max } Kategorien)
+setup-count = { $count } von { $max } Kategorien
+start = Los geht's
+too-many-categories = { $count } Kategorien angegeben, aber höchstens { $max } sind möglich
+shortcuts = Tastenkürzel
+found-images = { $count ->
+    [one] 1 Bild
+   *[other] { $count } Bilder
+} gefunden…
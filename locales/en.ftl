@@ -0,0 +1,485 @@
+# English, built in. Translations are read from <config dir>/leftright/locales/<code>.ftl
+# and only need the messages they change; anything missing comes from here.
+
+language-name = English
+
+## Settings values
+
+easing-cubic-out = Ease out
+easing-linear = Linear
+easing-spring = Spring
+fit-card = Card
+fit-window = Fit to window
+fit-fill = Fill window
+fit-actual = Actual size
+sort-mode-move = Move into category folders
+sort-mode-tag = Tag in XMP sidecars
+sort-mode-symlink = Symlink into category folders
+sort-mode-hardlink = Hard link into category folders
+collision-rename = Keep both (rename)
+collision-overwrite = Overwrite
+collision-skip = Skip
+collision-ask = Ask
+theme-system = Follow system
+theme-dark = Dark
+theme-light = Light
+theme-custom = Custom background
+layout-cross = Cross
+layout-corners = Corners
+layout-bottom-row = Row along the bottom
+layout-column = Column
+layout-halves = Two halves
+layout-grid = Grid
+
+## Key bindings
+
+action-category = Category { $number }
+action-undo = Undo last move
+action-redo = Redo last undo
+action-skip = Skip to next image
+action-previous = Back to previous image
+action-delete = Move image to trash
+action-keep = Keep image where it is
+action-help = Toggle shortcut help
+action-chord = Pick category by letter
+action-new-category = Add a new category
+action-pause = Pause or resume sorting
+action-fit = Cycle image fit
+action-history = Toggle move history
+action-favorite = Star or unstar image
+action-previous-page = Previous page of a multi-page image
+action-next-page = Next page of a multi-page image
+action-stats = Toggle load statistics
+action-filter = Toggle the filter bar
+action-compare = Hold to compare with the previous image
+action-fullscreen = Toggle fullscreen
+mouse-back = Mouse back
+mouse-forward = Mouse forward
+wheel-up = Wheel up
+wheel-down = Wheel down
+
+## Loading
+
+stage-read = read
+stage-decode = decode
+stage-resize = resize
+stage-convert = convert
+stage-upload = upload
+image-name = Image { $name }, { $position } of { $total }
+ffmpeg-missing = ffmpeg wasn't found, so videos show a placeholder. Install ffmpeg to see their frames
+decode-failed = Couldn't decode { $path }
+skipped-hidden = { $count } hidden
+skipped-empty = { $count } empty
+skipped-missing = { $count } missing
+skipped-not-images = { $count } non-image
+skipped-one = { $kinds } files
+skipped-many = { $rest } and { $last } files
+
+## Category names
+
+category-empty = Category names can't be empty
+category-dots = "{ $name }" can't be used as a category
+category-control = "{ $name }" contains a control character ({ $character })
+category-forbidden = "{ $name }" contains '{ $character }', which folder names can't
+category-reserved = "{ $name }" is a reserved name on Windows
+category-trailing-dot = "{ $name }" can't end with a dot
+category-none = Enter at least one category
+category-blank = Category { $number } is empty; remove the extra comma
+category-duplicate = "{ $name }" is the same folder as "{ $earlier }"
+
+## Notifications
+
+notifications = Notifications
+notification-history = Notification history
+click-to-dismiss = Click to dismiss
+nothing-to-report = Nothing to report
+clear = Clear
+seconds-ago = { $seconds }s ago
+cache-entry-corrupt = Discarding corrupt cache entry { $path }: { $error }
+cache-encode-failed = Failed to encode cache entry for { $path }: { $error }
+cache-write-failed = Failed to write cache entry for { $path }: { $error }
+
+## Sorting
+
+kept = Kept
+direction-left = Left category
+direction-right = Right category
+direction-up = Up category
+direction-down = Down category
+bucket-summary = { $count ->
+    [one] 1 file, { $size }
+   *[other] { $count } files, { $size }
+}
+tags-update-failed = Failed to update tags for { $name }: { $error }
+op-move = move { $from } to { $to }
+op-link = link { $from } from { $to }
+op-unlink = remove link { $link }
+op-replace-link = replace link { $link } with { $original }
+op-tag = tag { $path } as { $category }
+op-untag = remove tag { $category } from { $path }
+op-rate = rate { $path } { $rating ->
+    [one] 1 star
+   *[other] { $rating } stars
+}
+op-unrate = clear the rating of { $path }
+op-failed = Failed to { $operation }: { $error }
+disk-full = Failed to { $operation }: the disk is full. Sorting is paused; free up some space to go on
+low-space = Only { $free } free for { $name }, which is about to receive { $needed }
+folder-unreadable = Cannot read { $path }: { $error }
+scan-skipped = Found { $count ->
+    [one] 1 image
+   *[other] { $count } images
+}; skipped { $skipped }
+revert-blocked = Cannot revert { $path }: { $existing } already exists
+undo-deleted = Cannot undo moving { $path }: it was deleted from { $category } outside LeftRight
+batch-stopped = Stopped the batch after { $count ->
+    [one] 1 image
+   *[other] { $count } images
+} because a file operation failed
+still-moving = { $name } is still being moved; undo it once it's done
+grouped-similar = Put { $count } similar images together in { $groups ->
+    [one] 1 group
+   *[other] { $groups } groups
+}
+folder-create-failed = Failed to create folder { $path }: { $error }
+sorted-by-date = Sorted { $count ->
+    [one] 1 image
+   *[other] { $count } images
+} by date ({ $by_file_date } by file date, no EXIF)
+planned-applied = Applied { $applied } of { $count } planned changes
+tags-exported = Exported tags to { $path }
+tags-export-failed = Failed to export tags: { $error }
+copy-favorites-to = Copy favorites to
+copy-failed = Failed to copy { $path }: { $error }
+favorites-copied = Copied { $count ->
+    [one] 1 favorite
+   *[other] { $count } favorites
+} to { $path }
+session-save-failed = Failed to save session: { $error }
+settings-save-failed = Failed to save settings: { $error }
+export-report-title = Export report
+report-exported = Exported report to { $path }
+report-export-failed = Failed to export report: { $error }
+export-board-title = Export board image
+export-board-width = Width
+board-footer = { $moved } moved · { $kept } kept · { $left } left · { $time }
+board-exported = Saved the board image to { $path }
+board-export-failed = Failed to export the board image: { $error }
+open-folder-title = Open folder
+open-folder-busy = Wait for file operations to finish, and commit or discard planned changes, before opening another folder
+folder-open-failed = Cannot open { $path }: { $error }
+file-disappeared = { $name } is no longer on disk, so it left the queue
+drop-hint = Drop a folder to open it, or images to add them to the queue
+dropped-images = Added { $count ->
+    [one] 1 dropped image
+   *[other] { $count } dropped images
+} to the queue
+dropped-nothing = None of the dropped files are images
+page-decode-failed = Couldn't decode page { $page } of { $name }
+trash-failed = Failed to move { $path } to trash: { $error }
+category-folder-failed = Failed to create category folder { $name }: { $error }
+category-exists = Category { $name } already exists
+category-limit = Cannot add { $name }: at most { $max } categories are supported
+updated-externally = updated externally
+more-cards = +{ $count } more
+moving = moving…
+bucket-preview = { $category } — { $count ->
+    [one] 1 file
+   *[other] { $count } files
+}
+overview = Overview
+overview-hint = Click to select, Shift-click to select a run, double-click to open
+overview-selected = { $count } selected — press a category key to file them
+select-none = Select none
+review-bucket = Review { $category }
+left-in-place = Leaving { $path } in place: { $existing } already exists
+unlink-failed = Failed to remove link { $link }: { $error }
+return-failed = Failed to return { $path }: { $error }
+folder-remove-failed = Failed to remove folder { $path }: { $error }
+already-exists = { $path } already exists
+unknown-category = There is no category called { $name }
+nothing-to-sort = There is no image left to sort
+category-rename-failed = Failed to rename { $old } to { $new }: { $error }
+folder-recreate-failed = Failed to recreate folder { $name }: { $error }
+collision-skipped = Skipped { $path }: { $existing } already exists
+move-failed = Failed to move { $path }: { $error }
+oversized-skipped = Skipped { $name }: { $megapixels } MP image
+return-to-queue = Return to queue
+return-blocked = Cannot return { $path }: { $existing } already exists
+loading-images = Loading images... ({ $loaded }/{ $total })
+page-of = Page { $page } of { $pages }
+comparing-previous = Previous: { $name }
+group-position = { $position } of { $count } in this group
+star-image = Star image
+unstar-image = Unstar image
+oversized = Skipped: { $megapixels } MP image
+decode-anyway = Decode anyway
+decode-anyway-hint = This can take a lot of memory
+undecodable = Couldn't decode this image
+decode-again = Try again
+loading-this-image = Loading this image… { $seconds }s
+paused-banner = Paused — press { $key } to resume
+batch-banner = { $count } more to { $category } — Esc to stop
+repeat-banner = ×{ $count } — press a category key
+filter-no-matches = No queued files match the filter ({ $hidden } hidden)
+all-done = All done!
+    { $moved } moved, { $kept } kept
+export-report = Export report…
+export-board = Export board image…
+export-tags = Export tags as CSV
+commit-links = Turn links into moves
+commit-links-hint = Move each linked original into its category folder in place of the link
+committing-links = { $done } of { $count } links replaced
+links-committed = Replaced { $applied } of { $count } links with their files
+symlink-not-permitted = Windows only allows symbolic links with Developer Mode on or as administrator; try the hard link mode
+favorites-heading = ★ { $count ->
+    [one] 1 favorite
+   *[other] { $count } favorites
+}
+copy-list = Copy list to clipboard
+copy-files = Copy files to folder…
+bucket-layout = Bucket layout
+open-folder = Open folder…
+auto-sort-by-date = Auto-sort by date
+auto-sort-by-date-hint = File every queued image into a { $layout } folder from its capture date
+group-similar = Group similar images
+grouping-similar = Grouping similar images…
+group-similar-hint = Reorder the queue so bursts of lookalike shots come one after another
+move-all-remaining-to = Move all remaining to
+include-undone = Include undone moves
+title-loading = Loading { $percent }% — LeftRight
+title-sorting = { $name } — { $count } left — LeftRight
+finishing-operations = Finishing { $count ->
+    [one] 1 file operation
+   *[other] { $count } file operations
+}…
+planned-uncommitted = { $count ->
+    [one] 1 dry-run change hasn't
+   *[other] { $count } dry-run changes haven't
+} been committed
+commit-them = Commit them
+quit-anyway = Quit anyway
+moves-recovered = { $count ->
+    [one] Finished 1 move that was cut short last time
+   *[other] Finished { $count } moves that were cut short last time
+}
+move-recovery-failed = Failed to finish moving { $path } to { $to }: { $error }
+planned-menu = 📝 { $count } planned
+planned-empty = Dry run: sorted images are only noted down
+commit = Commit
+load-statistics = Load statistics
+images-per-second = Images/second
+stage-average = Average { $stage }
+waiting-to-decode = Waiting to decode
+waiting-to-upload = Waiting to upload
+texture-memory = Texture memory
+failed-decodes = Failed decodes
+failed-to-load = { $count ->
+    [one] 1 queued image failed to load
+   *[other] { $count } queued images failed to load
+}
+skip-failures = Skip all failures
+skip-failures-hint = Take the images that couldn't be decoded out of the queue. The files stay where they are
+skipped-failures = { $count ->
+    [one] Took 1 image that failed to load out of the queue
+   *[other] Took { $count } images that failed to load out of the queue
+}
+history = History
+nothing-sorted = Nothing sorted yet
+revert = Revert
+revert-hint = Undo just this move
+images-left = { $count } left
+scanning = scanning…
+loading-paused = Loading paused
+resume-loading = ▶ Resume loading
+pause-loading = ⏸ Pause loading
+pause-loading-hint = Stop decoding images in the background to save battery. Images already loaded stay
+new-category = New category
+add = Add
+cancel = Cancel
+then-a-letter = { $key } then a letter
+edit-categories = Edit categories
+dry-run-blocks = Commit or turn off dry run first
+save = Save
+category-files = { $category } ({ $count ->
+    [one] 1 file
+   *[other] { $count } files
+})
+rename = Rename
+remove = Remove
+remove-not-empty = Only empty categories can be removed here, and { $category } still has { $count ->
+    [one] 1 file
+   *[other] { $count } files
+}. Merge it into another one under Settings → Categories first.
+renaming = Renaming…
+new-category-key = A new category gets { $key }
+cannot-read-folder = Cannot read folder
+try-again = Try again
+move-all-remaining = Move all remaining
+move-all-confirm = Move { $count ->
+    [one] the 1 remaining image
+   *[other] all { $count } remaining images
+} to { $category }?
+move-all-undo = A single undo brings them all back.
+confirm-move-title = Move files?
+confirm-move-explain = Choosing a category moves the file on disk. This image goes into { $category } ({ $folder }), and so does every other image sorted the same way.
+confirm-move-undo = { $undo } puts a moved file back. The other modes and the dry run under Settings → Sorting leave files where they are.
+dont-show-again = Don't show this again
+confirm-first-move = Confirm the first move of a session
+move = Move
+file-exists = File already exists
+file-exists-in = { $category } already has a file called { $name }
+moving-file = Moving
+already-there = Already there
+more-waiting = { $count } more waiting
+apply-to-all = Apply to all remaining collisions
+move-to = Move to…
+chord-search-hint = Enter picks the first match, Escape cancels
+chord-hint = Press a letter, / to search, Escape to cancel
+palette-hint = Type to narrow, ↑ ↓ to choose, Enter to move, Escape to cancel
+keyboard-shortcuts = Keyboard shortcuts
+unused-category = { $label } (unused)
+help-close = Press ? or Escape to close
+
+## Settings
+
+settings = Settings
+settings-sorting = Sorting
+choosing-a-category = Choosing a category
+slideshow = Slideshow
+slideshow-hint = Move on to the next image if nothing happens for a while
+auto-pause = Auto-pause
+auto-pause-hint = Pause sorting after a while without any input
+stick-sort = Sort with stick flicks
+stick-sort-hint = Flicking a controller's left stick left, right, up or down files the image like the D-pad. The stick has to return to the middle before the next flick
+rename-on-move = Rename on move
+rename-tokens = Tokens: { $tokens }
+when-name-taken = When the name is taken
+date-folders = Date folders
+rate-with-numbers = Rate with number keys
+rate-with-numbers-hint = 1-5 sets a star rating in the XMP sidecar and moves on; 0 clears it
+dry-run = Dry run
+dry-run-hint = Sort as usual but leave the files alone until you commit
+settings-appearance = Appearance
+language = Language
+language-system = Follow system
+theme = Theme
+background-color = Background color
+image-fit = Image fit
+checkerboard = Checkerboard behind transparent images
+large-text = Large text
+large-text-hint = Bigger text, labels and buttons everywhere
+settings-loading = Loading
+preload-ahead = Preload ahead
+keep-behind = Keep behind
+preview-size = Preview size
+preview-size-hint = Longest side images are scaled down to for display. Bigger is sharper but slower and uses more memory
+resize-filter = Resize filter
+resize-nearest = Nearest (fastest)
+resize-triangle = Triangle
+resize-lanczos3 = Lanczos3 (sharpest)
+texture-memory-usage = Texture memory: { $size } for { $count } images and their thumbnails
+reload-visible = Reload visible images
+reload-visible-hint = Decode the images around the current one again with the settings above; otherwise they apply to images loaded from now on
+settings-key-bindings = Key bindings
+press-a-key = Press a key or mouse button…
+settings-animation = Animation
+reduce-motion = Reduce motion
+reduce-motion-hint = Skip the fly-to-bucket animation entirely
+duration = Duration
+crossfade = Crossfade between images
+easing = Easing
+settings-sound = Sound
+sound-enabled = Play sounds
+volume = Volume
+sound-sort = Sort
+sound-undo = Undo
+sound-error = Failed move
+sound-done = Queue finished
+sound-built-in = Built-in
+sound-file-hint = Path to a WAV or Ogg Vorbis file to play instead; leave empty for the built-in sound
+sound-test = Play
+category-keys = Categories:
+arrows = Arrows
+reset-all = Reset all
+settings-categories = Categories
+merge-into = Merge into…
+remove-category-hint = Move its files back to the queue and delete the folder
+settings-this-folder = This folder
+kept-in-place = { $count ->
+    [one] 1 image kept in place
+   *[other] { $count } images kept in place
+}
+reset-kept = Reset kept
+image-extensions = Image extensions
+image-extensions-hint = Used the next time the folder is scanned
+detect-by-content = Detect images by content
+detect-by-content-hint = Also check the first bytes of files with other or no extensions
+max-megapixels = Largest image to decode
+max-megapixels-hint = Bigger images are skipped, with a button to decode them anyway
+big-folder-images = Ask before sorting more images than
+big-file-size = Ask before decoding an image bigger than
+big-folder-hint = Guards against scanning a huge folder, such as your home folder, by accident
+low-space-margin = Warn when a destination drive would have less free than
+low-space-margin-hint = Checked for category folders on another drive, when sorting starts and before moving a big file there
+big-folder-title = This is a big folder
+big-folder-summary = { $path } has { $count } images, { $size } in all. Continue?
+big-folder-counting = Still counting…
+big-folder-largest = The largest, { $name }, is { $size }.
+continue = Continue
+narrow-with-filters = Narrow with filters
+narrow-with-filters-hint = Continue, leaving out files above the size limit; the filter bar can narrow it further
+occupied-title = Category folders aren't empty
+occupied-summary = These category folders already hold files, maybe from an earlier session. They would show up on the piles alongside what you sort now.
+occupied-folder = { $name } — { $count ->
+    [one] 1 item
+   *[other] { $count } items
+}
+occupied-merge = Continue
+occupied-merge-hint = Keep the files and sort in alongside them
+occupied-rename = Choose other names
+occupied-empty = Empty them first
+occupied-empty-hint = Move everything in these folders to the trash, then start
+emptying-folders = Moving to the trash…
+empty-folders-failed = Couldn't empty the category folders: { $error }
+settings-cache = Thumbnail cache
+cache-size-limit = Size limit
+cache-usage = Using { $size }
+clear-cache = Clear cache
+no-cache = No cache directory available on this system
+
+## Top bar
+
+menu-file = File
+settings-button = ⚙ Settings
+pause-button = 🔒 Pause
+resume-button = 🔓 Resume
+pause-hint = Ignore sorting input until resumed ({ $key })
+overview-button = ▦ Overview
+categories-button = ✏ Categories
+filter-hint = Filter, e.g. IMG_*
+filter-tooltip = Only sort files whose name contains this, or matches it as a glob
+filter-button = 🔍 Filter
+filter-matching = { $count } of { $total } match filter
+filter-modified = Modified
+filter-from = from
+filter-to = to
+filter-date-hint = A day as YYYY-MM-DD; either end can be left empty
+filter-size = Size
+filter-min = min
+filter-max = max
+filter-clear = Clear
+
+## Setup
+
+setup-title = Setup Categories
+setup-heading = Enter Categories
+setup-hint = Separate with commas (1-{ $max } categories)
+setup-count = { $count } of { $max } categories
+start = Start
+too-many-categories = { $count } categories given, but at most { $max } are supported
+shortcuts = Shortcuts
+found-images = Found { $count ->
+    [one] 1 image
+   *[other] { $count } images
+}…
@@ -0,0 +1,161 @@
+use crate::i18n::t;
+use eframe::egui::{self, accesskit};
+
+// Text and hit targets grow by this much in large-text mode
+pub const LARGE_TEXT_SCALE: f32 = 1.5;
+
+// Scale every text style and the minimum size of clickable things from egui's
+// defaults, so turning the mode off again restores them exactly
+pub fn apply_text_size(ctx: &egui::Context, large: bool) {
+    let scale = if large { LARGE_TEXT_SCALE } else { 1.0 };
+    let defaults = egui::Style::default();
+    ctx.style_mut(|style| {
+        for (text_style, font) in style.text_styles.iter_mut() {
+            if let Some(default) = defaults.text_styles.get(text_style) {
+                font.size = default.size * scale;
+            }
+        }
+        style.spacing.interact_size = defaults.spacing.interact_size * scale;
+        style.spacing.button_padding = defaults.spacing.button_padding * scale;
+        style.spacing.icon_width = defaults.spacing.icon_width * scale;
+        style.spacing.icon_width_inner = defaults.spacing.icon_width_inner * scale;
+    });
+}
+
+// How much larger than the default text is currently set, for text drawn
+// straight with the painter
+pub fn text_scale(style: &egui::Style) -> f32 {
+    let body = |style: &egui::Style| {
+        style
+            .text_styles
+            .get(&egui::TextStyle::Body)
+            .map_or(1.0, |font| font.size)
+    };
+    body(style) / body(&egui::Style::default())
+}
+
+// Screen reader name for the image being sorted
+pub fn image_name(name: &str, position: usize, total: usize) -> String {
+    t!(
+        "image-name",
+        name = name,
+        position = position,
+        total = total
+    )
+}
+
+// The painter-drawn image has no widget, so give its response a node of its own
+pub fn describe_image(response: &egui::Response, name: String) {
+    response.ctx.accesskit_node_builder(response.id, |node| {
+        node.set_role(accesskit::Role::Image);
+        node.set_name(name);
+    });
+}
+
+// A painted area that screen readers and switch access treat as a button. It
+// can be focused and is pressed by a click, Space or Enter while focused, or
+// the assistive technology's default action
+pub fn button(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    id: egui::Id,
+    name: &str,
+    enabled: bool,
+) -> egui::Response {
+    let sense = if enabled {
+        egui::Sense::click()
+    } else {
+        egui::Sense::hover()
+    };
+    let response = ui.interact(rect, id, sense);
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, name));
+    if response.has_focus() {
+        ui.painter()
+            .rect_stroke(rect.expand(3.0), 6.0, ui.visuals().selection.stroke);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+    use crate::theme::{Palette, Theme};
+    use std::collections::HashMap;
+
+    #[test]
+    fn sorting_surface_has_names_and_roles() {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let palette = Palette::new(Theme::Dark, egui::Color32::BLACK);
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                board::card(
+                    ui,
+                    egui::Rect::from_min_size(egui::pos2(100.0, 100.0), egui::vec2(200.0, 150.0)),
+                    egui::TextureId::default(),
+                    egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                    image_name("IMG_2011.jpg", 5, 120),
+                    true,
+                );
+                for (i, name) in ["keep, 3 files, 2.1 MB", "Kept, 0 files"]
+                    .into_iter()
+                    .enumerate()
+                {
+                    board::bucket(
+                        ui,
+                        egui::Rect::from_min_size(
+                            egui::pos2(10.0 + i as f32 * 120.0, 300.0),
+                            egui::vec2(100.0, 150.0),
+                        ),
+                        egui::Id::new(("bucket", i)),
+                        name,
+                        true,
+                        &palette,
+                        board::BucketLook {
+                            color: egui::Color32::RED,
+                            flash: 0.0,
+                        },
+                    );
+                }
+            });
+        });
+
+        // Walk the tree from its root, so only nodes a screen reader can
+        // reach count
+        let update = output
+            .platform_output
+            .accesskit_update
+            .expect("AccessKit is enabled");
+        let nodes: HashMap<_, _> = update.nodes.iter().map(|(id, node)| (*id, node)).collect();
+        let mut reachable = Vec::new();
+        let mut pending = vec![update.tree.expect("a full tree on the first frame").root];
+        while let Some(id) = pending.pop() {
+            let node = nodes[&id];
+            reachable.push((node.name(), node.role()));
+            pending.extend(node.children());
+        }
+        for expected in [
+            (Some("Image IMG_2011.jpg, 5 of 120"), accesskit::Role::Image),
+            (Some("keep, 3 files, 2.1 MB"), accesskit::Role::Button),
+            (Some("Kept, 0 files"), accesskit::Role::Button),
+        ] {
+            assert!(
+                reachable.contains(&expected),
+                "{:?} not in {:?}",
+                expected,
+                reachable
+            );
+        }
+    }
+
+    #[test]
+    fn large_text_scales_from_defaults() {
+        let ctx = egui::Context::default();
+        apply_text_size(&ctx, true);
+        assert_eq!(text_scale(&ctx.style()), LARGE_TEXT_SCALE);
+        apply_text_size(&ctx, false);
+        assert_eq!(text_scale(&ctx.style()), 1.0);
+    }
+}
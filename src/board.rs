@@ -0,0 +1,340 @@
+use crate::{accessibility, theme};
+use eframe::egui::{
+    self, epaint::text::Fonts, Align2, Color32, ColorImage, FontId, Galley, Pos2, Rect, Stroke,
+    Vec2,
+};
+use std::sync::Arc;
+
+// Where the sorting board gets drawn: the window through egui, or an image
+// on the CPU when the board is exported. Rects and fonts are in points
+pub trait Canvas {
+    type Image;
+
+    fn fill_rect(&mut self, rect: Rect, rounding: f32, color: Color32);
+    fn stroke_rect(&mut self, rect: Rect, rounding: f32, stroke: Stroke);
+    fn layout(&mut self, text: String, font: FontId, color: Color32) -> Arc<Galley>;
+    fn draw_galley(&mut self, pos: Pos2, galley: Arc<Galley>);
+    fn draw_image(&mut self, rect: Rect, image: &Self::Image);
+
+    // Returns where the text went
+    fn text(
+        &mut self,
+        pos: Pos2,
+        anchor: Align2,
+        text: String,
+        font: FontId,
+        color: Color32,
+    ) -> Rect {
+        let galley = self.layout(text, font, color);
+        let rect = anchor.anchor_rect(Rect::from_min_size(pos, galley.size()));
+        self.draw_galley(rect.min, galley);
+        rect
+    }
+}
+
+impl Canvas for egui::Painter {
+    type Image = egui::TextureId;
+
+    fn fill_rect(&mut self, rect: Rect, rounding: f32, color: Color32) {
+        self.rect_filled(rect, rounding, color);
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, rounding: f32, stroke: Stroke) {
+        self.rect_stroke(rect, rounding, stroke);
+    }
+
+    fn layout(&mut self, text: String, font: FontId, color: Color32) -> Arc<Galley> {
+        self.layout_no_wrap(text, font, color)
+    }
+
+    fn draw_galley(&mut self, pos: Pos2, galley: Arc<Galley>) {
+        self.galley(pos, galley);
+    }
+
+    fn draw_image(&mut self, rect: Rect, image: &egui::TextureId) {
+        let uv = Rect::from_min_max(Pos2::ZERO, egui::pos2(1.0, 1.0));
+        self.image(*image, rect, uv, Color32::WHITE);
+    }
+}
+
+// A bucket's background, tinted with its category color, and its border,
+// thickened by `flash` (1 as a card lands, fading to 0)
+pub fn bucket_frame(
+    canvas: &mut impl Canvas,
+    rect: Rect,
+    fill: Color32,
+    color: Color32,
+    flash: f32,
+) {
+    canvas.fill_rect(rect, 5.0, fill);
+    canvas.fill_rect(rect, 5.0, color.gamma_multiply(0.3));
+    canvas.stroke_rect(
+        rect.expand(flash * 2.0),
+        5.0,
+        Stroke::new(1.5 + flash * 3.0, color),
+    );
+}
+
+// The card being sorted, drawn over `rect` and named for screen readers.
+// Dragging it sorts it, unless `draggable` is off while sorting is paused
+pub fn card(
+    ui: &mut egui::Ui,
+    rect: Rect,
+    texture: egui::TextureId,
+    uv: Rect,
+    tint: Color32,
+    name: String,
+    draggable: bool,
+) -> egui::Response {
+    ui.painter().image(texture, rect, uv, tint);
+    let sense = if draggable {
+        egui::Sense::drag()
+    } else {
+        egui::Sense::hover()
+    };
+    let response = ui.interact(rect, egui::Id::new("current_image"), sense);
+    accessibility::describe_image(&response, name);
+    response
+}
+
+// A bucket's category color, and how far its border is thickened by a card
+// landing (1 as it lands, fading to 0)
+pub struct BucketLook {
+    pub color: Color32,
+    pub flash: f32,
+}
+
+// A bucket on the sorting surface, with its frame. Pressing it files the
+// image there, for switch access and screen readers as much as the mouse
+pub fn bucket(
+    ui: &mut egui::Ui,
+    rect: Rect,
+    id: egui::Id,
+    name: &str,
+    enabled: bool,
+    palette: &theme::Palette,
+    look: BucketLook,
+) -> egui::Response {
+    let response = accessibility::button(ui, rect, id, name, enabled);
+    bucket_frame(
+        &mut ui.painter().clone(),
+        rect,
+        palette.bucket_fill,
+        look.color,
+        look.flash,
+    );
+    response
+}
+
+// The label near the bottom of a bucket, on a pill of its color
+pub fn bucket_label(
+    canvas: &mut impl Canvas,
+    rect: Rect,
+    text: String,
+    font: FontId,
+    color: Color32,
+) {
+    let galley = canvas.layout(text, font, theme::contrast_text(color));
+    let label_rect = Rect::from_center_size(
+        rect.center() + egui::vec2(0.0, rect.height() * 0.4),
+        galley.size() + egui::vec2(10.0, 4.0),
+    );
+    canvas.fill_rect(label_rect, 4.0, color);
+    canvas.draw_galley(label_rect.min + egui::vec2(5.0, 2.0), galley);
+}
+
+// A line of text segments, each in its own color, left to right from `pos`
+// with a gap between them
+pub fn text_line(
+    canvas: &mut impl Canvas,
+    pos: Pos2,
+    segments: &[(String, Color32)],
+    font: FontId,
+) {
+    let mut x = pos.x;
+    for (text, color) in segments {
+        let rect = canvas.text(
+            egui::pos2(x, pos.y),
+            Align2::LEFT_CENTER,
+            text.clone(),
+            font.clone(),
+            *color,
+        );
+        x = rect.right() + font.size;
+    }
+}
+
+// A canvas drawn on the CPU into an image, `scale` pixels to the point.
+// Text is laid out with egui's own fonts and copied from their atlas
+pub struct Raster {
+    image: ColorImage,
+    scale: f32,
+    fonts: Fonts,
+}
+
+impl Raster {
+    pub fn new(size: Vec2, scale: f32, background: Color32) -> Self {
+        let pixels = [
+            (size.x * scale).round().max(1.0) as usize,
+            (size.y * scale).round().max(1.0) as usize,
+        ];
+        Self {
+            image: ColorImage::new(pixels, background),
+            scale,
+            fonts: Fonts::new(scale, 8192, egui::FontDefinitions::default()),
+        }
+    }
+
+    pub fn finish(self) -> ColorImage {
+        self.image
+    }
+
+    // Pixels whose centers fall in `rect`, as column and row ranges
+    fn pixels(&self, rect: Rect) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let [width, height] = self.image.size;
+        let range = |min: f32, max: f32, len: usize| {
+            let start = (min * self.scale - 0.5).ceil().clamp(0.0, len as f32) as usize;
+            let end = (max * self.scale - 0.5)
+                .floor()
+                .clamp(-1.0, len as f32 - 1.0)
+                + 1.0;
+            start..(end as usize).max(start)
+        };
+        (
+            range(rect.min.x, rect.max.x, width),
+            range(rect.min.y, rect.max.y, height),
+        )
+    }
+
+    // Blend a premultiplied `color` over the pixel, `coverage` of it
+    fn blend(&mut self, x: usize, y: usize, color: Color32, coverage: f32) {
+        if coverage <= 0.0 {
+            return;
+        }
+        let pixel = &mut self.image.pixels[y * self.image.size[0] + x];
+        let src = color.to_array().map(|c| c as f32 * coverage.min(1.0));
+        let keep = 1.0 - src[3] / 255.0;
+        let dst = pixel.to_array();
+        let mix = |i: usize| (src[i] + dst[i] as f32 * keep).round().min(255.0) as u8;
+        *pixel = Color32::from_rgba_premultiplied(mix(0), mix(1), mix(2), mix(3));
+    }
+
+    // Paint `color` over `rect`, each pixel by how much of it `coverage`,
+    // given the pixel's center in points, says is inside
+    fn shade(&mut self, rect: Rect, color: Color32, coverage: impl Fn(Pos2) -> f32) {
+        let (columns, rows) = self.pixels(rect.expand(1.0));
+        for y in rows {
+            for x in columns.clone() {
+                let center = egui::pos2(x as f32 + 0.5, y as f32 + 0.5) / self.scale;
+                self.blend(x, y, color, coverage(center));
+            }
+        }
+    }
+}
+
+// Signed distance from `point` to the edge of `rect` with round corners,
+// negative inside
+fn rounded_distance(rect: Rect, rounding: f32, point: Pos2) -> f32 {
+    let rounding = rounding.min(rect.width().min(rect.height()) / 2.0);
+    let q = (point - rect.center()).abs() - (rect.size() / 2.0 - Vec2::splat(rounding));
+    q.max(Vec2::ZERO).length() + q.x.max(q.y).min(0.0) - rounding
+}
+
+impl Canvas for Raster {
+    type Image = ColorImage;
+
+    fn fill_rect(&mut self, rect: Rect, rounding: f32, color: Color32) {
+        let scale = self.scale;
+        self.shade(rect, color, |point| {
+            0.5 - rounded_distance(rect, rounding, point) * scale
+        });
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, rounding: f32, stroke: Stroke) {
+        let scale = self.scale;
+        let half = stroke.width / 2.0;
+        self.shade(rect.expand(half), stroke.color, |point| {
+            (half - rounded_distance(rect, rounding, point).abs()) * scale + 0.5
+        });
+    }
+
+    fn layout(&mut self, text: String, font: FontId, color: Color32) -> Arc<Galley> {
+        self.fonts.layout_no_wrap(text, font, color)
+    }
+
+    fn draw_galley(&mut self, pos: Pos2, galley: Arc<Galley>) {
+        let atlas = self.fonts.image();
+        for row in &galley.rows {
+            let glyphs = &row.visuals.mesh.vertices[row.visuals.glyph_vertex_range.clone()];
+            // Each glyph is a quad: left top, right top, left bottom, right bottom
+            for quad in glyphs.chunks_exact(4) {
+                let rect = Rect::from_min_max(quad[0].pos, quad[3].pos).translate(pos.to_vec2());
+                let (uv_min, uv_max) = (quad[0].uv, quad[3].uv);
+                let color = quad[0].color;
+                let (columns, rows) = self.pixels(rect);
+                for y in rows {
+                    for x in columns.clone() {
+                        let point = egui::pos2(x as f32 + 0.5, y as f32 + 0.5) / self.scale;
+                        let t = (point - rect.min) / rect.size();
+                        let texel = uv_min + (uv_max - uv_min) * t;
+                        let tx = (texel.x as usize).min(atlas.size[0] - 1);
+                        let ty = (texel.y as usize).min(atlas.size[1] - 1);
+                        self.blend(x, y, color, atlas.pixels[ty * atlas.size[0] + tx]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_image(&mut self, rect: Rect, image: &ColorImage) {
+        let [width, height] = image.size;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let (columns, rows) = self.pixels(rect);
+        for y in rows {
+            for x in columns.clone() {
+                let point = egui::pos2(x as f32 + 0.5, y as f32 + 0.5) / self.scale;
+                let t = (point - rect.min) / rect.size();
+                let sx = ((t.x * width as f32) as usize).min(width - 1);
+                let sy = ((t.y * height as f32) as usize).min(height - 1);
+                self.blend(x, y, image.pixels[sy * width + sx], 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_frame, Canvas, Raster};
+    use eframe::egui::{self, Align2, Color32, FontId};
+
+    #[test]
+    fn raster_draws_buckets_and_text_at_its_scale() {
+        let background = Color32::from_rgb(10, 10, 10);
+        let mut raster = Raster::new(egui::vec2(100.0, 50.0), 2.0, background);
+        let bucket = egui::Rect::from_min_size(egui::pos2(10.0, 10.0), egui::vec2(30.0, 30.0));
+        bucket_frame(&mut raster, bucket, Color32::WHITE, Color32::RED, 0.0);
+        let text = raster.text(
+            egui::pos2(60.0, 25.0),
+            Align2::LEFT_CENTER,
+            "Hi".into(),
+            FontId::proportional(14.0),
+            Color32::WHITE,
+        );
+
+        let image = raster.finish();
+        assert_eq!(image.size, [200, 100]);
+        let at = |x: f32, y: f32| image.pixels[(y * 2.0) as usize * 200 + (x * 2.0) as usize];
+        // Inside the bucket, away from its border, and outside it
+        assert_ne!(at(25.0, 25.0), background);
+        assert_eq!(at(5.0, 5.0), background);
+        assert_eq!(at(bucket.left() + 0.2, 25.0).r(), 255);
+        // Some of the text's pixels are lit
+        let lit = (text.left() as usize..text.right() as usize)
+            .flat_map(|x| (text.top() as usize..text.bottom() as usize).map(move |y| (x, y)))
+            .filter(|(x, y)| at(*x as f32, *y as f32) != background)
+            .count();
+        assert!(lit > 0);
+    }
+}
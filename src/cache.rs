@@ -0,0 +1,268 @@
+use crate::i18n::t;
+use crate::notifications::Notifier;
+use image::DynamicImage;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+// On-disk cache of downscaled previews, keyed by source path, size and mtime so
+// an edited or replaced file never serves a stale preview
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    max_bytes: AtomicU64,
+    total_bytes: AtomicU64,
+    notifier: Notifier,
+}
+
+impl ThumbnailCache {
+    // What earlier runs left in the cache isn't counted until `index` runs
+    pub fn new(max_bytes: u64, notifier: Notifier) -> Option<Self> {
+        let dir = dirs::cache_dir()?.join("leftright").join("thumbnails");
+        Self::at(dir, max_bytes, notifier)
+    }
+
+    fn at(dir: PathBuf, max_bytes: u64, notifier: Notifier) -> Option<Self> {
+        std::fs::create_dir_all(&dir).ok()?;
+        Some(Self {
+            dir,
+            max_bytes: AtomicU64::new(max_bytes),
+            total_bytes: AtomicU64::new(0),
+            notifier,
+        })
+    }
+
+    // Count every entry on disk, and trim the cache if that's over its cap.
+    // It reads the whole folder, so it belongs on a background thread
+    pub fn index(&self) {
+        let total = self.entries().iter().map(|entry| entry.1).sum();
+        self.total_bytes.store(total, Ordering::Relaxed);
+        if total > self.max_bytes.load(Ordering::Relaxed) {
+            self.prune();
+        }
+    }
+
+    pub fn set_max_bytes(&self, max_bytes: u64) {
+        self.max_bytes.store(max_bytes, Ordering::Relaxed);
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    // `variant` tells apart previews of the same file made at different sizes
+    // or with different filters
+    fn key(path: &Path, variant: &str) -> Option<String> {
+        let path = path.canonicalize().ok()?;
+        let metadata = std::fs::metadata(&path).ok()?;
+        let mtime = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+
+        let source = format!(
+            "{}|{}|{}|{}",
+            path.display(),
+            metadata.len(),
+            mtime,
+            variant
+        );
+        Some(format!("{:016x}", stable_hash(source.as_bytes())))
+    }
+
+    // Returns None on a miss or an unreadable entry, in which case the caller
+    // decodes the original instead
+    pub fn load(&self, path: &Path, variant: &str) -> Option<DynamicImage> {
+        let entry = self.dir.join(Self::key(path, variant)?);
+        let bytes = std::fs::read(&entry).ok()?;
+
+        match image::load_from_memory(&bytes) {
+            Ok(image) => Some(image),
+            Err(e) => {
+                self.notifier.warn(t!(
+                    "cache-entry-corrupt",
+                    path = entry.display().to_string(),
+                    error = e.to_string()
+                ));
+                if std::fs::remove_file(&entry).is_ok() {
+                    self.total_bytes
+                        .fetch_sub(bytes.len() as u64, Ordering::Relaxed);
+                }
+                None
+            }
+        }
+    }
+
+    pub fn store(&self, path: &Path, variant: &str, image: &DynamicImage) {
+        let Some(key) = Self::key(path, variant) else {
+            return;
+        };
+
+        // JPEG keeps entries small; images with transparency need PNG
+        let mut bytes = Vec::new();
+        let result = if image.color().has_alpha() {
+            image.write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+        } else {
+            image.to_rgb8().write_to(
+                &mut Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Jpeg(85),
+            )
+        };
+        if let Err(e) = result {
+            self.notifier.warn(t!(
+                "cache-encode-failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            ));
+            return;
+        }
+
+        // Write to a temporary name first so readers never see a partial entry
+        let entry = self.dir.join(&key);
+        let partial = self.dir.join(format!("{}.partial", key));
+        let written =
+            std::fs::write(&partial, &bytes).and_then(|_| std::fs::rename(&partial, &entry));
+        if let Err(e) = written {
+            self.notifier.warn(t!(
+                "cache-write-failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            ));
+            let _ = std::fs::remove_file(&partial);
+            return;
+        }
+
+        let total = self
+            .total_bytes
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed)
+            + bytes.len() as u64;
+        if total > self.max_bytes.load(Ordering::Relaxed) {
+            self.prune();
+        }
+    }
+
+    fn entries(&self) -> Vec<(PathBuf, u64, std::time::SystemTime)> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect()
+    }
+
+    // Delete the oldest entries until the cache fits in its size cap
+    fn prune(&self) {
+        let mut entries = self.entries();
+        entries.sort_by_key(|entry| entry.2);
+
+        let max_bytes = self.max_bytes.load(Ordering::Relaxed);
+        let mut total: u64 = entries.iter().map(|entry| entry.1).sum();
+        for (path, size, _) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+        self.total_bytes.store(total, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for (path, _, _) in self.entries() {
+            let _ = std::fs::remove_file(path);
+        }
+        self.total_bytes.store(0, Ordering::Relaxed);
+    }
+}
+
+// FNV-1a, which unlike std's DefaultHasher stays the same across builds, so
+// cache keys and anything else derived from it survive an update
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThumbnailCache;
+    use crate::notifications::Notifications;
+    use crate::ScratchDir;
+    use eframe::egui;
+    use image::DynamicImage;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    fn cache(dir: &Path, max_bytes: u64) -> ThumbnailCache {
+        let notifier = Notifications::new(&egui::Context::default()).notifier();
+        ThumbnailCache::at(dir.join("cache"), max_bytes, notifier).unwrap()
+    }
+
+    // A source file, and where the cache keeps its preview
+    fn source(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+        let path = dir.join(name);
+        std::fs::write(&path, name).unwrap();
+        let entry = dir
+            .join("cache")
+            .join(ThumbnailCache::key(&path, "test").unwrap());
+        (path, entry)
+    }
+
+    #[test]
+    fn truncated_entry_is_a_miss_and_is_removed() {
+        let dir = ScratchDir::new("cache-truncated");
+        let cache = cache(&dir, u64::MAX);
+        let (path, entry) = source(&dir, "a.png");
+        cache.store(&path, "test", &DynamicImage::new_rgba8(16, 16));
+        assert!(cache.load(&path, "test").is_some());
+
+        let bytes = std::fs::read(&entry).unwrap();
+        std::fs::write(&entry, &bytes[..bytes.len() / 2]).unwrap();
+        cache.index();
+        assert_eq!(cache.total_bytes(), bytes.len() as u64 / 2);
+        assert!(cache.load(&path, "test").is_none());
+        assert!(!entry.exists());
+        assert_eq!(cache.total_bytes(), 0);
+    }
+
+    #[test]
+    fn oldest_entries_go_once_over_the_cap() {
+        let dir = ScratchDir::new("cache-evict");
+        let cache = cache(&dir, u64::MAX);
+        let image = DynamicImage::new_rgb8(16, 16);
+        let (old, old_entry) = source(&dir, "old.jpg");
+        let (new, new_entry) = source(&dir, "new.jpg");
+        cache.store(&old, "test", &image);
+        let size = cache.total_bytes();
+        std::fs::File::options()
+            .write(true)
+            .open(&old_entry)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+
+        // Room for one entry and a half
+        cache.set_max_bytes(size * 3 / 2);
+        cache.store(&new, "test", &image);
+        assert!(!old_entry.exists());
+        assert!(new_entry.exists());
+        assert_eq!(cache.total_bytes(), size);
+
+        // A later run counts what's left
+        let reopened = self::cache(&dir, u64::MAX);
+        assert_eq!(reopened.total_bytes(), 0);
+        reopened.index();
+        assert_eq!(reopened.total_bytes(), size);
+    }
+}
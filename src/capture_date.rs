@@ -0,0 +1,76 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use exif::{In, Tag, Value};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// How auto-sort by date names the folder for a day
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateLayout {
+    #[default]
+    Month,
+    Day,
+}
+
+impl DateLayout {
+    pub const ALL: [DateLayout; 2] = [DateLayout::Month, DateLayout::Day];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateLayout::Month => "YYYY-MM",
+            DateLayout::Day => "YYYY/MM/DD",
+        }
+    }
+
+    pub fn folder(self, date: NaiveDate) -> PathBuf {
+        match self {
+            DateLayout::Month => PathBuf::from(format!("{:04}-{:02}", date.year(), date.month())),
+            DateLayout::Day => PathBuf::from(format!("{:04}", date.year()))
+                .join(format!("{:02}", date.month()))
+                .join(format!("{:02}", date.day())),
+        }
+    }
+}
+
+// When the photo was taken, from EXIF if it has it, else the file's
+// modification date. The flag is true when EXIF was used
+pub fn capture_date(path: &Path) -> Option<(NaiveDate, bool)> {
+    capture_time(path).map(|(time, from_exif)| (time.date(), from_exif))
+}
+
+// Same as `capture_date`, down to the second
+pub fn capture_time(path: &Path) -> Option<(NaiveDateTime, bool)> {
+    if let Some(time) = exif_time(path) {
+        return Some((time, true));
+    }
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some((
+        chrono::DateTime::<chrono::Local>::from(modified).naive_local(),
+        false,
+    ))
+}
+
+fn exif_time(path: &Path) -> Option<NaiveDateTime> {
+    let file = std::fs::File::open(path).ok()?;
+    let exif = exif::Reader::new()
+        .read_from_container(&mut std::io::BufReader::new(file))
+        .ok()?;
+    [Tag::DateTimeOriginal, Tag::DateTimeDigitized, Tag::DateTime]
+        .into_iter()
+        .find_map(|tag| {
+            let field = exif.get_field(tag, In::PRIMARY)?;
+            let Value::Ascii(values) = &field.value else {
+                return None;
+            };
+            let datetime = exif::DateTime::from_ascii(values.first()?).ok()?;
+            NaiveDate::from_ymd_opt(
+                datetime.year.into(),
+                datetime.month.into(),
+                datetime.day.into(),
+            )?
+            .and_hms_opt(
+                datetime.hour.into(),
+                datetime.minute.into(),
+                datetime.second.into(),
+            )
+        })
+}
@@ -0,0 +1,75 @@
+// Category names become folder names, so they have to be valid as one
+
+use crate::i18n::t;
+
+// Not allowed in a file name on Windows; only the slash is a problem elsewhere
+const WINDOWS_FORBIDDEN: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const WINDOWS_RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Why `name` can't be used as a category folder on this OS, if it can't
+pub fn check(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err(t!("category-empty"));
+    }
+    if name == "." || name == ".." {
+        return Err(t!("category-dots", name = name));
+    }
+    if let Some(c) = name.chars().find(|c| c.is_control()) {
+        return Err(t!(
+            "category-control",
+            name = name,
+            character = format!("{:?}", c)
+        ));
+    }
+    if cfg!(windows) {
+        if let Some(c) = name.chars().find(|c| WINDOWS_FORBIDDEN.contains(c)) {
+            return Err(t!(
+                "category-forbidden",
+                name = name,
+                character = c.to_string()
+            ));
+        }
+        let stem = name.split('.').next().unwrap_or_default();
+        if WINDOWS_RESERVED
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            return Err(t!("category-reserved", name = name));
+        }
+        if name.ends_with('.') {
+            return Err(t!("category-trailing-dot", name = name));
+        }
+    } else if name.contains('/') {
+        return Err(t!("category-forbidden", name = name, character = "/"));
+    }
+    Ok(())
+}
+
+// Comma-separated names from the setup screen, trimmed and checked, with
+// duplicates refused (ignoring case, as many file systems do)
+pub fn parse(input: &str) -> Result<Vec<String>, String> {
+    let names: Vec<String> = input.split(',').map(|s| s.trim().to_string()).collect();
+    if names.iter().all(String::is_empty) {
+        return Err(t!("category-none"));
+    }
+    for (i, name) in names.iter().enumerate() {
+        if name.is_empty() {
+            return Err(t!("category-blank", number = i + 1));
+        }
+        check(name)?;
+        if let Some(earlier) = names[..i]
+            .iter()
+            .find(|earlier| earlier.to_lowercase() == name.to_lowercase())
+        {
+            return Err(t!(
+                "category-duplicate",
+                name = name.as_str(),
+                earlier = earlier.as_str()
+            ));
+        }
+    }
+    Ok(names)
+}
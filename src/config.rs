@@ -0,0 +1,401 @@
+use crate::capture_date::DateLayout;
+use crate::i18n::t;
+use crate::keybindings::KeyBindings;
+use crate::layout::BucketLayout;
+use crate::scan;
+use crate::sound::Cue;
+use crate::theme::Theme;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    #[default]
+    CubicOut,
+    Linear,
+    Spring,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 3] = [Easing::CubicOut, Easing::Linear, Easing::Spring];
+
+    pub fn label(self) -> String {
+        match self {
+            Easing::CubicOut => t!("easing-cubic-out"),
+            Easing::Linear => t!("easing-linear"),
+            Easing::Spring => t!("easing-spring"),
+        }
+    }
+
+    // Map linear progress in 0..=1 to eased progress
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::Linear => t,
+            Easing::Spring => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    // Damped oscillation that overshoots slightly before settling
+                    1.0 - (-6.0 * t).exp() * (12.0 * t).cos()
+                }
+            }
+        }
+    }
+}
+
+// How the current image is sized in the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFit {
+    // A card in the middle, leaving room for the buckets
+    #[default]
+    Card,
+    Fit,
+    // Cover the whole window, cropping what doesn't fit
+    Fill,
+    // One texture pixel per screen point, panned by dragging
+    Actual,
+}
+
+impl ImageFit {
+    pub const ALL: [ImageFit; 4] = [
+        ImageFit::Card,
+        ImageFit::Fit,
+        ImageFit::Fill,
+        ImageFit::Actual,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            ImageFit::Card => t!("fit-card"),
+            ImageFit::Fit => t!("fit-window"),
+            ImageFit::Fill => t!("fit-fill"),
+            ImageFit::Actual => t!("fit-actual"),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|fit| *fit == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+// How decoded images are scaled down to preview size, fastest first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizeFilter {
+    Nearest,
+    #[default]
+    Triangle,
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    pub const ALL: [ResizeFilter; 3] = [
+        ResizeFilter::Nearest,
+        ResizeFilter::Triangle,
+        ResizeFilter::Lanczos3,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            ResizeFilter::Nearest => t!("resize-nearest"),
+            ResizeFilter::Triangle => t!("resize-triangle"),
+            ResizeFilter::Lanczos3 => t!("resize-lanczos3"),
+        }
+    }
+
+    pub fn filter_type(self) -> image::imageops::FilterType {
+        match self {
+            ResizeFilter::Nearest => image::imageops::FilterType::Nearest,
+            ResizeFilter::Triangle => image::imageops::FilterType::Triangle,
+            ResizeFilter::Lanczos3 => image::imageops::FilterType::Lanczos3,
+        }
+    }
+}
+
+// What choosing a category does to the file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+    #[default]
+    Move,
+    // Leave the file alone and record the category in an XMP sidecar
+    Tag,
+    // Leave the file alone and link to it from the category folder
+    Symlink,
+    Hardlink,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [
+        SortMode::Move,
+        SortMode::Tag,
+        SortMode::Symlink,
+        SortMode::Hardlink,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            SortMode::Move => t!("sort-mode-move"),
+            SortMode::Tag => t!("sort-mode-tag"),
+            SortMode::Symlink => t!("sort-mode-symlink"),
+            SortMode::Hardlink => t!("sort-mode-hardlink"),
+        }
+    }
+
+    // Whether sorted files end up in a folder per category
+    pub fn uses_folders(self) -> bool {
+        self != SortMode::Tag
+    }
+
+    pub fn is_link(self) -> bool {
+        matches!(self, SortMode::Symlink | SortMode::Hardlink)
+    }
+}
+
+// What to do when a sorted file's name is already taken in the category folder
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionPolicy {
+    #[default]
+    Rename,
+    Overwrite,
+    Skip,
+    Ask,
+}
+
+impl CollisionPolicy {
+    pub const ALL: [CollisionPolicy; 4] = [
+        CollisionPolicy::Rename,
+        CollisionPolicy::Overwrite,
+        CollisionPolicy::Skip,
+        CollisionPolicy::Ask,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            CollisionPolicy::Rename => t!("collision-rename"),
+            CollisionPolicy::Overwrite => t!("collision-overwrite"),
+            CollisionPolicy::Skip => t!("collision-skip"),
+            CollisionPolicy::Ask => t!("collision-ask"),
+        }
+    }
+}
+
+// The image sorting was on when the app was last closed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LastPosition {
+    pub dir: PathBuf,
+    pub image: PathBuf,
+}
+
+// The window as it was when the app was last closed, in points. Size and
+// position are from before it was maximized or made fullscreen
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub size: [f32; 2],
+    pub position: Option<[f32; 2]>,
+    // Size of the monitor it was on, to tell when that one is gone
+    pub monitor: Option<[f32; 2]>,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+impl WindowGeometry {
+    // Where to put the restored window once it's known to be on a monitor
+    // of size `monitor`, if not where it was. A window from a monitor that's
+    // no longer there is centered on the primary one, shrunk to fit
+    pub fn misplaced_on(&self, monitor: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+        let size = [self.size[0].min(monitor[0]), self.size[1].min(monitor[1])];
+        if self.monitor == Some(monitor) && size == self.size {
+            return None;
+        }
+        let position = [(monitor[0] - size[0]) / 2.0, (monitor[1] - size[1]) / 2.0];
+        Some((position, size))
+    }
+}
+
+// User settings, persisted as JSON in the platform config directory
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub animation_duration: f32,
+    pub easing: Easing,
+    // Seconds the previous image takes to fade into the next; 0 to cut
+    pub crossfade_duration: f32,
+    pub reduce_motion: bool,
+    pub cache_max_mb: u64,
+    // Bigger images aren't decoded unless asked to, so one can't exhaust memory
+    pub max_megapixels: u32,
+    // A folder with more images than this, or any image bigger than this,
+    // is only decoded after asking
+    pub big_folder_images: usize,
+    pub big_file_mb: u64,
+    // Warn when a destination drive would have less than this left
+    pub low_space_margin_mb: u64,
+    pub preload_ahead: usize,
+    pub keep_behind: usize,
+    // Longest side, in logical pixels, that decoded images are scaled down to
+    pub preview_size: u32,
+    pub resize_filter: ResizeFilter,
+    // RGB color per category name
+    pub category_colors: HashMap<String, [u8; 3]>,
+    pub key_bindings: KeyBindings,
+    pub theme: Theme,
+    // RGB background used by Theme::Custom
+    pub background_color: [u8; 3],
+    pub checkerboard: bool,
+    // Bigger text and click targets throughout
+    pub large_text: bool,
+    pub image_fit: ImageFit,
+    // File extensions treated as images, lowercase without the dot
+    pub image_extensions: Vec<String>,
+    // Also queue files whose extension doesn't match but whose contents are an image
+    pub detect_by_content: bool,
+    // Where the category buckets sit around the image
+    pub bucket_layout: BucketLayout,
+    pub sort_mode: SortMode,
+    // Note disk changes down instead of making them, until committed
+    pub dry_run: bool,
+    // Explain that sorting moves files before the first move of each session
+    pub confirm_first_move: bool,
+    // Number keys set a star rating in the XMP sidecar instead of a batch count
+    pub rate_with_numbers: bool,
+    // File name pattern for sorted images, e.g. "{date}_{original}"
+    pub rename_template: Option<String>,
+    pub collision_policy: CollisionPolicy,
+    // Folder naming for auto-sort by date
+    pub date_layout: DateLayout,
+    // Seconds before moving on to the next image by itself; None to wait
+    pub slideshow_interval: Option<f32>,
+    // Minutes without input before sorting pauses by itself; None to never
+    pub auto_pause_minutes: Option<f32>,
+    // Flicking a controller's left stick sorts like the D-pad
+    pub gamepad_stick_sort: bool,
+    // Audio cues for sorting, undo, failures and finishing, at 0 to 1 volume
+    pub sound: bool,
+    pub sound_volume: f32,
+    // Files played instead of the built-in sounds
+    pub sound_files: HashMap<Cue, PathBuf>,
+    // List undone moves in the session report, flagged, instead of leaving them out
+    pub report_undone: bool,
+    // Catalog code for the UI language, e.g. "de"; None follows the system
+    pub language: Option<String>,
+    // Where to pick up when the same folder is opened again
+    pub last_position: Option<LastPosition>,
+    pub window: Option<WindowGeometry>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            animation_duration: 0.5,
+            easing: Easing::CubicOut,
+            crossfade_duration: 0.15,
+            reduce_motion: false,
+            cache_max_mb: 1024,
+            max_megapixels: 200,
+            big_folder_images: 5000,
+            big_file_mb: 500,
+            low_space_margin_mb: 1024,
+            preload_ahead: 5,
+            keep_behind: 2,
+            preview_size: 1200,
+            resize_filter: ResizeFilter::Triangle,
+            category_colors: HashMap::new(),
+            key_bindings: KeyBindings::default(),
+            theme: Theme::System,
+            background_color: [128, 128, 128],
+            checkerboard: true,
+            large_text: false,
+            image_fit: ImageFit::Card,
+            image_extensions: scan::default_extensions(),
+            detect_by_content: false,
+            bucket_layout: BucketLayout::Cardinal,
+            sort_mode: SortMode::Move,
+            dry_run: false,
+            confirm_first_move: true,
+            rate_with_numbers: false,
+            rename_template: None,
+            collision_policy: CollisionPolicy::Rename,
+            date_layout: DateLayout::Month,
+            slideshow_interval: None,
+            auto_pause_minutes: None,
+            gamepad_stick_sort: false,
+            sound: false,
+            sound_volume: 0.5,
+            sound_files: HashMap::new(),
+            report_undone: false,
+            language: None,
+            last_position: None,
+            window: None,
+        }
+    }
+}
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("leftright").join("config.json"))
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid config file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowGeometry;
+
+    fn geometry(monitor: [f32; 2]) -> WindowGeometry {
+        WindowGeometry {
+            size: [1600.0, 1000.0],
+            position: Some([2200.0, 100.0]),
+            monitor: Some(monitor),
+            maximized: false,
+            fullscreen: false,
+        }
+    }
+
+    #[test]
+    fn window_stays_put_on_the_same_monitor() {
+        assert_eq!(
+            geometry([2560.0, 1440.0]).misplaced_on([2560.0, 1440.0]),
+            None
+        );
+    }
+
+    #[test]
+    fn window_from_a_missing_monitor_is_centered_and_fitted() {
+        assert_eq!(
+            geometry([2560.0, 1440.0]).misplaced_on([1280.0, 800.0]),
+            Some(([0.0, 0.0], [1280.0, 800.0]))
+        );
+        assert_eq!(
+            geometry([2560.0, 1440.0]).misplaced_on([1920.0, 1080.0]),
+            Some(([160.0, 40.0], [1600.0, 1000.0]))
+        );
+    }
+}
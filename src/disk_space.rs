@@ -0,0 +1,152 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+// How long a free space figure is trusted before it's looked up again
+const FRESH_FOR: Duration = Duration::from_secs(10);
+
+// Bytes free to this user on the filesystem holding `path`
+#[cfg(unix)]
+pub fn available(path: &Path) -> io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stats` is a valid out pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+// Bytes free to this user on the filesystem holding `path`
+#[cfg(windows)]
+pub fn available(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut free: winapi::shared::ntdef::ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    // SAFETY: `wide` is NUL-terminated and the out pointers are valid or null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { *free.QuadPart() })
+}
+
+// Whether `a` and `b` are on the same filesystem, so a move between them is a
+// rename that needs no space. Unknown counts as different
+pub fn same_filesystem(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev(),
+            _ => false,
+        }
+    }
+    #[cfg(windows)]
+    {
+        // The drive letter or share, which is as much as std tells apart
+        let prefix = |path: &Path| {
+            std::fs::canonicalize(path)
+                .ok()
+                .and_then(|path| path.components().next().map(|c| c.as_os_str().to_owned()))
+        };
+        prefix(a).is_some_and(|prefix_a| Some(prefix_a) == prefix(b))
+    }
+}
+
+// Whether an operation failed because its destination filled up
+pub fn is_disk_full(error: &io::Error) -> bool {
+    // ENOSPC, or ERROR_DISK_FULL and ERROR_HANDLE_DISK_FULL on Windows
+    error.kind() == io::ErrorKind::StorageFull
+        || (cfg!(windows) && matches!(error.raw_os_error(), Some(112) | Some(39)))
+}
+
+// Free space of destination folders. Lookups run in the background and the
+// answer is kept briefly, so asking before every move costs next to nothing
+pub struct DiskSpace {
+    known: HashMap<PathBuf, (Instant, u64)>,
+    running: HashSet<PathBuf>,
+    tx: Sender<(PathBuf, io::Result<u64>)>,
+    rx: Receiver<(PathBuf, io::Result<u64>)>,
+}
+
+impl Default for DiskSpace {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        Self {
+            known: HashMap::new(),
+            running: HashSet::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+impl DiskSpace {
+    // The last figure for `dir`, if it's recent
+    pub fn get(&self, dir: &Path) -> Option<u64> {
+        self.known
+            .get(dir)
+            .filter(|(when, _)| when.elapsed() < FRESH_FOR)
+            .map(|(_, free)| *free)
+    }
+
+    // Look up `dir` in the background unless that happened recently or is
+    // already under way. The answer comes from `poll`
+    pub fn request(&mut self, dir: &Path, runtime: &Runtime, ctx: &egui::Context) {
+        if self.get(dir).is_some() || !self.running.insert(dir.to_path_buf()) {
+            return;
+        }
+        let dir = dir.to_path_buf();
+        let tx = self.tx.clone();
+        let ctx = ctx.clone();
+        runtime.spawn_blocking(move || {
+            let free = available(&dir);
+            let _ = tx.send((dir, free));
+            ctx.request_repaint();
+        });
+    }
+
+    // Take in the figures looked up since the last call
+    pub fn poll(&mut self) {
+        for (dir, free) in self.rx.try_iter() {
+            self.running.remove(&dir);
+            match free {
+                Ok(free) => {
+                    self.known.insert(dir, (Instant::now(), free));
+                }
+                Err(e) => log::warn!("Free space of {} unknown: {}", dir.display(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{available, is_disk_full, same_filesystem};
+    use std::io;
+
+    #[test]
+    fn temp_dir_has_some_space_and_is_on_its_own_filesystem() {
+        let dir = std::env::temp_dir();
+        assert!(available(&dir).unwrap() > 0);
+        assert!(same_filesystem(&dir, &dir));
+        assert!(!same_filesystem(&dir, &dir.join("no-such-folder")));
+        assert!(is_disk_full(&io::Error::from(io::ErrorKind::StorageFull)));
+        assert!(!is_disk_full(&io::Error::from(io::ErrorKind::NotFound)));
+    }
+}
@@ -0,0 +1,236 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::runtime::Runtime;
+
+// How long a look at a file is trusted before it's looked at again
+const FRESH_FOR: Duration = Duration::from_secs(2);
+
+// A file as last seen on disk
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileState {
+    Missing,
+    // With its modification time, where the platform has one
+    Present(Option<SystemTime>),
+}
+
+impl FileState {
+    pub fn of(path: &Path) -> Self {
+        match std::fs::metadata(path) {
+            Ok(metadata) => Self::Present(metadata.modified().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::Missing,
+            // Unreadable isn't gone; decoding it reports the error
+            Err(_) => Self::Present(None),
+        }
+    }
+}
+
+// What changed about a queued file since it was decoded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    // Deleted or renamed by something else
+    Gone,
+    // Written over in place, so the preview on screen is stale
+    Replaced,
+}
+
+// Whether queued files are still there and still what was decoded. Files
+// are looked at in the background and the answer is kept briefly, so
+// checking the current image every frame costs next to nothing. A folder
+// watcher makes answers stale sooner; without one they age out
+pub struct FileChecks {
+    seen: HashMap<PathBuf, (Instant, FileState)>,
+    // Modification time each file had when it was decoded
+    decoded: HashMap<PathBuf, SystemTime>,
+    running: HashSet<PathBuf>,
+    tx: Sender<(PathBuf, FileState)>,
+    rx: Receiver<(PathBuf, FileState)>,
+}
+
+impl Default for FileChecks {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        Self {
+            seen: HashMap::new(),
+            decoded: HashMap::new(),
+            running: HashSet::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+impl FileChecks {
+    // Note what `path` looked like when it was decoded
+    pub fn decoded(&mut self, path: &Path, modified: Option<SystemTime>) {
+        match modified {
+            Some(modified) => self.decoded.insert(path.to_path_buf(), modified),
+            None => self.decoded.remove(path),
+        };
+    }
+
+    // Drop what's known about `path`, e.g. once the app moved it itself
+    pub fn forget(&mut self, path: &Path) {
+        self.seen.remove(path);
+        self.decoded.remove(path);
+    }
+
+    // Something in `dir` changed, so files there are looked at again
+    pub fn invalidate_dir(&mut self, dir: &Path) {
+        self.seen.retain(|path, _| path.parent() != Some(dir));
+    }
+
+    fn fresh(&self, path: &Path) -> Option<FileState> {
+        self.seen
+            .get(path)
+            .filter(|(when, _)| when.elapsed() < FRESH_FOR)
+            .map(|(_, state)| *state)
+    }
+
+    // Look at `path` in the background, unless that happened recently or is
+    // already under way. The answer comes from `poll`
+    pub fn request(&mut self, path: &Path, runtime: &Runtime, ctx: &egui::Context) {
+        if self.fresh(path).is_some() || !self.running.insert(path.to_path_buf()) {
+            return;
+        }
+        let path = path.to_path_buf();
+        let tx = self.tx.clone();
+        let ctx = ctx.clone();
+        runtime.spawn_blocking(move || {
+            let state = FileState::of(&path);
+            let _ = tx.send((path, state));
+            ctx.request_repaint();
+        });
+    }
+
+    // What a recent look at `path` found. None means there's been none, and
+    // the answer to a `request` has to be waited for
+    pub fn recent(&self, path: &Path) -> Option<FileState> {
+        self.fresh(path)
+    }
+
+    // Changes found in the background since the last call
+    pub fn poll(&mut self) -> Vec<(PathBuf, Change)> {
+        let results: Vec<_> = self.rx.try_iter().collect();
+        results
+            .into_iter()
+            .filter_map(|(path, state)| {
+                self.running.remove(&path);
+                let change = self.judge(path.clone(), state)?;
+                Some((path, change))
+            })
+            .collect()
+    }
+
+    fn judge(&mut self, path: PathBuf, state: FileState) -> Option<Change> {
+        let change = match state {
+            FileState::Missing => Some(Change::Gone),
+            FileState::Present(Some(modified))
+                if self
+                    .decoded
+                    .get(&path)
+                    .is_some_and(|when| *when != modified) =>
+            {
+                // Reported once; the next decode records the new time
+                self.decoded.remove(&path);
+                Some(Change::Replaced)
+            }
+            FileState::Present(_) => None,
+        };
+        self.seen.insert(path, (Instant::now(), state));
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, FileChecks, FileState};
+    use crate::ScratchDir;
+    use eframe::egui;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+    use tokio::runtime::Runtime;
+
+    fn temp_file(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, name).unwrap();
+        path
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        match FileState::of(path) {
+            FileState::Present(modified) => modified,
+            FileState::Missing => None,
+        }
+    }
+
+    // Look at `path` in the background, unless that happened recently, and
+    // wait for the answer
+    fn look(checks: &mut FileChecks, path: &Path, runtime: &Runtime) -> Option<Change> {
+        checks.request(path, runtime, &egui::Context::default());
+        loop {
+            let change = checks
+                .poll()
+                .into_iter()
+                .find(|(changed, _)| changed == path)
+                .map(|(_, change)| change);
+            if change.is_some() || checks.recent(path).is_some() {
+                return change;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn file_deleted_between_load_and_move_is_gone() {
+        let runtime = Runtime::new().unwrap();
+        let dir = ScratchDir::new("checks-deleted");
+        let path = temp_file(&dir, "deleted.png");
+        let mut checks = FileChecks::default();
+        checks.decoded(&path, modified(&path));
+
+        std::fs::remove_file(&path).unwrap();
+        // A watcher saw the folder change, so a move has to wait for a look
+        checks.invalidate_dir(path.parent().unwrap());
+        assert_eq!(checks.recent(&path), None);
+        assert_eq!(look(&mut checks, &path, &runtime), Some(Change::Gone));
+        assert_eq!(checks.recent(&path), Some(FileState::Missing));
+    }
+
+    #[test]
+    fn file_deleted_without_a_watcher_is_gone_once_the_answer_ages_out() {
+        let runtime = Runtime::new().unwrap();
+        let dir = ScratchDir::new("checks-unwatched");
+        let path = temp_file(&dir, "unwatched.png");
+        let mut checks = FileChecks::default();
+        assert_eq!(look(&mut checks, &path, &runtime), None);
+
+        std::fs::remove_file(&path).unwrap();
+        // Still trusted just after the last look
+        assert_eq!(look(&mut checks, &path, &runtime), None);
+        let (when, _) = checks.seen.get_mut(&path).unwrap();
+        *when -= super::FRESH_FOR;
+        assert_eq!(look(&mut checks, &path, &runtime), Some(Change::Gone));
+    }
+
+    #[test]
+    fn file_written_over_after_decoding_is_replaced_once() {
+        let runtime = Runtime::new().unwrap();
+        let dir = ScratchDir::new("checks-replaced");
+        let path = temp_file(&dir, "replaced.png");
+        let mut checks = FileChecks::default();
+        let decoded_at = modified(&path);
+        checks.decoded(&path, decoded_at);
+        assert_eq!(look(&mut checks, &path, &runtime), None);
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(decoded_at.unwrap() + Duration::from_secs(60))
+            .unwrap();
+        checks.invalidate_dir(path.parent().unwrap());
+        assert_eq!(look(&mut checks, &path, &runtime), Some(Change::Replaced));
+        checks.invalidate_dir(path.parent().unwrap());
+        assert_eq!(look(&mut checks, &path, &runtime), None);
+    }
+}
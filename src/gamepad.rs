@@ -0,0 +1,145 @@
+use crate::keybindings::Action;
+use eframe::egui;
+
+// Game controller input, when built with the `gamepad` feature. Without a
+// controller connected this does nothing. Controllers can come and go at any
+// time
+#[derive(Default)]
+pub struct Gamepad {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+    // A stick flick sorts once, then has to come back to the middle
+    #[cfg(feature = "gamepad")]
+    stick_armed: bool,
+}
+
+// The button for `action`, as shown in the shortcut overlay
+pub fn glyph(action: Action) -> Option<&'static str> {
+    match action {
+        Action::Category(0) => Some("D-pad ←"),
+        Action::Category(1) => Some("D-pad →"),
+        Action::Category(2) => Some("D-pad ↑"),
+        Action::Category(3) => Some("D-pad ↓"),
+        Action::Undo => Some("B"),
+        Action::Previous => Some("LB"),
+        Action::Skip => Some("RB"),
+        Action::Pause => Some("Start"),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepad {
+    // How often to check for button presses while a controller is connected;
+    // egui only repaints on its own input
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+    // How far a stick has to be pushed to count as a flick, and how close to
+    // the middle it has to come back before the next one
+    const FLICK: f32 = 0.8;
+    const DEADZONE: f32 = 0.3;
+
+    pub fn new() -> Self {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                log::debug!("Gamepad support unavailable: {}", e);
+                None
+            }
+        };
+        Self {
+            gilrs,
+            stick_armed: true,
+        }
+    }
+
+    pub fn connected(&self) -> bool {
+        self.gilrs
+            .as_ref()
+            .is_some_and(|gilrs| gilrs.gamepads().next().is_some())
+    }
+
+    // Action for the first mapped button pressed since the last call, or for
+    // a left stick flick when `stick_sort` is on
+    pub fn poll(&mut self, ctx: &egui::Context, stick_sort: bool) -> Option<Action> {
+        let gilrs = self.gilrs.as_mut()?;
+        let mut action = None;
+        while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+            match event {
+                gilrs::EventType::ButtonPressed(button, _) => {
+                    action = action.or(button_action(button));
+                }
+                gilrs::EventType::Connected => {
+                    log::info!("Controller connected: {}", gilrs.gamepad(id).name());
+                }
+                gilrs::EventType::Disconnected => {
+                    log::info!("Controller disconnected: {}", gilrs.gamepad(id).name());
+                }
+                _ => {}
+            }
+        }
+
+        if stick_sort {
+            let stick = gilrs.gamepads().find_map(|(_, pad)| {
+                let x = pad.value(gilrs::Axis::LeftStickX);
+                let y = pad.value(gilrs::Axis::LeftStickY);
+                (x.abs().max(y.abs()) > Self::DEADZONE).then_some((x, y))
+            });
+            match stick {
+                Some((x, y)) if self.stick_armed && x.abs().max(y.abs()) > Self::FLICK => {
+                    self.stick_armed = false;
+                    action = action.or(Some(stick_action(x, y)));
+                }
+                Some(_) => {}
+                None => self.stick_armed = true,
+            }
+        }
+
+        if gilrs.gamepads().next().is_some() {
+            ctx.request_repaint_after(Self::POLL_INTERVAL);
+        }
+        action
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn button_action(button: gilrs::Button) -> Option<Action> {
+    use gilrs::Button;
+
+    // D-pad in the same order as the arrow keys
+    match button {
+        Button::DPadLeft => Some(Action::Category(0)),
+        Button::DPadRight => Some(Action::Category(1)),
+        Button::DPadUp => Some(Action::Category(2)),
+        Button::DPadDown => Some(Action::Category(3)),
+        Button::East => Some(Action::Undo),
+        Button::LeftTrigger => Some(Action::Previous),
+        Button::RightTrigger => Some(Action::Skip),
+        Button::Start => Some(Action::Pause),
+        _ => None,
+    }
+}
+
+// The D-pad direction a stick pushed to (x, y) is closest to; up is positive
+#[cfg(feature = "gamepad")]
+fn stick_action(x: f32, y: f32) -> Action {
+    if x.abs() > y.abs() {
+        Action::Category(if x < 0.0 { 0 } else { 1 })
+    } else {
+        Action::Category(if y > 0.0 { 2 } else { 3 })
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+impl Gamepad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connected(&self) -> bool {
+        false
+    }
+
+    pub fn poll(&mut self, _ctx: &egui::Context, _stick_sort: bool) -> Option<Action> {
+        None
+    }
+}
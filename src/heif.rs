@@ -0,0 +1,56 @@
+use crate::loader::DecodeError;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use libheif_rs::{ColorSpace, FileTypeResult, HeifContext, ImageHandle, LibHeif, RgbChroma};
+use std::path::Path;
+
+// HEIC/HEIF decoding through libheif, in builds with the `heif` feature
+
+// Whether a file header is HEIF that libheif can read
+pub fn is_heif(header: &[u8]) -> bool {
+    matches!(
+        libheif_rs::check_file_type(header),
+        FileTypeResult::Supported
+    )
+}
+
+// The primary image of the file, as 8-bit RGB(A). Like other images, one
+// with more than `max_pixels` is refused before it's decoded
+pub fn decode(path: &Path, max_pixels: Option<u64>) -> Result<DynamicImage, DecodeError> {
+    let context = path
+        .to_str()
+        .and_then(|path| HeifContext::read_from_file(path).ok())
+        .ok_or(DecodeError::Unreadable)?;
+    let handle = context
+        .primary_image_handle()
+        .map_err(|_| DecodeError::Unreadable)?;
+    let pixels = handle.width() as u64 * handle.height() as u64;
+    if max_pixels.is_some_and(|max_pixels| pixels > max_pixels) {
+        return Err(DecodeError::TooLarge(pixels));
+    }
+    decode_handle(&handle).ok_or(DecodeError::Unreadable)
+}
+
+fn decode_handle(handle: &ImageHandle) -> Option<DynamicImage> {
+    let alpha = handle.has_alpha_channel();
+    let (chroma, channels) = if alpha {
+        (RgbChroma::Rgba, 4)
+    } else {
+        (RgbChroma::Rgb, 3)
+    };
+    let image = LibHeif::new()
+        .decode(handle, ColorSpace::Rgb(chroma), None)
+        .ok()?;
+    let plane = image.planes().interleaved?;
+
+    // Rows can be padded past the last pixel
+    let row = plane.width as usize * channels;
+    let mut pixels = Vec::with_capacity(row * plane.height as usize);
+    for y in 0..plane.height as usize {
+        pixels.extend_from_slice(&plane.data[y * plane.stride..][..row]);
+    }
+    if alpha {
+        RgbaImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgba8)
+    } else {
+        RgbImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgb8)
+    }
+}
@@ -0,0 +1,221 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+// UI text comes from Fluent catalogs (https://projectfluent.org). English is
+// built in and fills in any message a translation lacks. Other languages are
+// built in below or read from <config dir>/leftright/locales/<code>.ftl,
+// which also replaces a built-in catalog of the same code
+
+const ENGLISH: &str = include_str!("../locales/en.ftl");
+const BUILT_IN: [(&str, &str); 1] = [("de", include_str!("../locales/de.ftl"))];
+
+type Bundle = FluentBundle<FluentResource>;
+
+struct Catalog {
+    english: Bundle,
+    selected: Option<Bundle>,
+}
+
+fn catalog() -> &'static RwLock<Catalog> {
+    static CATALOG: OnceLock<RwLock<Catalog>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        RwLock::new(Catalog {
+            english: bundle("en", ENGLISH.to_string()),
+            selected: None,
+        })
+    })
+}
+
+// Look up a message, e.g. `t!("images-left", count = 3)`. Arguments are
+// anything Fluent takes: numbers (which pick plural forms) and strings.
+// Exported under a private name and used as `i18n::t`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __t {
+    ($id:literal) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub use __t as t;
+
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let catalog = catalog().read().unwrap();
+    let text = catalog
+        .selected
+        .iter()
+        .chain([&catalog.english])
+        .find_map(|bundle| format(bundle, id, args));
+    text.unwrap_or_else(|| {
+        log::warn!("No text for {}", id);
+        id.to_string()
+    })
+}
+
+fn format(bundle: &Bundle, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        log::warn!("Problems formatting {}: {:?}", id, errors);
+    }
+    Some(text.into_owned())
+}
+
+fn bundle(code: &str, source: String) -> Bundle {
+    let language: LanguageIdentifier = code.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // Bidi isolation marks around arguments show up as boxes in egui's fonts
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source).unwrap_or_else(|(resource, errors)| {
+        log::warn!("Errors in the {} catalog: {:?}", code, errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::warn!("Errors in the {} catalog: {:?}", code, errors);
+    }
+    bundle
+}
+
+fn user_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("leftright").join("locales"))
+}
+
+// Codes of user catalogs in the config directory
+fn user_languages() -> Vec<String> {
+    let Some(entries) = user_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect()
+}
+
+fn source(code: &str) -> Option<String> {
+    if let Some(user) =
+        user_dir().and_then(|dir| std::fs::read_to_string(dir.join(format!("{}.ftl", code))).ok())
+    {
+        return Some(user);
+    }
+    if code == "en" {
+        return Some(ENGLISH.to_string());
+    }
+    BUILT_IN
+        .iter()
+        .find(|(built_in, _)| *built_in == code)
+        .map(|(_, source)| source.to_string())
+}
+
+// Every language there's a catalog for, as (code, name in that language)
+pub fn languages() -> Vec<(String, String)> {
+    let mut codes: Vec<String> = std::iter::once("en".to_string())
+        .chain(BUILT_IN.iter().map(|(code, _)| code.to_string()))
+        .collect();
+    for code in user_languages() {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+        .into_iter()
+        .map(|code| {
+            let name = source(&code)
+                .and_then(|source| format(&bundle(&code, source), "language-name", None))
+                .unwrap_or_else(|| code.clone());
+            (code, name)
+        })
+        .collect()
+}
+
+// The catalog matching the system locale, by exact code or else language
+fn system_language() -> Option<String> {
+    let locale: LanguageIdentifier = sys_locale::get_locale()?.parse().ok()?;
+    let codes: Vec<String> = languages().into_iter().map(|(code, _)| code).collect();
+    codes
+        .iter()
+        .find(|code| **code == locale.to_string())
+        .or_else(|| codes.iter().find(|code| **code == locale.language.as_str()))
+        .cloned()
+}
+
+// Switch to `code`, or with None to the system's language
+pub fn set_language(code: Option<&str>) {
+    let code = code.map(String::from).or_else(system_language);
+    let selected = code
+        .filter(|code| code != "en")
+        .and_then(|code| Some(bundle(&code, source(&code)?)));
+    catalog().write().unwrap().selected = selected;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(source: &str) -> Vec<String> {
+        FluentResource::try_new(source.to_string())
+            .unwrap()
+            .entries()
+            .filter_map(|entry| match entry {
+                fluent_syntax::ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_message_used_is_in_the_english_catalog() {
+        let english = ids(ENGLISH);
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            for (at, _) in source.match_indices("t!(\"") {
+                // Not the end of format!( or print!(
+                if source[..at].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let id = source[at + 4..].split('"').next().unwrap();
+                assert!(
+                    english.iter().any(|known| known == id),
+                    "{} uses {}, which en.ftl doesn't have",
+                    path.display(),
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn translations_only_have_known_messages() {
+        let english = ids(ENGLISH);
+        for (code, source) in BUILT_IN {
+            for id in ids(source) {
+                assert!(english.contains(&id), "{}.ftl has stray {}", code, id);
+            }
+        }
+    }
+
+    #[test]
+    fn plurals_follow_the_language() {
+        // The built-in catalog, not a de.ftl the user may have dropped in
+        let (_, german) = BUILT_IN.iter().find(|(code, _)| *code == "de").unwrap();
+        let german = bundle("de", german.to_string());
+        let count = |count: usize| {
+            let mut args = FluentArgs::new();
+            args.set("count", count);
+            format(&german, "images-left", Some(&args)).unwrap()
+        };
+        assert_eq!(count(1), "1 Bild übrig");
+        assert_eq!(count(3), "3 Bilder übrig");
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+// Background file operations still running, so closing the window can wait
+// for them instead of dropping the runtime halfway through a move, and tests
+// can wait for files to land before looking for them
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<(Mutex<usize>, Condvar)>);
+
+// Held by a running file operation; counts it as finished when dropped
+pub struct InFlightGuard(Arc<(Mutex<usize>, Condvar)>);
+
+impl InFlight {
+    pub fn start(&self) -> InFlightGuard {
+        *self.0 .0.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        InFlightGuard(self.0.clone())
+    }
+
+    pub fn count(&self) -> usize {
+        *self.0 .0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Block until no operation is running, or `timeout` has passed. Whether
+    // they all finished
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let (count, finished) = &*self.0;
+        let count = count.lock().unwrap_or_else(PoisonError::into_inner);
+        let (count, _) = finished
+            .wait_timeout_while(count, timeout, |count| *count > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        *count == 0
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let (count, finished) = &*self.0;
+        let mut count = count.lock().unwrap_or_else(PoisonError::into_inner);
+        *count -= 1;
+        if *count == 0 {
+            finished.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InFlight;
+    use std::time::Duration;
+
+    #[test]
+    fn wait_returns_once_every_operation_is_done() {
+        let in_flight = InFlight::default();
+        assert!(in_flight.wait(Duration::ZERO));
+
+        let guards = [in_flight.start(), in_flight.start()];
+        assert_eq!(in_flight.count(), 2);
+        assert!(!in_flight.wait(Duration::from_millis(10)));
+
+        let worker = std::thread::spawn(move || {
+            for guard in guards {
+                std::thread::sleep(Duration::from_millis(20));
+                drop(guard);
+            }
+        });
+        assert!(in_flight.wait(Duration::from_secs(10)));
+        assert_eq!(in_flight.count(), 0);
+        worker.join().unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use crate::sorter::move_file;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+// Moves the window already shows as done, written down as each starts and
+// crossed off as it finishes. A process killed in between leaves them in the
+// file, and the next start finishes them. Lines go straight to the OS, which
+// keeps them through a crash of the app; only losing power can drop one
+#[derive(Clone, Default)]
+pub struct Journal(Arc<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    file: Option<Mutex<File>>,
+    next_id: AtomicU64,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Entry {
+    Begin { id: u64, from: PathBuf, to: PathBuf },
+    Done { id: u64 },
+}
+
+// A move the last run didn't get to finish, and how finishing it went
+pub struct Recovered {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub result: io::Result<()>,
+}
+
+impl Journal {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("leftright").join("journal.jsonl"))
+    }
+
+    // Finish the moves a previous run left in the journal at `path`, then
+    // start it afresh. Without a path nothing is written down
+    pub fn open(path: Option<&Path>) -> (Self, Vec<Recovered>) {
+        let Some(path) = path else {
+            return (Self::default(), Vec::new());
+        };
+        let recovered = recover(path);
+        let file = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(true)
+                    .open(path)
+            })
+            .map_err(|e| log::warn!("Can't keep a move journal at {}: {}", path.display(), e))
+            .ok();
+        let journal = Self(Arc::new(Inner {
+            file: file.map(Mutex::new),
+            next_id: AtomicU64::new(0),
+        }));
+        (journal, recovered)
+    }
+
+    // Note down a move about to start; pass the id to `finish` once it has
+    pub fn begin(&self, from: &Path, to: &Path) -> u64 {
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        self.write(&Entry::Begin {
+            id,
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        id
+    }
+
+    // The move is over, whether it worked or not; a failed one was reported
+    // and isn't tried again
+    pub fn finish(&self, id: u64) {
+        self.write(&Entry::Done { id });
+    }
+
+    fn write(&self, entry: &Entry) {
+        let Some(file) = &self.0.file else {
+            return;
+        };
+        let Ok(mut line) = serde_json::to_string(entry) else {
+            return;
+        };
+        line.push('\n');
+        let mut file = file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            log::warn!("Can't write to the move journal: {}", e);
+        }
+    }
+}
+
+// Moves begun and not done, finished now. One whose file has already left
+// its source got there before the process stopped. A destination next to a
+// source that's still there is a copy across drives cut short
+fn recover(path: &Path) -> Vec<Recovered> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut begun: Vec<(u64, PathBuf, PathBuf)> = Vec::new();
+    // A line cut off by the crash is skipped
+    for entry in BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+    {
+        match entry {
+            Entry::Begin { id, from, to } => begun.push((id, from, to)),
+            Entry::Done { id } => begun.retain(|(begun, ..)| *begun != id),
+        }
+    }
+    begun
+        .into_iter()
+        .filter(|(_, from, _)| from.exists())
+        .map(|(_, from, to)| {
+            let result = match std::fs::remove_file(&to) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => move_file(&from, &to),
+            };
+            Recovered { from, to, result }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+    use crate::ScratchDir;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+
+    // Set in the child process `killed_mid_move` runs in, to its folder
+    const CHILD: &str = "LEFTRIGHT_JOURNAL_CHILD";
+
+    fn files(dir: &Path) -> (PathBuf, PathBuf, PathBuf, PathBuf) {
+        (
+            dir.join("done.jpg"),
+            dir.join("sorted").join("done.jpg"),
+            dir.join("cut.jpg"),
+            dir.join("sorted").join("cut.jpg"),
+        )
+    }
+
+    // Run by `acknowledged_moves_survive_a_kill` in a process of its own: one
+    // move finishes, another is acknowledged, then the process dies before
+    // it can run
+    #[test]
+    fn killed_mid_move() {
+        let Some(dir) = std::env::var_os(CHILD).map(PathBuf::from) else {
+            return;
+        };
+        let (journal, _) = Journal::open(Some(&dir.join("journal.jsonl")));
+        let (done_from, done_to, cut_from, cut_to) = files(&dir);
+        let id = journal.begin(&done_from, &done_to);
+        std::fs::rename(&done_from, &done_to).unwrap();
+        journal.finish(id);
+        journal.begin(&cut_from, &cut_to);
+        std::process::abort();
+    }
+
+    #[test]
+    fn acknowledged_moves_survive_a_kill() {
+        let dir = ScratchDir::new("journal");
+        std::fs::create_dir(dir.join("sorted")).unwrap();
+        let (done_from, done_to, cut_from, cut_to) = files(&dir);
+        std::fs::write(&done_from, b"done").unwrap();
+        std::fs::write(&cut_from, b"cut").unwrap();
+
+        let status = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "journal::tests::killed_mid_move", "--nocapture"])
+            .env(CHILD, &*dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!status.success());
+        assert!(done_to.exists() && cut_from.exists() && !cut_to.exists());
+
+        let (_, recovered) = Journal::open(Some(&dir.join("journal.jsonl")));
+        assert_eq!(recovered.len(), 1);
+        assert!(recovered[0].result.is_ok());
+        assert_eq!(std::fs::read(&cut_to).unwrap(), b"cut");
+        assert!(!cut_from.exists());
+        assert!(done_to.exists() && !done_from.exists());
+
+        // Finished moves are crossed off the fresh journal
+        let (_, recovered) = Journal::open(Some(&dir.join("journal.jsonl")));
+        assert!(recovered.is_empty());
+    }
+}
@@ -0,0 +1,319 @@
+use crate::i18n::t;
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const MAX_CATEGORIES: usize = 4;
+// Categories past the direct keys are reached through the chord picker
+pub const MAX_CHORD_CATEGORIES: usize = 26;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Category(usize),
+    Undo,
+    Redo,
+    Skip,
+    Previous,
+    Delete,
+    Keep,
+    Help,
+    Chord,
+    NewCategory,
+    Pause,
+    Fit,
+    History,
+    Favorite,
+    PreviousPage,
+    NextPage,
+    Stats,
+    Filter,
+    // Held rather than pressed
+    Compare,
+    Fullscreen,
+}
+
+impl Action {
+    pub fn label(self) -> String {
+        match self {
+            Action::Category(idx) => t!("action-category", number = idx + 1),
+            Action::Undo => t!("action-undo"),
+            Action::Redo => t!("action-redo"),
+            Action::Skip => t!("action-skip"),
+            Action::Previous => t!("action-previous"),
+            Action::Delete => t!("action-delete"),
+            Action::Keep => t!("action-keep"),
+            Action::Help => t!("action-help"),
+            Action::Chord => t!("action-chord"),
+            Action::NewCategory => t!("action-new-category"),
+            Action::Pause => t!("action-pause"),
+            Action::Fit => t!("action-fit"),
+            Action::History => t!("action-history"),
+            Action::Favorite => t!("action-favorite"),
+            Action::PreviousPage => t!("action-previous-page"),
+            Action::NextPage => t!("action-next-page"),
+            Action::Stats => t!("action-stats"),
+            Action::Filter => t!("action-filter"),
+            Action::Compare => t!("action-compare"),
+            Action::Fullscreen => t!("action-fullscreen"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: Key,
+    #[serde(default)]
+    pub modifiers: Modifiers,
+}
+
+impl KeyBinding {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    pub const fn command(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::COMMAND,
+        }
+    }
+
+    pub fn shortcut(self) -> KeyboardShortcut {
+        KeyboardShortcut::new(self.modifiers, self.key)
+    }
+}
+
+// Mouse buttons and wheel directions that can trigger an action alongside its key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MouseInput {
+    Back,
+    Forward,
+    WheelUp,
+    WheelDown,
+}
+
+impl MouseInput {
+    pub fn label(self) -> String {
+        match self {
+            MouseInput::Back => t!("mouse-back"),
+            MouseInput::Forward => t!("mouse-forward"),
+            MouseInput::WheelUp => t!("wheel-up"),
+            MouseInput::WheelDown => t!("wheel-down"),
+        }
+    }
+}
+
+// Which key triggers each action, persisted in the config file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub categories: Vec<KeyBinding>,
+    pub undo: KeyBinding,
+    pub redo: KeyBinding,
+    pub skip: KeyBinding,
+    pub previous: KeyBinding,
+    pub delete: KeyBinding,
+    pub keep: KeyBinding,
+    pub help: KeyBinding,
+    pub chord: KeyBinding,
+    pub new_category: KeyBinding,
+    pub pause: KeyBinding,
+    pub fit: KeyBinding,
+    pub history: KeyBinding,
+    pub favorite: KeyBinding,
+    pub previous_page: KeyBinding,
+    pub next_page: KeyBinding,
+    pub stats: KeyBinding,
+    pub filter: KeyBinding,
+    pub compare: KeyBinding,
+    pub fullscreen: KeyBinding,
+    pub mouse: BTreeMap<MouseInput, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            categories: Self::ARROWS.map(KeyBinding::new).to_vec(),
+            undo: KeyBinding::command(Key::Z),
+            redo: KeyBinding::command(Key::Y),
+            skip: KeyBinding::new(Key::Space),
+            previous: KeyBinding::new(Key::Backspace),
+            delete: KeyBinding::new(Key::Delete),
+            keep: KeyBinding::new(Key::Enter),
+            help: KeyBinding::new(Key::F1),
+            chord: KeyBinding::new(Key::Tab),
+            new_category: KeyBinding::new(Key::N),
+            pause: KeyBinding::new(Key::P),
+            fit: KeyBinding::new(Key::F),
+            history: KeyBinding::new(Key::H),
+            favorite: KeyBinding::new(Key::X),
+            previous_page: KeyBinding::new(Key::PageUp),
+            next_page: KeyBinding::new(Key::PageDown),
+            stats: KeyBinding::new(Key::F12),
+            filter: KeyBinding::command(Key::F),
+            compare: KeyBinding::new(Key::B),
+            fullscreen: KeyBinding::new(Key::F11),
+            mouse: BTreeMap::from([
+                (MouseInput::Back, Action::Undo),
+                (MouseInput::Forward, Action::Redo),
+                (MouseInput::WheelUp, Action::Previous),
+                (MouseInput::WheelDown, Action::Skip),
+            ]),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub const ARROWS: [Key; MAX_CATEGORIES] = [
+        Key::ArrowLeft,
+        Key::ArrowRight,
+        Key::ArrowUp,
+        Key::ArrowDown,
+    ];
+    pub const WASD: [Key; MAX_CATEGORIES] = [Key::A, Key::D, Key::W, Key::S];
+    pub const HJKL: [Key; MAX_CATEGORIES] = [Key::H, Key::L, Key::K, Key::J];
+
+    // All bindable actions, categories first
+    pub fn actions(&self) -> Vec<Action> {
+        (0..MAX_CATEGORIES)
+            .map(Action::Category)
+            .chain([
+                Action::Undo,
+                Action::Redo,
+                Action::Skip,
+                Action::Previous,
+                Action::Delete,
+                Action::Keep,
+                Action::Help,
+                Action::Chord,
+                Action::NewCategory,
+                Action::Pause,
+                Action::Fit,
+                Action::History,
+                Action::Favorite,
+                Action::PreviousPage,
+                Action::NextPage,
+                Action::Stats,
+                Action::Filter,
+                Action::Compare,
+                Action::Fullscreen,
+            ])
+            .collect()
+    }
+
+    pub fn get(&self, action: Action) -> Option<KeyBinding> {
+        match action {
+            Action::Category(idx) => self.categories.get(idx).copied(),
+            Action::Undo => Some(self.undo),
+            Action::Redo => Some(self.redo),
+            Action::Skip => Some(self.skip),
+            Action::Previous => Some(self.previous),
+            Action::Delete => Some(self.delete),
+            Action::Keep => Some(self.keep),
+            Action::Help => Some(self.help),
+            Action::Chord => Some(self.chord),
+            Action::NewCategory => Some(self.new_category),
+            Action::Pause => Some(self.pause),
+            Action::Fit => Some(self.fit),
+            Action::History => Some(self.history),
+            Action::Favorite => Some(self.favorite),
+            Action::PreviousPage => Some(self.previous_page),
+            Action::NextPage => Some(self.next_page),
+            Action::Stats => Some(self.stats),
+            Action::Filter => Some(self.filter),
+            Action::Compare => Some(self.compare),
+            Action::Fullscreen => Some(self.fullscreen),
+        }
+    }
+
+    fn slot(&mut self, action: Action) -> Option<&mut KeyBinding> {
+        match action {
+            Action::Category(idx) => self.categories.get_mut(idx),
+            Action::Undo => Some(&mut self.undo),
+            Action::Redo => Some(&mut self.redo),
+            Action::Skip => Some(&mut self.skip),
+            Action::Previous => Some(&mut self.previous),
+            Action::Delete => Some(&mut self.delete),
+            Action::Keep => Some(&mut self.keep),
+            Action::Help => Some(&mut self.help),
+            Action::Chord => Some(&mut self.chord),
+            Action::NewCategory => Some(&mut self.new_category),
+            Action::Pause => Some(&mut self.pause),
+            Action::Fit => Some(&mut self.fit),
+            Action::History => Some(&mut self.history),
+            Action::Favorite => Some(&mut self.favorite),
+            Action::PreviousPage => Some(&mut self.previous_page),
+            Action::NextPage => Some(&mut self.next_page),
+            Action::Stats => Some(&mut self.stats),
+            Action::Filter => Some(&mut self.filter),
+            Action::Compare => Some(&mut self.compare),
+            Action::Fullscreen => Some(&mut self.fullscreen),
+        }
+    }
+
+    // Bind `action` to `binding`; an action already using that binding takes
+    // over the old one so no key ends up doing two things
+    pub fn set(&mut self, action: Action, binding: KeyBinding) {
+        let Some(previous) = self.get(action) else {
+            return;
+        };
+        let clash = self
+            .actions()
+            .into_iter()
+            .find(|other| *other != action && self.get(*other) == Some(binding));
+
+        if let Some(other) = clash.and_then(|other| self.slot(other)) {
+            *other = previous;
+        }
+        if let Some(slot) = self.slot(action) {
+            *slot = binding;
+        }
+    }
+
+    pub fn mouse_input(&self, action: Action) -> Option<MouseInput> {
+        self.mouse
+            .iter()
+            .find(|(_, bound)| **bound == action)
+            .map(|(input, _)| *input)
+    }
+
+    // Each action has at most one mouse input and each input one action
+    pub fn set_mouse(&mut self, action: Action, input: MouseInput) {
+        self.mouse.retain(|_, bound| *bound != action);
+        self.mouse.insert(input, action);
+    }
+
+    pub fn set_category_keys(&mut self, keys: [Key; MAX_CATEGORIES]) {
+        for (idx, key) in keys.into_iter().enumerate() {
+            self.set(Action::Category(idx), KeyBinding::new(key));
+        }
+    }
+}
+
+// Letter for each category in the chord picker: the first letter of its name
+// that isn't taken yet, or else the first free letter of the alphabet
+pub fn chord_letters(categories: &[String]) -> Vec<char> {
+    let mut letters: Vec<char> = Vec::with_capacity(categories.len());
+    for category in categories {
+        let letter = category
+            .to_lowercase()
+            .chars()
+            .chain('a'..='z')
+            .find(|c| c.is_ascii_lowercase() && !letters.contains(c))
+            .unwrap_or('?');
+        letters.push(letter);
+    }
+    letters
+}
+
+// Case-insensitive subsequence match, so "vcn" finds "vacation"
+pub fn fuzzy_match(query: &str, candidate: &str) -> bool {
+    let mut candidate = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| candidate.any(|c| c == q))
+}
@@ -0,0 +1,235 @@
+use crate::i18n::t;
+use eframe::egui::{self, Pos2, Vec2};
+use serde::{Deserialize, Serialize};
+
+// Where the category buckets sit around the current image
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BucketLayout {
+    // Left, right, above and below, matching the arrow keys
+    #[default]
+    Cardinal,
+    Corners,
+    BottomRow,
+    // One column down the right edge
+    Column,
+    // Two half-window drop zones, for yes/no culling
+    Halves,
+    // Columns either side of the image
+    Grid,
+}
+
+impl BucketLayout {
+    pub const ALL: [BucketLayout; 6] = [
+        BucketLayout::Cardinal,
+        BucketLayout::Corners,
+        BucketLayout::BottomRow,
+        BucketLayout::Column,
+        BucketLayout::Halves,
+        BucketLayout::Grid,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            BucketLayout::Cardinal => t!("layout-cross"),
+            BucketLayout::Corners => t!("layout-corners"),
+            BucketLayout::BottomRow => t!("layout-bottom-row"),
+            BucketLayout::Column => t!("layout-column"),
+            BucketLayout::Halves => t!("layout-halves"),
+            BucketLayout::Grid => t!("layout-grid"),
+        }
+    }
+}
+
+// Bucket sizes and centers for `count` categories plus the kept pile, in a
+// panel of `panel_size` around `center`
+pub struct Placement {
+    pub size: Vec2,
+    pub categories: Vec<Pos2>,
+    pub kept_size: Vec2,
+    pub kept: Pos2,
+}
+
+// Room left at the bottom of the panel for the filmstrip
+const FILMSTRIP_HEIGHT: f32 = 100.0;
+const MARGIN: f32 = 20.0;
+
+// How much bigger than at 800x600 buckets, cards and labels are drawn. Sizes
+// are in points, so the monitor's scale factor is already taken care of; this
+// follows the window size on top of that
+pub fn ui_scale(panel_size: Vec2) -> f32 {
+    (panel_size.min_elem() / 600.0).clamp(0.75, 2.0)
+}
+
+// Categories in the order they're laid out along a line, so the first two
+// (Left and Right keys) land at the ends and the rest fill in between
+fn line_order(count: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..count).filter(|idx| *idx != 1).collect();
+    if count > 1 {
+        order.push(1);
+    }
+    order
+}
+
+pub fn place(layout: BucketLayout, count: usize, center: Pos2, panel_size: Vec2) -> Placement {
+    let size = if count > 8 {
+        egui::vec2(70.0, 105.0)
+    } else {
+        egui::vec2(100.0, 150.0)
+    } * ui_scale(panel_size);
+    // Bottom right corner, clear of the filmstrip
+    let corner_kept = center + panel_size / 2.0 - size / 2.0 - egui::vec2(MARGIN, 40.0);
+    let top = center.y - panel_size.y / 2.0;
+    let bottom = center.y + panel_size.y / 2.0 - FILMSTRIP_HEIGHT;
+    let left = center.x - panel_size.x / 2.0;
+    let right = center.x + panel_size.x / 2.0;
+
+    match layout {
+        BucketLayout::BottomRow => {
+            // Shrink to fit everyone in one row above the filmstrip, with the
+            // kept pile next to the rightmost category
+            let slots = (count + 1) as f32;
+            let step = panel_size.x / slots;
+            let size = size * (step * 0.9 / size.x).min(0.6);
+            let y = bottom - size.y / 2.0;
+            let mut row: Vec<Option<usize>> = line_order(count).into_iter().map(Some).collect();
+            let kept_at = if count > 1 { count - 1 } else { count };
+            row.insert(kept_at, None);
+
+            let mut categories = vec![Pos2::ZERO; count];
+            let mut kept = corner_kept;
+            for (slot, entry) in row.into_iter().enumerate() {
+                let pos = egui::pos2(left + step * (slot as f32 + 0.5), y);
+                match entry {
+                    Some(idx) => categories[idx] = pos,
+                    None => kept = pos,
+                }
+            }
+            Placement {
+                size,
+                categories,
+                kept_size: size,
+                kept,
+            }
+        }
+        BucketLayout::Column => {
+            // Up at the top and Down at the bottom, everything else between
+            let mut order: Vec<usize> = (0..count).filter(|idx| *idx != 2 && *idx != 3).collect();
+            if count > 2 {
+                order.insert(0, 2);
+            }
+            if count > 3 {
+                order.push(3);
+            }
+            let step = (bottom - top) / count.max(1) as f32;
+            let size = size * (step * 0.9 / size.y).min(1.0);
+            let x = right - size.x / 2.0 - MARGIN;
+            let mut categories = vec![Pos2::ZERO; count];
+            for (slot, idx) in order.into_iter().enumerate() {
+                categories[idx] = egui::pos2(x, top + step * (slot as f32 + 0.5));
+            }
+            Placement {
+                size,
+                categories,
+                kept_size: size,
+                kept: egui::pos2(left + size.x / 2.0 + MARGIN, bottom - size.y / 2.0),
+            }
+        }
+        BucketLayout::Halves if count <= 2 => {
+            let half = egui::vec2(
+                panel_size.x / 2.0 - MARGIN * 1.5,
+                bottom - top - MARGIN * 2.0,
+            );
+            let y = (top + bottom) / 2.0;
+            let kept_size = size * 0.6;
+            Placement {
+                size: half,
+                categories: [
+                    egui::pos2(left + MARGIN + half.x / 2.0, y),
+                    egui::pos2(right - MARGIN - half.x / 2.0, y),
+                ]
+                .into_iter()
+                .take(count)
+                .collect(),
+                kept_size,
+                kept: egui::pos2(center.x, top + MARGIN + kept_size.y / 2.0),
+            }
+        }
+        BucketLayout::Grid => {
+            // Left key's category on the left, Right's on the right, and the
+            // rest alternating
+            let rows = count.div_ceil(2).max(1);
+            let step = (bottom - top) / rows as f32;
+            let size = size * (step * 0.9 / size.y).min(1.0);
+            let kept_size = size * 0.8;
+            let categories = (0..count)
+                .map(|idx| {
+                    let x = if idx % 2 == 0 {
+                        left + size.x / 2.0 + MARGIN
+                    } else {
+                        right - size.x / 2.0 - MARGIN
+                    };
+                    egui::pos2(x, top + step * ((idx / 2) as f32 + 0.5))
+                })
+                .collect();
+            Placement {
+                size,
+                categories,
+                kept_size,
+                kept: egui::pos2(center.x, top + MARGIN + kept_size.y / 2.0),
+            }
+        }
+        BucketLayout::Corners if count <= 4 => {
+            let inset = panel_size / 2.0 - size / 2.0 - egui::vec2(MARGIN, MARGIN);
+            Placement {
+                size,
+                categories: [
+                    egui::vec2(-inset.x, -inset.y),
+                    egui::vec2(inset.x, -inset.y),
+                    egui::vec2(-inset.x, inset.y),
+                    egui::vec2(inset.x, inset.y),
+                ]
+                .into_iter()
+                .take(count)
+                .map(|offset| center + offset)
+                .collect(),
+                kept_size: size,
+                // The bottom right corner is taken, so the right edge instead
+                kept: center + egui::vec2(inset.x, 0.0),
+            }
+        }
+        BucketLayout::Cardinal if count <= 4 => {
+            let spacing = panel_size.x * 0.25;
+            Placement {
+                size,
+                categories: [
+                    egui::vec2(-spacing, 0.0),
+                    egui::vec2(spacing, 0.0),
+                    egui::vec2(0.0, -spacing),
+                    egui::vec2(0.0, spacing),
+                ]
+                .into_iter()
+                .take(count)
+                .map(|offset| center + offset)
+                .collect(),
+                kept_size: size,
+                kept: corner_kept,
+            }
+        }
+        _ => {
+            // Too many for the chosen layout, so spread them around the image
+            let radius = egui::vec2(panel_size.x * 0.38, panel_size.y * 0.36);
+            Placement {
+                size,
+                categories: (0..count)
+                    .map(|i| {
+                        let angle =
+                            std::f32::consts::PI + std::f32::consts::TAU * i as f32 / count as f32;
+                        center + egui::vec2(angle.cos() * radius.x, angle.sin() * radius.y)
+                    })
+                    .collect(),
+                kept_size: size,
+                kept: corner_kept,
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Everything but the window: scanning, the config, file operations, image
+// loading and the widgets the app is built from. `Sorter` sorts a folder
+// without any UI, for scripts, tests and other front ends; the egui app in
+// main.rs is built on the rest
+pub mod accessibility;
+pub mod board;
+pub mod cache;
+pub mod capture_date;
+pub mod category_name;
+pub mod config;
+pub mod disk_space;
+pub mod file_checks;
+pub mod gamepad;
+#[cfg(feature = "heif")]
+pub mod heif;
+pub mod i18n;
+pub mod in_flight;
+pub mod journal;
+pub mod keybindings;
+pub mod layout;
+pub mod loader;
+pub mod notifications;
+pub mod queue;
+pub mod rename;
+pub mod report;
+pub mod scan;
+pub mod session;
+pub mod similarity;
+pub mod sorter;
+pub mod sound;
+pub mod stats;
+pub mod taskbar;
+pub mod theme;
+pub mod tiff_pages;
+#[cfg(feature = "video")]
+pub mod video;
+pub mod watcher;
+pub mod xmp;
+
+pub use sorter::Sorter;
+
+// An empty folder for a test to work in, removed once the test is over,
+// whether it passed or panicked
+#[cfg(test)]
+pub(crate) struct ScratchDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl ScratchDir {
+    // `name` keeps tests running side by side out of each other's way
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("leftright-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for ScratchDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
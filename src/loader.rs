@@ -0,0 +1,974 @@
+use crate::cache::ThumbnailCache;
+use crate::config::{Config, ResizeFilter};
+use crate::notifications::Notifier;
+use crate::stats::{LoadStats, Stage};
+use crate::tiff_pages;
+use eframe::egui;
+use image::DynamicImage;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::runtime::Runtime;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+pub const THUMBNAIL_SIZE: u32 = 160;
+
+// Decodes allowed to run at once
+const DECODE_SLOTS: usize = 4;
+
+// Tries before a busy file is reported as failed, and the wait before the
+// first retry
+const DECODE_ATTEMPTS: u32 = 4;
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+// Preview size and filter from the settings, read as each decode starts so
+// a change applies to the next image loaded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Preview {
+    // Longest side in logical pixels
+    pub size: u32,
+    pub filter: ResizeFilter,
+}
+
+impl Preview {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            size: config.preview_size,
+            filter: config.resize_filter,
+        }
+    }
+
+    // Longest side in physical pixels; a high-DPI display gets more
+    fn max_dimension(self, ctx: &egui::Context) -> f32 {
+        self.size as f32 * ctx.pixels_per_point().max(1.0)
+    }
+
+    // Part of the cache key, so changing either never serves an old preview
+    pub fn cache_variant(self) -> String {
+        format!("{}-{:?}", self.size, self.filter)
+    }
+}
+
+// Why a file produced no image
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    // The header promises more pixels than the limit; holds the pixel count
+    TooLarge(u64),
+    // Couldn't be read just now, e.g. while a sync client holds it; worth
+    // another try
+    Busy,
+    Unreadable,
+}
+
+// A decoded image ready for upload. Uploading happens on the UI thread, a few
+// per frame, so a burst of finished decodes doesn't stall rendering
+pub struct LoadedImage {
+    pub path: PathBuf,
+    pub image: egui::ColorImage,
+    pub thumbnail: egui::ColorImage,
+    pub has_alpha: bool,
+    // More than 1 for multi-page TIFFs; `image` is the first page
+    pub pages: usize,
+    // When the file was last changed, as of the decode
+    pub modified: Option<SystemTime>,
+}
+
+// Order in which waiting decodes get a slot: the priority lane first, newest
+// request first since that's the image the user just jumped to, then
+// everything else in the order it was requested
+pub struct LoadQueue<T> {
+    priority: VecDeque<T>,
+    normal: VecDeque<T>,
+}
+
+impl<T> Default for LoadQueue<T> {
+    fn default() -> Self {
+        Self {
+            priority: VecDeque::new(),
+            normal: VecDeque::new(),
+        }
+    }
+}
+
+impl<T: PartialEq> LoadQueue<T> {
+    // Returns false if `item` was already waiting; a priority push still
+    // moves it to the front in that case
+    pub fn push(&mut self, item: T, priority: bool) -> bool {
+        let queued = self.contains(&item);
+        if !priority {
+            if !queued {
+                self.normal.push_back(item);
+            }
+            return !queued;
+        }
+
+        self.remove(&item);
+        self.priority.push_front(item);
+        !queued
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.priority
+            .pop_front()
+            .or_else(|| self.normal.pop_front())
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.priority.len() + self.normal.len()
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.priority.contains(item) || self.normal.contains(item)
+    }
+
+    pub fn remove(&mut self, item: &T) {
+        self.priority.retain(|i| i != item);
+        self.normal.retain(|i| i != item);
+    }
+}
+
+// A path waiting for or holding a decode slot
+struct Request {
+    id: u64,
+    cancel: CancellationToken,
+    max_pixels: Option<u64>,
+}
+
+#[derive(Default)]
+struct Pending {
+    queue: LoadQueue<PathBuf>,
+    // Every queued or running path; one that's running isn't in the queue
+    requests: HashMap<PathBuf, Request>,
+    next_id: u64,
+}
+
+// What comes of a request, sent exactly once. Asking again for a path that's
+// still waiting or decoding doesn't start a second decode or a second event
+pub enum LoadEvent {
+    Loaded(LoadedImage),
+    // Refused for its size, with its pixel count
+    TooLarge(PathBuf, u64),
+    Failed(PathBuf),
+    Cancelled(PathBuf),
+}
+
+// Turns a file into pixels; swapped out in tests
+type Decoder =
+    Arc<dyn Fn(&Path, Option<u64>, &LoadStats) -> Result<DynamicImage, DecodeError> + Send + Sync>;
+
+// The loader's shared state, cloned into each decode task
+#[derive(Clone)]
+struct Worker {
+    cache: Option<Arc<ThumbnailCache>>,
+    stats: Arc<LoadStats>,
+    preview: Arc<Mutex<Preview>>,
+    pending: Arc<Mutex<Pending>>,
+    slots: Arc<Semaphore>,
+    decoder: Decoder,
+    // Wait before the first retry of a busy file, doubled for each one after
+    retry_delay: Duration,
+    tx: Sender<LoadEvent>,
+}
+
+// Background worker for image loading
+pub struct ImageLoader {
+    pub runtime: Runtime,
+    pub cache: Option<Arc<ThumbnailCache>>,
+    pub stats: Arc<LoadStats>,
+    worker: Worker,
+}
+
+impl ImageLoader {
+    // Every request ends in one event on `tx`
+    pub fn new(config: &Config, notifier: Notifier, tx: Sender<LoadEvent>) -> Self {
+        let cache =
+            ThumbnailCache::new(config.cache_max_mb * 1024 * 1024, notifier.clone()).map(Arc::new);
+        let loader = Self::build(
+            Preview::from_config(config),
+            cache,
+            tx,
+            Arc::new(decode_timed),
+            DECODE_SLOTS,
+            RETRY_DELAY,
+        );
+        if let Some(cache) = loader.cache.clone() {
+            loader.runtime.spawn_blocking(move || cache.index());
+        }
+        loader
+    }
+
+    fn build(
+        preview: Preview,
+        cache: Option<Arc<ThumbnailCache>>,
+        tx: Sender<LoadEvent>,
+        decoder: Decoder,
+        slots: usize,
+        retry_delay: Duration,
+    ) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4) // Use 4 worker threads
+            .enable_all()
+            .build()
+            .unwrap();
+        let stats = Arc::<LoadStats>::default();
+
+        Self {
+            runtime,
+            cache: cache.clone(),
+            stats: stats.clone(),
+            worker: Worker {
+                cache,
+                stats,
+                preview: Arc::new(Mutex::new(preview)),
+                pending: Arc::default(),
+                slots: Arc::new(Semaphore::new(slots)),
+                decoder,
+                retry_delay,
+                tx,
+            },
+        }
+    }
+
+    pub fn preview(&self) -> Preview {
+        *self.worker.preview.lock().unwrap()
+    }
+
+    pub fn set_preview(&self, preview: Preview) {
+        *self.worker.preview.lock().unwrap() = preview;
+    }
+
+    // Queue a decode. Files with more than `max_pixels` pixels aren't
+    // decoded. A priority request jumps the queue, or moves an earlier
+    // request for the same path to its front
+    pub fn request(
+        &self,
+        path: PathBuf,
+        priority: bool,
+        max_pixels: Option<u64>,
+        ctx: &egui::Context,
+    ) {
+        let added = {
+            let mut pending = self.worker.pending.lock().unwrap();
+            let pending = &mut *pending;
+            match pending.requests.get(&path) {
+                // Already decoding
+                Some(_) if !pending.queue.contains(&path) => return,
+                Some(_) => {
+                    pending.queue.push(path, priority);
+                    false
+                }
+                None => {
+                    pending.next_id += 1;
+                    let id = pending.next_id;
+                    pending.requests.insert(
+                        path.clone(),
+                        Request {
+                            id,
+                            cancel: CancellationToken::new(),
+                            max_pixels,
+                        },
+                    );
+                    pending.queue.push(path, priority);
+                    true
+                }
+            }
+        };
+
+        // One waiter per queued path. Whichever waiter gets a slot takes the
+        // most urgent path at that moment, not necessarily its own
+        if added {
+            let worker = self.worker.clone();
+            let ctx = ctx.clone();
+            self.runtime.spawn(async move {
+                let Ok(_slot) = worker.slots.clone().acquire_owned().await else {
+                    return;
+                };
+                worker.run_next(&ctx).await;
+            });
+        }
+    }
+
+    // Whether `path` is waiting for a decode or being decoded
+    pub fn is_pending(&self, path: &Path) -> bool {
+        self.worker
+            .pending
+            .lock()
+            .unwrap()
+            .requests
+            .contains_key(path)
+    }
+
+    // Every path waiting for a decode or being decoded
+    pub fn pending(&self) -> Vec<PathBuf> {
+        let pending = self.worker.pending.lock().unwrap();
+        pending.requests.keys().cloned().collect()
+    }
+
+    // Drop the request for `path`. A waiting one is reported cancelled at
+    // once, a running one as soon as its decode notices
+    pub fn cancel(&self, path: &Path) {
+        let mut pending = self.worker.pending.lock().unwrap();
+        let path = path.to_path_buf();
+        let Some(request) = pending.requests.remove(&path) else {
+            return;
+        };
+        request.cancel.cancel();
+        if pending.queue.contains(&path) {
+            pending.queue.remove(&path);
+            let _ = self.worker.tx.send(LoadEvent::Cancelled(path));
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        for path in self.pending() {
+            self.cancel(&path);
+        }
+    }
+
+    // Decodes waiting for a slot
+    pub fn queue_depth(&self) -> usize {
+        self.worker.pending.lock().unwrap().queue.waiting()
+    }
+
+    // Decode one page of a multi-page TIFF at display size. The result, or
+    // None if the page couldn't be read, is sent with its path and page
+    pub fn load_page(
+        &self,
+        path: PathBuf,
+        page: usize,
+        ctx: &egui::Context,
+        tx: Sender<(PathBuf, usize, Option<egui::ColorImage>)>,
+    ) {
+        let preview = self.preview();
+        let max_dimension = preview.max_dimension(ctx);
+        let ctx = ctx.clone();
+        self.runtime.spawn_blocking(move || {
+            let image = tiff_pages::decode_page(&path, page)
+                .map(|image| to_color_image(&fit(image, max_dimension, preview.filter)));
+            let _ = tx.send((path, page, image));
+            ctx.request_repaint();
+        });
+    }
+}
+
+impl Worker {
+    // Decode the most urgent waiting path, holding a slot
+    async fn run_next(&self, ctx: &egui::Context) {
+        let next = {
+            let mut pending = self.pending.lock().unwrap();
+            let pending = &mut *pending;
+            std::iter::from_fn(|| pending.queue.pop()).find_map(|path| {
+                let request = pending.requests.get(&path)?;
+                Some((path, request.id, request.cancel.clone(), request.max_pixels))
+            })
+        };
+        let Some((path, id, cancel, max_pixels)) = next else {
+            return;
+        };
+
+        let mut attempt = 1;
+        let result = loop {
+            let result = self
+                .load_image(path.clone(), ctx, cancel.clone(), max_pixels)
+                .await;
+            match result {
+                Err(DecodeError::Busy) if attempt < DECODE_ATTEMPTS => {
+                    let delay = self.retry_delay * 2u32.pow(attempt - 1);
+                    log::debug!("{} is busy, retrying in {:?}", path.display(), delay);
+                    attempt += 1;
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cancel.cancelled() => break result,
+                    }
+                }
+                result => break result,
+            }
+        };
+
+        // A cancelled path may have been requested again in the meantime;
+        // that request reports for itself
+        let requested_again = {
+            let mut pending = self.pending.lock().unwrap();
+            match pending.requests.get(&path) {
+                Some(request) if request.id == id => {
+                    pending.requests.remove(&path);
+                    false
+                }
+                other => other.is_some(),
+            }
+        };
+        let event = match result {
+            _ if cancel.is_cancelled() => {
+                if requested_again {
+                    return;
+                }
+                LoadEvent::Cancelled(path)
+            }
+            Ok(loaded) => {
+                log::debug!("Finished loading image: {}", loaded.path.display());
+                LoadEvent::Loaded(loaded)
+            }
+            Err(DecodeError::TooLarge(pixels)) => LoadEvent::TooLarge(path, pixels),
+            Err(DecodeError::Busy | DecodeError::Unreadable) => {
+                self.stats.failed();
+                LoadEvent::Failed(path)
+            }
+        };
+        let _ = self.tx.send(event);
+        ctx.request_repaint();
+    }
+
+    async fn load_image(
+        &self,
+        path: PathBuf,
+        ctx: &egui::Context,
+        cancel: CancellationToken,
+        max_pixels: Option<u64>,
+    ) -> Result<LoadedImage, DecodeError> {
+        let preview = *self.preview.lock().unwrap();
+        let stats = self.stats.clone();
+        let cache = self.cache.clone();
+        let decoder = self.decoder.clone();
+        let max_dimension = preview.max_dimension(ctx);
+        let variant = preview.cache_variant();
+        let variant_for_image = variant.clone();
+        let path_for_image = path.clone();
+        let cancel_for_image = cancel.clone();
+        let cache_for_image = cache.clone();
+        let stats_for_image = stats.clone();
+
+        // Move image loading to a blocking task with optimized settings
+        let (image_result, from_cache, pages, modified) = tokio::task::spawn_blocking(move || {
+            // The blocking decode can't be interrupted, so bail before starting it
+            if cancel_for_image.is_cancelled() {
+                return Err(DecodeError::Unreadable);
+            }
+            let stats = stats_for_image;
+            stats.started();
+            // Read before the pixels, so a file replaced mid-decode looks stale
+            let modified = std::fs::metadata(&path_for_image)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            let pages = tiff_pages::page_count(&path_for_image);
+            let cached = stats.time(Stage::Read, || {
+                cache_for_image
+                    .as_ref()
+                    .and_then(|cache| cache.load(&path_for_image, &variant_for_image))
+            });
+            if let Some(cached) = cached {
+                // A preview that was capped below what this display wants is
+                // decoded again
+                let longest = cached.width().max(cached.height()) as f32;
+                if longest >= max_dimension || longest < preview.size as f32 - 1.0 {
+                    return Ok((cached, true, pages, modified));
+                }
+            }
+            Ok((
+                decoder(&path_for_image, max_pixels, &stats)?,
+                false,
+                pages,
+                modified,
+            ))
+        })
+        .await
+        .map_err(|_| DecodeError::Unreadable)??;
+
+        if cancel.is_cancelled() {
+            return Err(DecodeError::Unreadable);
+        }
+
+        let resized = stats.time(Stage::Resize, || {
+            fit(image_result, max_dimension, preview.filter)
+        });
+
+        #[cfg(feature = "video")]
+        let from_cache =
+            from_cache || crate::video::ffmpeg_missing() && crate::video::is_video(&path);
+
+        // Write the preview back to the cache without holding up the upload
+        if let (false, Some(cache)) = (from_cache, cache) {
+            let preview = resized.clone();
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || cache.store(&path, &variant, &preview));
+        }
+
+        // Pixel conversion is as slow as a small decode, so it stays off the
+        // async workers too
+        let has_alpha = resized.color().has_alpha();
+        let stats_for_convert = stats.clone();
+        let (image, thumbnail) = tokio::task::spawn_blocking(move || {
+            stats_for_convert.time(Stage::Convert, || {
+                // Small copy that replaces the full texture once the image is sorted
+                let thumbnail = to_color_image(&resized.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+                (to_color_image(&resized), thumbnail)
+            })
+        })
+        .await
+        .map_err(|_| DecodeError::Unreadable)?;
+        stats.finished();
+
+        Ok(LoadedImage {
+            path,
+            image,
+            thumbnail,
+            has_alpha,
+            pages,
+            modified,
+        })
+    }
+}
+
+// Scale down so neither side is longer than `max_dimension`
+fn fit(
+    image: image::DynamicImage,
+    max_dimension: f32,
+    filter: ResizeFilter,
+) -> image::DynamicImage {
+    if image.width() as f32 <= max_dimension && image.height() as f32 <= max_dimension {
+        return image;
+    }
+    // A filtered resize keeps a buffer as tall as the source, which for a huge
+    // image is nearly as big as the image itself. Averaging down to twice the
+    // target first frees the full-size pixels before that happens
+    let image = if (image.width().max(image.height()) as f32) > max_dimension * 4.0 {
+        let step = (max_dimension * 2.0) as u32;
+        image.thumbnail(step, step)
+    } else {
+        image
+    };
+    let scale = max_dimension / image.width().max(image.height()) as f32;
+    image.resize(
+        (image.width() as f32 * scale) as u32,
+        (image.height() as f32 * scale) as u32,
+        filter.filter_type(),
+    )
+}
+
+// Decode the file at `path`, recognising the format by its contents. With a
+// `max_pixels`, the dimensions are read from the header first and anything
+// bigger is refused before its pixels are allocated
+pub fn decode(path: &Path, max_pixels: Option<u64>) -> Result<image::DynamicImage, DecodeError> {
+    decode_timed(path, max_pixels, &LoadStats::default())
+}
+
+// `decode`, adding the time spent reading the file and decoding it to `stats`
+fn decode_timed(
+    path: &Path,
+    max_pixels: Option<u64>,
+    stats: &LoadStats,
+) -> Result<image::DynamicImage, DecodeError> {
+    #[cfg(feature = "video")]
+    if crate::video::is_video(path) {
+        return stats.time(Stage::Decode, || crate::video::frame(path, max_pixels));
+    }
+    let io_error = |error: std::io::Error| {
+        if is_transient(&error) {
+            DecodeError::Busy
+        } else {
+            DecodeError::Unreadable
+        }
+    };
+    let started = Instant::now();
+    let mut file = BufReader::new(TimedRead::new(std::fs::File::open(path).map_err(io_error)?));
+    // Only the header is read to check the size; a refused image costs a few
+    // kilobytes of disk, not the whole file
+    if let Some(max_pixels) = max_pixels {
+        let dimensions = image::io::Reader::new(&mut file)
+            .with_guessed_format()
+            .map_err(io_error)?
+            .into_dimensions();
+        if let Ok((width, height)) = dimensions {
+            let pixels = width as u64 * height as u64;
+            if pixels > max_pixels {
+                return Err(DecodeError::TooLarge(pixels));
+            }
+        }
+        file.rewind().map_err(io_error)?;
+    }
+    let decoded = image::io::Reader::new(&mut file)
+        .with_guessed_format()
+        .map_err(io_error)?
+        .decode()
+        .map_err(|e| match e {
+            // A read that failed partway is as worth retrying as a failed open
+            image::ImageError::IoError(e) => io_error(e),
+            _ => DecodeError::Unreadable,
+        });
+    #[cfg(feature = "heif")]
+    let decoded = decoded.or_else(|e| match e {
+        DecodeError::Unreadable => crate::heif::decode(path, max_pixels),
+        e => Err(e),
+    });
+    // The decoder reads as it goes; the time it spent waiting on the file
+    // counts as reading, the rest as decoding
+    let read = file.get_ref().waited;
+    stats.add(Stage::Read, read);
+    stats.add(Stage::Decode, started.elapsed().saturating_sub(read));
+    decoded
+}
+
+// A file that adds up how long reads from it take
+struct TimedRead<R> {
+    inner: R,
+    waited: Duration,
+}
+
+impl<R> TimedRead<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            waited: Duration::ZERO,
+        }
+    }
+}
+
+impl<R: Read> Read for TimedRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let started = Instant::now();
+        let result = self.inner.read(buf);
+        self.waited += started.elapsed();
+        result
+    }
+}
+
+impl<R: Seek> Seek for TimedRead<R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+// Errors that a moment later may well be gone. Windows reports a file that
+// another program has open without sharing as a sharing or lock violation
+fn is_transient(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        error.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::TimedOut
+            | ErrorKind::ResourceBusy
+    ) || cfg!(windows) && matches!(error.raw_os_error(), Some(32 | 33))
+}
+
+fn to_color_image(image: &image::DynamicImage) -> egui::ColorImage {
+    let size = [image.width() as _, image.height() as _];
+    egui::ColorImage::from_rgba_unmultiplied(size, &image.to_rgba8())
+}
+
+// Hand a decoded image to the renderer
+pub fn upload(ctx: &egui::Context, name: String, image: egui::ColorImage) -> egui::TextureHandle {
+    ctx.load_texture(name, image, egui::TextureOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, DecodeError, ImageLoader, LoadEvent, LoadQueue, Preview};
+    use crate::config::ResizeFilter;
+    use crate::ScratchDir;
+    use eframe::egui;
+    use image::DynamicImage;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for byte in bytes {
+            crc ^= *byte as u32;
+            for _ in 0..8 {
+                crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+            }
+        }
+        !crc
+    }
+
+    // A PNG that claims to be `width` by `height` RGBA but carries no pixels
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut chunk = |kind: &[u8], data: &[u8]| {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let body = [kind, data].concat();
+            png.extend_from_slice(&body);
+            png.extend_from_slice(&crc32(&body).to_be_bytes());
+        };
+        let ihdr = [
+            &width.to_be_bytes()[..],
+            &height.to_be_bytes(),
+            &[8, 6, 0, 0, 0],
+        ]
+        .concat();
+        chunk(b"IHDR", &ihdr);
+        chunk(b"IDAT", &[]);
+        chunk(b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn huge_images_are_refused_from_the_header() {
+        let dir = ScratchDir::new("huge");
+        let path = dir.join("huge.png");
+        std::fs::write(&path, png_header(30_000, 30_000)).unwrap();
+        // Decoding 900 MP of RGBA would need 3.6 GB; this returns at once
+        assert_eq!(
+            decode(&path, Some(200_000_000)).err(),
+            Some(DecodeError::TooLarge(900_000_000))
+        );
+    }
+
+    fn drain(queue: &mut LoadQueue<u32>) -> Vec<u32> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn normal_requests_are_first_in_first_out() {
+        let mut queue = LoadQueue::default();
+        for item in [1, 2, 3] {
+            assert!(queue.push(item, false));
+        }
+        assert_eq!(drain(&mut queue), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn priority_request_takes_the_next_slot() {
+        let mut queue = LoadQueue::default();
+        queue.push(1, false);
+        queue.push(2, false);
+        assert!(queue.push(9, true));
+        assert_eq!(drain(&mut queue), vec![9, 1, 2]);
+    }
+
+    #[test]
+    fn latest_priority_request_goes_first() {
+        let mut queue = LoadQueue::default();
+        queue.push(1, false);
+        queue.push(7, true);
+        queue.push(8, true);
+        assert_eq!(drain(&mut queue), vec![8, 7, 1]);
+    }
+
+    #[test]
+    fn prioritizing_a_queued_item_moves_it_without_duplicating() {
+        let mut queue = LoadQueue::default();
+        queue.push(1, false);
+        queue.push(2, false);
+        queue.push(3, false);
+        assert!(!queue.push(3, true));
+        assert!(!queue.push(2, false));
+        assert_eq!(drain(&mut queue), vec![3, 1, 2]);
+    }
+
+    // A loader with one decode slot whose decoder logs each call and then
+    // hands off to `decode`, so no real image files are needed
+    struct Harness {
+        loader: ImageLoader,
+        events: Receiver<LoadEvent>,
+        decoded: Arc<Mutex<Vec<PathBuf>>>,
+        dir: ScratchDir,
+        ctx: egui::Context,
+    }
+
+    impl Harness {
+        fn new(
+            name: &str,
+            decode: impl Fn(&Path) -> Result<DynamicImage, DecodeError> + Send + Sync + 'static,
+        ) -> Self {
+            let dir = ScratchDir::new(&format!("loader-{}", name));
+            let (tx, events) = channel();
+            let decoded = Arc::<Mutex<Vec<PathBuf>>>::default();
+            let log = decoded.clone();
+            let preview = Preview {
+                size: 64,
+                filter: ResizeFilter::Nearest,
+            };
+            let loader = ImageLoader::build(
+                preview,
+                None,
+                tx,
+                Arc::new(move |path: &Path, _: Option<u64>, _: &_| {
+                    log.lock().unwrap().push(path.to_path_buf());
+                    decode(path)
+                }),
+                1,
+                Duration::from_millis(1),
+            );
+            Self {
+                loader,
+                events,
+                decoded,
+                dir,
+                ctx: egui::Context::default(),
+            }
+        }
+
+        fn file(&self, name: &str) -> PathBuf {
+            let path = self.dir.join(name);
+            std::fs::write(&path, name).unwrap();
+            path
+        }
+
+        fn request(&self, path: &Path, priority: bool) {
+            self.loader
+                .request(path.to_path_buf(), priority, None, &self.ctx);
+        }
+
+        fn next_events(&self, count: usize) -> Vec<String> {
+            (0..count)
+                .map(|_| {
+                    let event = self
+                        .events
+                        .recv_timeout(Duration::from_secs(10))
+                        .expect("an event");
+                    let (what, path) = match event {
+                        LoadEvent::Loaded(loaded) => ("loaded", loaded.path),
+                        LoadEvent::TooLarge(path, _) => ("too large", path),
+                        LoadEvent::Failed(path) => ("failed", path),
+                        LoadEvent::Cancelled(path) => ("cancelled", path),
+                    };
+                    format!("{} {}", what, name(&path))
+                })
+                .collect()
+        }
+
+        fn decoded(&self) -> Vec<String> {
+            self.decoded
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|p| name(p))
+                .collect()
+        }
+    }
+
+    fn name(path: &Path) -> String {
+        path.file_name().unwrap().to_string_lossy().into_owned()
+    }
+
+    fn pixels() -> Result<DynamicImage, DecodeError> {
+        Ok(DynamicImage::new_rgb8(4, 4))
+    }
+
+    // Holds up the decode of `slow.png` until released, so later requests
+    // wait behind it for the only slot. Returns the started signal, the
+    // release and the hook for the decoder
+    fn gate() -> (Receiver<()>, Sender<()>, impl Fn(&Path) + Send + Sync) {
+        let (started_tx, started) = channel();
+        let (release, release_rx) = channel();
+        let (started_tx, release_rx) = (Mutex::new(started_tx), Mutex::new(release_rx));
+        let hold = move |path: &Path| {
+            if path.ends_with("slow.png") {
+                let _ = started_tx.lock().unwrap().send(());
+                let _ = release_rx.lock().unwrap().recv();
+            }
+        };
+        (started, release, hold)
+    }
+
+    #[test]
+    fn priority_request_is_decoded_next() {
+        let (started, release, hold) = gate();
+        let harness = Harness::new("priority", move |path| {
+            hold(path);
+            pixels()
+        });
+        let slow = harness.file("slow.png");
+        let [a, b, c] = ["a.png", "b.png", "c.png"].map(|n| harness.file(n));
+
+        harness.request(&slow, false);
+        started.recv().unwrap();
+        harness.request(&a, false);
+        harness.request(&b, false);
+        harness.request(&c, true);
+        assert_eq!(harness.loader.queue_depth(), 3);
+        release.send(()).unwrap();
+
+        assert_eq!(
+            harness.next_events(4),
+            [
+                "loaded slow.png",
+                "loaded c.png",
+                "loaded a.png",
+                "loaded b.png"
+            ]
+        );
+        assert_eq!(harness.decoded(), ["slow.png", "c.png", "a.png", "b.png"]);
+    }
+
+    #[test]
+    fn cancelled_requests_are_reported_and_not_decoded() {
+        let (started, release, hold) = gate();
+        let harness = Harness::new("cancel", move |path| {
+            hold(path);
+            pixels()
+        });
+        let slow = harness.file("slow.png");
+        let [a, b] = ["a.png", "b.png"].map(|n| harness.file(n));
+
+        harness.request(&slow, false);
+        started.recv().unwrap();
+        harness.request(&a, false);
+        harness.request(&b, false);
+        // Asking again while it waits doesn't queue a second decode
+        harness.request(&b, false);
+        assert_eq!(harness.loader.queue_depth(), 2);
+        assert!(harness.loader.is_pending(&b));
+
+        // Waiting requests are dropped at once, running ones once they finish
+        harness.loader.cancel(&a);
+        assert_eq!(harness.next_events(1), ["cancelled a.png"]);
+        harness.loader.cancel(&slow);
+        release.send(()).unwrap();
+
+        assert_eq!(
+            harness.next_events(2),
+            ["cancelled slow.png", "loaded b.png"]
+        );
+        assert_eq!(harness.decoded(), ["slow.png", "b.png"]);
+        assert_eq!(harness.loader.queue_depth(), 0);
+    }
+
+    #[test]
+    fn busy_files_are_retried_until_they_can_be_read() {
+        let calls = Mutex::new(0);
+        let harness = Harness::new("retry", move |_| {
+            let mut calls = calls.lock().unwrap();
+            *calls += 1;
+            if *calls < 3 {
+                Err(DecodeError::Busy)
+            } else {
+                pixels()
+            }
+        });
+        let synced = harness.file("synced.png");
+
+        harness.request(&synced, false);
+        assert_eq!(harness.next_events(1), ["loaded synced.png"]);
+        assert_eq!(harness.decoded().len(), 3);
+    }
+
+    #[test]
+    fn decodes_fail_after_the_last_retry_or_at_once_when_unreadable() {
+        let harness = Harness::new("fail", |path| {
+            if path.ends_with("locked.png") {
+                Err(DecodeError::Busy)
+            } else {
+                Err(DecodeError::Unreadable)
+            }
+        });
+        let locked = harness.file("locked.png");
+        let broken = harness.file("broken.png");
+
+        harness.request(&locked, false);
+        assert_eq!(harness.next_events(1), ["failed locked.png"]);
+        assert_eq!(harness.decoded().len(), super::DECODE_ATTEMPTS as usize);
+
+        harness.request(&broken, false);
+        assert_eq!(harness.next_events(1), ["failed broken.png"]);
+        assert_eq!(harness.decoded().len(), super::DECODE_ATTEMPTS as usize + 1);
+        assert_eq!(harness.loader.stats.failures(), 2);
+    }
+}
@@ -1,677 +1,8278 @@
-use clap::Parser;
+use capture_date::DateLayout;
+use clap::{CommandFactory, Parser};
+use config::{
+    CollisionPolicy, Config, Easing, ImageFit, LastPosition, ResizeFilter, SortMode, WindowGeometry,
+};
+use disk_space::DiskSpace;
 use eframe::egui;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use file_checks::{Change, FileChecks, FileState};
+use gamepad::Gamepad;
+use i18n::t;
+use in_flight::InFlight;
+use keybindings::{
+    chord_letters, fuzzy_match, Action, KeyBinding, KeyBindings, MouseInput, MAX_CATEGORIES,
+    MAX_CHORD_CATEGORIES,
+};
+use layout::BucketLayout;
+use leftright::{
+    accessibility, board, cache, capture_date, category_name, config, disk_space, file_checks,
+    gamepad, i18n, in_flight, journal, keybindings, layout, loader, notifications, queue, rename,
+    report, scan, session, similarity, sorter, sound, stats, taskbar, theme, watcher, xmp,
+};
+use loader::{ImageLoader, LoadEvent, LoadedImage};
+use notifications::{Notifications, Notifier};
+use raw_window_handle::HasRawWindowHandle;
+use rename::RenameContext;
+use session::Session;
+use sorter::{unique_destination, Resolution};
+use sound::{Cue, SoundSettings, Sounds};
+use stats::Stage;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::runtime::Runtime;
+use std::time::{Instant, SystemTime};
+use taskbar::Taskbar;
+use theme::{Palette, Theme};
+use tokio_util::sync::CancellationToken;
+use watcher::FolderWatcher;
 
-// Background worker for image loading
-struct ImageLoader {
-    runtime: Runtime,
+// `sorter::move_file` off the UI thread
+async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    tokio::task::spawn_blocking(move || sorter::move_file(&from, &to))
+        .await
+        .map_err(std::io::Error::other)?
 }
 
-impl ImageLoader {
-    fn new() -> Self {
-        let runtime = tokio::runtime::Builder::new_multi_thread()
-            .worker_threads(4) // Use 4 worker threads
-            .enable_all()
-            .build()
-            .unwrap();
+// `sorter::link_file` off the UI thread
+async fn link_file(from: &Path, to: &Path, mode: SortMode) -> std::io::Result<()> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    tokio::task::spawn_blocking(move || sorter::link_file(&from, &to, mode))
+        .await
+        .map_err(std::io::Error::other)?
+}
 
-        Self { runtime }
+// Put the file itself where its link is, for turning links into moves
+async fn replace_link(original: &Path, link: &Path, mode: SortMode) -> std::io::Result<()> {
+    let is_symlink = tokio::fs::symlink_metadata(link)
+        .await?
+        .file_type()
+        .is_symlink();
+    if let Err(e) = tokio::fs::metadata(original).await {
+        // A hard link whose original is gone already is the file; a symlink
+        // is left alone, dangling
+        return if is_symlink { Err(e) } else { Ok(()) };
+    }
+    // Copying over a symlink would write through it into the original, so
+    // the link goes first
+    tokio::fs::remove_file(link).await?;
+    if let Err(e) = move_file(original, link).await {
+        // Don't leave the category without the file
+        let _ = link_file(original, link, mode).await;
+        return Err(e);
     }
+    Ok(())
+}
 
-    async fn load_image(
-        path: PathBuf,
-        ctx: egui::Context,
-    ) -> Option<(PathBuf, egui::TextureHandle)> {
-        let path_for_image = path.clone();
-
-        // Move image loading to a blocking task with optimized settings
-        let image_result = tokio::task::spawn_blocking(move || {
-            image::io::Reader::open(&path_for_image)
-                .ok()?
-                .with_guessed_format()
-                .ok()?
-                .decode()
-                .ok()
-        })
-        .await
-        .ok()??;
+// Swap one XMP tag for another on a file that was tagged rather than moved
+async fn retag(path: PathBuf, from: String, to: String, notifier: &Notifier) {
+    let name = path.display().to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        xmp::remove_tag(&path, &from)?;
+        xmp::add_tag(&path, &to)
+    })
+    .await;
+    match result {
+        Ok(Err(e)) => notifier.error(t!(
+            "tags-update-failed",
+            name = name.as_str(),
+            error = e.to_string()
+        )),
+        Err(e) => notifier.error(t!(
+            "tags-update-failed",
+            name = name.as_str(),
+            error = e.to_string()
+        )),
+        Ok(Ok(())) => {}
+    }
+}
 
-        let max_dimension = 1200.0;
-        let resized = if image_result.width() as f32 > max_dimension
-            || image_result.height() as f32 > max_dimension
-        {
-            let scale = max_dimension / image_result.width().max(image_result.height()) as f32;
-            image_result.resize(
-                (image_result.width() as f32 * scale) as u32,
-                (image_result.height() as f32 * scale) as u32,
-                image::imageops::FilterType::Triangle,
-            )
-        } else {
-            image_result
-        };
+const KEPT_COLOR: egui::Color32 = egui::Color32::from_rgb(140, 140, 140);
+const FAVORITE_COLOR: egui::Color32 = egui::Color32::from_rgb(250, 200, 40);
+
+// Pseudo-bucket for images that are fine where they are
+fn kept_label() -> String {
+    t!("kept")
+}
 
-        let size = [resized.width() as _, resized.height() as _];
-        let image_buffer = resized.to_rgba8();
+// Points added under an exported board for the session's numbers
+const BOARD_FOOTER: f32 = 28.0;
 
-        let texture = ctx.load_texture(
-            path.to_string_lossy().to_string(),
-            egui::ImageData::Color(Arc::new(egui::ColorImage::from_rgba_unmultiplied(
-                size,
-                &image_buffer,
-            ))),
-            egui::TextureOptions::default(),
+// The board as it was on screen, for drawing again off screen. Rects are in
+// points from the window's top left
+struct BoardSnapshot {
+    size: egui::Vec2,
+    palette: Palette,
+    panel: egui::Color32,
+    weak_text: egui::Color32,
+    image: Option<(PathBuf, egui::Rect)>,
+    max_pixels: Option<u64>,
+    // Rect, color and label of each bucket
+    buckets: Vec<(egui::Rect, egui::Color32, String)>,
+    label_size: f32,
+    status: Vec<(String, egui::Color32)>,
+    footer: String,
+}
+
+// Draw `snapshot` into an image, `scale` pixels to the point
+fn render_board(snapshot: &BoardSnapshot, scale: f32) -> egui::ColorImage {
+    use board::Canvas;
+
+    let mut canvas = board::Raster::new(snapshot.size, scale, snapshot.palette.background);
+    if let Some((path, rect)) = &snapshot.image {
+        match loader::decode(path, snapshot.max_pixels) {
+            Ok(image) => {
+                let size = rect.size() * scale;
+                let image = image.resize_exact(
+                    size.x.round().max(1.0) as u32,
+                    size.y.round().max(1.0) as u32,
+                    image::imageops::FilterType::Triangle,
+                );
+                let pixels = egui::ColorImage::from_rgba_unmultiplied(
+                    [image.width() as usize, image.height() as usize],
+                    &image.to_rgba8(),
+                );
+                canvas.draw_image(*rect, &pixels);
+            }
+            Err(e) => log::warn!("Left {} out of the board image: {:?}", path.display(), e),
+        }
+    }
+
+    for (rect, color, label) in &snapshot.buckets {
+        board::bucket_frame(
+            &mut canvas,
+            *rect,
+            snapshot.palette.bucket_fill,
+            *color,
+            0.0,
+        );
+        board::bucket_label(
+            &mut canvas,
+            *rect,
+            label.clone(),
+            egui::FontId::proportional(snapshot.label_size),
+            *color,
         );
+    }
+
+    // The status bar, then the footer under what was on screen
+    let font = egui::FontId::proportional(14.0);
+    let footer = egui::Rect::from_min_max(
+        egui::pos2(0.0, snapshot.size.y - BOARD_FOOTER),
+        snapshot.size.to_pos2(),
+    );
+    let status = footer.translate(egui::vec2(0.0, -BOARD_FOOTER));
+    canvas.fill_rect(status.union(footer), 0.0, snapshot.panel);
+    board::text_line(
+        &mut canvas,
+        status.left_center() + egui::vec2(8.0, 0.0),
+        &snapshot.status,
+        font.clone(),
+    );
+    board::text_line(
+        &mut canvas,
+        footer.left_center() + egui::vec2(8.0, 0.0),
+        &[(snapshot.footer.clone(), snapshot.weak_text)],
+        font,
+    );
+    canvas.finish()
+}
+
+// What's written on a bucket: its key, its name and what's in it
+fn bucket_label(key_hint: &str, category: Option<&str>, bucket: &CategoryBucket) -> String {
+    format!(
+        "{} {}\n{}",
+        key_hint,
+        category.unwrap_or(&kept_label()),
+        bucket.summary()
+    )
+}
 
-        Some((path, texture))
+// Name of each bucket position, in category order
+fn direction_category(idx: usize) -> String {
+    match idx {
+        0 => t!("direction-left"),
+        1 => t!("direction-right"),
+        2 => t!("direction-up"),
+        _ => t!("direction-down"),
     }
 }
 
+enum ScanEvent {
+    // An image and its size and modified time
+    Found(PathBuf, scan::FileInfo),
+    Done(scan::Skipped),
+    // The folder couldn't be read at all
+    Failed(String),
+}
+
+// Running totals of what a scan has found so far
+#[derive(Default)]
+struct ScanTally {
+    images: usize,
+    bytes: u64,
+    largest: Option<(PathBuf, u64)>,
+}
+
+impl ScanTally {
+    fn add(&mut self, path: &Path, size: u64) {
+        self.images += 1;
+        self.bytes += size;
+        if self
+            .largest
+            .as_ref()
+            .is_none_or(|(_, largest)| size > *largest)
+        {
+            self.largest = Some((path.to_path_buf(), size));
+        }
+    }
+}
+
+// Category folders that already hold files, asked about before sorting starts
+// so an earlier session's output isn't mixed in unnoticed
+struct OccupiedFolders {
+    // Category and how many entries its folder holds
+    folders: Vec<(String, usize)>,
+    // While the folders are emptied into the trash
+    emptying: Option<Receiver<Result<(), String>>>,
+}
+
+// Whether the scanned folder is big enough to ask before decoding anything,
+// e.g. because the app was started in the home folder
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BigFolder {
+    // Under the limits so far
+    Fine,
+    Asking,
+    // Over them, but the user said to go ahead
+    Allowed,
+    // Scanning and decoding stopped while another folder is picked
+    Declined,
+}
+
+// How key presses are interpreted while sorting
+#[derive(Clone, PartialEq, Eq)]
+enum InputMode {
+    Direct,
+    // Leader pressed; the next letter picks a category
+    Chord,
+    // `/` pressed in the chord picker; typing narrows the list by name
+    ChordSearch(String),
+    // `/` pressed while sorting; typing narrows the list, the arrow keys pick
+    // a row and Enter files the image there
+    Palette { filter: String, selected: usize },
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Directory containing images to sort
     #[arg(short, long)]
     dir: Option<PathBuf>,
+
+    /// Comma-separated categories; skips the setup screen
+    #[arg(short, long, value_delimiter = ',')]
+    categories: Vec<String>,
+
+    /// Sort the images listed in this file, one path per line
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["dir", "from_stdin"], requires = "dest")]
+    from_list: Option<PathBuf>,
+
+    /// Sort the image paths piped in on stdin, one per line
+    #[arg(long, conflicts_with = "dir", requires = "dest")]
+    from_stdin: bool,
+
+    /// Where the category folders go when sorting from a list
+    #[arg(long, value_name = "DIR", conflicts_with = "dir")]
+    dest: Option<PathBuf>,
 }
 
 #[derive(Clone)]
 struct Animation {
     path: PathBuf,
+    // Bucket the card flies to, None for the kept pile; its rect is looked
+    // up every frame so a relayout mid-flight is followed
+    bucket: Option<String>,
     start_pos: egui::Pos2,
     end_pos: egui::Pos2,
     start_time: Instant,
     duration: f32,
     start_scale: f32,
     end_scale: f32,
+    easing: Easing,
+    // Flying back out of the bucket on undo, from the card's place on the
+    // pile to `end_pos`
+    returning: bool,
+    // Where the card sat on its pile, from the bucket's center in unscaled points
+    card_offset: egui::Vec2,
+}
+
+// The center image changing over: the old one fades out as the new one fades in
+struct Crossfade {
+    // Texture, rect and uv of the old image; None when it flew into a bucket
+    // or went to the trash, so only the new one fades in
+    from: Option<(egui::TextureHandle, egui::Rect, egui::Rect)>,
+    start: Instant,
+}
+
+// The image shown in the center before the current one, and since when.
+// Holding the handle keeps its texture alive after it's evicted or sorted
+struct PreviousImage {
+    path: PathBuf,
+    texture: egui::TextureHandle,
+    since: Instant,
+}
+
+// The centered image is no longer on screen; keep it for comparing
+fn retire_shown(
+    shown: &mut Option<(PathBuf, egui::TextureHandle, egui::Rect, egui::Rect)>,
+    previous: &mut Option<PreviousImage>,
+) {
+    if let Some((path, texture, ..)) = shown.take() {
+        *previous = Some(PreviousImage {
+            path,
+            texture,
+            since: Instant::now(),
+        });
+    }
 }
 
+#[derive(Clone)]
 struct MoveOperation {
+    // Stays with the move however the history around it changes
+    id: u64,
     from: PathBuf,
     to: PathBuf,
+    // None when the image was kept in place
+    category: Option<String>,
+    mode: SortMode,
+    timestamp: Instant,
+    // Wall-clock time for the session report
+    time: SystemTime,
+    // Moves sharing a batch are undone and redone together
+    batch: Option<u64>,
+}
+
+// Links being turned into moves in the background, one result per file
+struct LinkCommit {
+    rx: Receiver<(u64, std::io::Result<()>)>,
+    total: usize,
+    done: usize,
+    failed: usize,
+}
+
+// A category being applied to the next few images in one undoable batch
+struct BatchRun {
+    category: usize,
+    remaining: usize,
+    id: u64,
+    next_at: Instant,
+    // File operation failures already seen when the batch started
+    failures: usize,
+}
+
+// Time between the cards of a batch
+const BATCH_STAGGER: std::time::Duration = std::time::Duration::from_millis(80);
+
+// A change on disk made for a sort, its undo or a return to the queue
+enum FileOp {
+    // Filing an image into a category, in whatever way the sort mode says,
+    // and taking that back. Both go through the same code as `Sorter`
+    Sort(sorter::Move),
+    Unsort(sorter::Move),
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Unlink(PathBuf),
+    Tag {
+        path: PathBuf,
+        category: String,
+        add: bool,
+    },
+    Rate {
+        path: PathBuf,
+        rating: Option<u8>,
+    },
+}
+
+impl FileOp {
+    fn describe(&self) -> String {
+        match self {
+            FileOp::Sort(sort) => match sort.mode {
+                SortMode::Move => FileOp::Move {
+                    from: sort.from.clone(),
+                    to: sort.to.clone(),
+                }
+                .describe(),
+                SortMode::Tag => FileOp::Tag {
+                    path: sort.from.clone(),
+                    category: sort.category.clone(),
+                    add: true,
+                }
+                .describe(),
+                _ => t!(
+                    "op-link",
+                    from = path_arg(&sort.from),
+                    to = path_arg(&sort.to)
+                ),
+            },
+            FileOp::Unsort(sort) => match sort.mode {
+                SortMode::Move => FileOp::Move {
+                    from: sort.to.clone(),
+                    to: sort.from.clone(),
+                }
+                .describe(),
+                SortMode::Tag => FileOp::Tag {
+                    path: sort.from.clone(),
+                    category: sort.category.clone(),
+                    add: false,
+                }
+                .describe(),
+                _ => FileOp::Unlink(sort.to.clone()).describe(),
+            },
+            FileOp::Move { from, to } => t!("op-move", from = path_arg(from), to = path_arg(to)),
+            FileOp::Unlink(link) => t!("op-unlink", link = path_arg(link)),
+            FileOp::Tag {
+                path,
+                category,
+                add: true,
+            } => t!(
+                "op-tag",
+                path = path_arg(path),
+                category = category.as_str()
+            ),
+            FileOp::Tag { path, category, .. } => {
+                t!(
+                    "op-untag",
+                    category = category.as_str(),
+                    path = path_arg(path)
+                )
+            }
+            FileOp::Rate {
+                path,
+                rating: Some(rating),
+            } => t!("op-rate", path = path_arg(path), rating = *rating),
+            FileOp::Rate { path, .. } => t!("op-unrate", path = path_arg(path)),
+        }
+    }
+
+    // Whether running `self` puts things back the way they were before `other`
+    fn undoes(&self, other: &FileOp) -> bool {
+        match (self, other) {
+            (FileOp::Unsort(undo), FileOp::Sort(sort)) => undo == sort,
+            (FileOp::Move { from, to }, FileOp::Move { from: f, to: t }) => from == t && to == f,
+            (FileOp::Unlink(link), FileOp::Sort(sort)) => sort.mode.is_link() && *link == sort.to,
+            (
+                FileOp::Tag {
+                    path,
+                    category,
+                    add: false,
+                },
+                FileOp::Sort(sort),
+            ) => sort.mode == SortMode::Tag && *path == sort.from && *category == sort.category,
+            (
+                FileOp::Tag {
+                    path,
+                    category,
+                    add,
+                },
+                FileOp::Tag {
+                    path: p,
+                    category: c,
+                    add: a,
+                },
+            ) => path == p && category == c && add != a,
+            _ => false,
+        }
+    }
+
+    // The file it moves from one place to another, if it moves one
+    fn rename(&self) -> Option<(&Path, &Path)> {
+        match self {
+            FileOp::Sort(sort) if sort.mode == SortMode::Move => Some((&sort.from, &sort.to)),
+            FileOp::Unsort(sort) if sort.mode == SortMode::Move => Some((&sort.to, &sort.from)),
+            FileOp::Move { from, to } => Some((from, to)),
+            _ => None,
+        }
+    }
+
+    // The files it creates or takes away
+    fn paths(&self) -> Vec<PathBuf> {
+        match self {
+            FileOp::Sort(sort) | FileOp::Unsort(sort) => vec![sort.from.clone(), sort.to.clone()],
+            FileOp::Move { from, to } => vec![from.clone(), to.clone()],
+            FileOp::Unlink(path) | FileOp::Tag { path, .. } | FileOp::Rate { path, .. } => {
+                vec![path.clone()]
+            }
+        }
+    }
+
+    async fn run(self) -> std::io::Result<()> {
+        match self {
+            FileOp::Sort(sort) => tokio::task::spawn_blocking(move || {
+                // Category folders are made up front, but date folders only
+                // once something lands in them
+                if let Some(dir) = sort.to.parent().filter(|_| sort.mode.uses_folders()) {
+                    std::fs::create_dir_all(dir)?;
+                }
+                sorter::apply(&sort)
+            })
+            .await
+            .map_err(std::io::Error::other)?,
+            FileOp::Unsort(sort) => tokio::task::spawn_blocking(move || sorter::revert(&sort))
+                .await
+                .map_err(std::io::Error::other)?,
+            FileOp::Move { from, to } => move_file(&from, &to).await,
+            FileOp::Unlink(link) => tokio::fs::remove_file(&link).await,
+            FileOp::Tag {
+                path,
+                category,
+                add,
+            } => tokio::task::spawn_blocking(move || {
+                if add {
+                    xmp::add_tag(&path, &category)
+                } else {
+                    xmp::remove_tag(&path, &category)
+                }
+            })
+            .await
+            .map_err(std::io::Error::other)?,
+            FileOp::Rate { path, rating } => {
+                tokio::task::spawn_blocking(move || xmp::set_rating(&path, rating))
+                    .await
+                    .map_err(std::io::Error::other)?
+            }
+        }
+    }
+}
+
+// How long closing waits for file operations before offering to quit anyway
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+// How long the window, once gone, still waits for file operations left running
+const EXIT_WAIT: std::time::Duration = std::time::Duration::from_secs(1);
+
+// How often an idle window without a folder watcher looks at the current file
+const CURRENT_FILE_RECHECK: std::time::Duration = std::time::Duration::from_secs(3);
+// How often the set of watched category folders is brought up to date
+const WATCH_SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+// The last card filed into a bucket stays this much bigger for LAST_MOVED_HOLD,
+// then shrinks back over LAST_MOVED_RELAX
+const LAST_MOVED_GROWTH: f32 = 0.25;
+const LAST_MOVED_HOLD: f32 = 1.0;
+const LAST_MOVED_RELAX: f32 = 0.5;
+// Moves quicker than this don't flash a busy indicator
+const MOVING_INDICATOR_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+// Seconds the "updated externally" note stays on a bucket
+const EXTERNAL_FLASH: f32 = 1.5;
+// Seconds a bucket squashes and pops back after a flying card lands in it
+const ABSORB_PULSE: f32 = 0.2;
+// Moves to another drive of files at least this big check for space first
+const SPACE_CHECK_BYTES: u64 = 50 * 1024 * 1024;
+// How long the image before the current one can still be compared against
+const COMPARE_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+// Cards on a pile sit up to this far from its center, in points, and are
+// turned up to this many radians either way
+const CARD_SCATTER: f32 = 5.0;
+const CARD_TILT: f32 = 0.07;
+// Cards drawn on a pile; the rest are counted in a "+N more" note
+const MAX_VISIBLE_CARDS: usize = 5;
+
+// Scroll distance that counts as one wheel step
+const WHEEL_STEP: f32 = 50.0;
+const MAX_ZOOM: f32 = 4.0;
+
+// A released swipe sorts the image if it went this far, or was at least a
+// quarter of the way and moving this fast; anything less snaps back
+const SWIPE_DISTANCE: f32 = 120.0;
+const SWIPE_SPEED: f32 = 800.0;
+
+// Time per frame spent handing decoded images to the renderer; the rest wait
+// for the next frame
+const UPLOAD_BUDGET: std::time::Duration = std::time::Duration::from_millis(3);
+
+// Size to draw a `texture_size` image at in `panel` under `fit`, and the
+// part of the texture to show
+fn fit_image(
+    fit: ImageFit,
+    texture_size: egui::Vec2,
+    panel: egui::Rect,
+    zoom: f32,
+) -> (egui::Vec2, egui::Rect) {
+    let full = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    let aspect = texture_size.x / texture_size.y;
+    match fit {
+        ImageFit::Card => {
+            let height = panel.height() * 0.4 * zoom;
+            (egui::vec2(height * aspect, height), full)
+        }
+        ImageFit::Fit => {
+            let scale = (panel.width() / texture_size.x).min(panel.height() / texture_size.y);
+            (texture_size * scale * zoom, full)
+        }
+        ImageFit::Fill => {
+            // Crop the longer side to the panel's shape, and zoom by cropping further
+            let panel_aspect = panel.width() / panel.height();
+            let visible = if aspect > panel_aspect {
+                egui::vec2(panel_aspect / aspect, 1.0)
+            } else {
+                egui::vec2(1.0, aspect / panel_aspect)
+            } / zoom;
+            (
+                panel.size(),
+                egui::Rect::from_center_size(egui::pos2(0.5, 0.5), visible),
+            )
+        }
+        ImageFit::Actual => (texture_size * zoom, full),
+    }
+}
+
+// Text on a filled box centered on `pos`, over whatever is drawn there
+fn draw_banner(ui: &egui::Ui, pos: egui::Pos2, text: String) {
+    let galley = ui.painter().layout_no_wrap(
+        text,
+        egui::FontId::proportional(20.0 * accessibility::text_scale(ui.style())),
+        ui.visuals().text_color(),
+    );
+    let banner = egui::Rect::from_center_size(pos, galley.size() + egui::vec2(32.0, 16.0));
+    ui.painter()
+        .rect_filled(banner, 6.0, ui.visuals().window_fill());
+    ui.painter()
+        .galley(banner.center() - galley.size() / 2.0, galley);
+}
+
+// Category for a released swipe, in arrow key order
+fn swipe_direction(offset: egui::Vec2, velocity: egui::Vec2) -> Option<usize> {
+    let horizontal = offset.x.abs() >= offset.y.abs();
+    let (distance, speed) = if horizontal {
+        (offset.x, velocity.x)
+    } else {
+        (offset.y, velocity.y)
+    };
+    let far = distance.abs() >= SWIPE_DISTANCE;
+    let fast = speed.abs() >= SWIPE_SPEED
+        && speed.signum() == distance.signum()
+        && distance.abs() >= SWIPE_DISTANCE / 4.0;
+    if !far && !fast {
+        return None;
+    }
+    Some(match (horizontal, distance < 0.0) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (false, false) => 3,
+    })
+}
+
+// Upcoming images in the filmstrip, of which about eight are visible at once
+const FILMSTRIP_LEN: usize = 32;
+
+// Bucket open in the review window: None for the kept pile
+struct BucketReview {
+    slot: Option<String>,
+    focus: Option<PathBuf>,
+    // Bring the focused file into view on the next frame
+    scroll: bool,
+}
+
+// Thumbnail of a filed image, or its name on a chip when there's no texture yet
+fn file_card(
+    ui: &mut egui::Ui,
+    path: &Path,
+    thumbnail: Option<&egui::TextureHandle>,
+    selected: bool,
+) -> egui::Response {
+    let size = egui::vec2(96.0, 96.0);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let response = match thumbnail {
+        Some(texture) => ui.add_sized(
+            size,
+            egui::ImageButton::new(egui::Image::from_texture(texture).max_size(size))
+                .selected(selected),
+        ),
+        None => ui.add_sized(
+            size,
+            egui::Button::new(egui::RichText::new(&name).small())
+                .wrap(true)
+                .selected(selected),
+        ),
+    };
+    response.on_hover_text(name)
+}
+
+// The filter bar's fields as typed; ones that don't parse are left out
+#[derive(Default)]
+struct FilterInput {
+    name: String,
+    modified_from: String,
+    modified_to: String,
+    min_mb: String,
+    max_mb: String,
+}
+
+impl FilterInput {
+    fn parse(&self) -> scan::QueueFilter {
+        scan::QueueFilter {
+            name: scan::NameFilter::new(&self.name),
+            modified_from: scan::parse_date(&self.modified_from),
+            modified_to: scan::parse_date(&self.modified_to),
+            min_bytes: scan::parse_megabytes(&self.min_mb),
+            max_bytes: scan::parse_megabytes(&self.max_mb),
+        }
+    }
+}
+
+// One merge of `source` into `target`, undone as a whole
+struct MergeOperation {
+    source: String,
+    target: String,
+    color: egui::Color32,
+    // Where `source` sat in the category order
+    index: usize,
+    // Each file's path before and after the merge; equal for tagged files
+    files: Vec<(PathBuf, PathBuf)>,
     timestamp: Instant,
 }
 
+// What a merge running in the background carried from `source` to `target`,
+// each file before and after
+struct Merged {
+    source: String,
+    target: String,
+    files: Vec<(PathBuf, PathBuf)>,
+}
+
+// A file on a bucket's pile and where its card sits, settled when it lands so
+// the pile doesn't shift as other files come and go
+#[derive(Clone)]
+struct BucketCard {
+    path: PathBuf,
+    // From the pile's center, in unscaled points
+    offset: egui::Vec2,
+    rotation: f32,
+}
+
+impl BucketCard {
+    // Scattered the same way for the same path every time, so a pile read
+    // back from disk looks as it did
+    fn new(path: PathBuf) -> Self {
+        let hash = cache::stable_hash(path.to_string_lossy().as_bytes());
+        // -1..=1 from 16 bits of the hash
+        let spread = |shift: u32| ((hash >> shift) & 0xffff) as f32 / 32767.5 - 1.0;
+        Self {
+            path,
+            offset: egui::vec2(spread(0), spread(16)) * CARD_SCATTER,
+            rotation: spread(32) * CARD_TILT,
+        }
+    }
+}
+
 struct CategoryBucket {
-    files: Vec<PathBuf>,
+    // Top of the pile first
+    files: Vec<BucketCard>,
+    color: egui::Color32,
+    flash_time: Option<Instant>,
+    // When a flying card last landed in it
+    absorb_time: Option<Instant>,
+    // When files last came or went outside the app
+    external_time: Option<Instant>,
+    // The card that landed last, shown bigger for a moment
+    last_moved: Option<(PathBuf, Instant)>,
     rect: egui::Rect,
-    stack_offset: f32,
-    next_stack_position: f32, // Add this field to track where the next card should go
+    // Size of every file in the bucket, and their sum
+    sizes: HashMap<PathBuf, u64>,
+    total_bytes: u64,
+}
+
+impl CategoryBucket {
+    fn new(color: egui::Color32) -> Self {
+        Self {
+            files: Vec::new(),
+            color,
+            flash_time: None,
+            absorb_time: None,
+            external_time: None,
+            last_moved: None,
+            rect: egui::Rect::NOTHING,
+            sizes: HashMap::new(),
+            total_bytes: 0,
+        }
+    }
+
+    // Put `path` on top of the pile; `size` is taken from disk when not known
+    fn add_file(&mut self, path: PathBuf, size: Option<u64>) {
+        let size = size.unwrap_or_else(|| file_size(&path));
+        self.total_bytes += size;
+        if let Some(previous) = self.sizes.insert(path.clone(), size) {
+            self.total_bytes -= previous;
+        }
+        self.files.retain(|card| card.path != path);
+        self.files.insert(0, BucketCard::new(path));
+    }
+
+    // Take `path` off the pile, returning its size
+    fn remove_file(&mut self, path: &Path) -> Option<u64> {
+        self.files.retain(|card| card.path != path);
+        let size = self.sizes.remove(path)?;
+        self.total_bytes -= size;
+        Some(size)
+    }
+
+    // Replace the whole pile with files and their sizes, top first
+    fn set_files(&mut self, files: Vec<(PathBuf, u64)>) {
+        self.total_bytes = files.iter().map(|(_, size)| size).sum();
+        self.files = files
+            .iter()
+            .map(|(path, _)| BucketCard::new(path.clone()))
+            .collect();
+        self.sizes = files.into_iter().collect();
+    }
+
+    // Files read from the category's folder, under whatever landed on the
+    // pile while it was being read
+    fn add_listed(&mut self, files: Vec<(PathBuf, u64)>) {
+        for (path, size) in files {
+            if self.sizes.contains_key(&path) {
+                continue;
+            }
+            self.total_bytes += size;
+            self.sizes.insert(path.clone(), size);
+            self.files.push(BucketCard::new(path));
+        }
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().map(|card| &card.path)
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.files.iter().any(|card| card.path == path)
+    }
+
+    // `old` is now at `new`, e.g. after its folder was renamed
+    fn rename_file(&mut self, old: &Path, new: PathBuf) {
+        if let Some(size) = self.sizes.remove(old) {
+            self.sizes.insert(new.clone(), size);
+        }
+        for card in &mut self.files {
+            // Same place on the pile, so nothing moves as the folder is renamed
+            if card.path == old {
+                card.path = new.clone();
+            }
+        }
+    }
+
+    // "142 files, 3.4 GB"
+    fn summary(&self) -> String {
+        t!(
+            "bucket-summary",
+            count = self.files.len(),
+            size = format_bytes(self.total_bytes)
+        )
+    }
+}
+
+// Whether a move's file is gone from both ends. One still in flight is still
+// at its source
+fn is_deleted(op: &MoveOperation) -> bool {
+    op.mode == SortMode::Move && op.category.is_some() && !op.to.exists() && !op.from.exists()
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+// Paths as catalog arguments
+fn path_arg(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
 }
 
 struct ImageSorter {
     base_dir: PathBuf,
 
-    images: Vec<PathBuf>,
+    images: queue::Queue,
     categories: Vec<String>,
     category_buckets: HashMap<String, CategoryBucket>,
+    kept_bucket: CategoryBucket,
+    session: Session,
     current_image: Option<usize>,
     textures: HashMap<PathBuf, egui::TextureHandle>,
+    thumbnails: HashMap<PathBuf, egui::TextureHandle>,
+    // Thumbnails of queued images, kept in memory and uploaded once the
+    // image lands in a bucket
+    thumbnail_images: HashMap<PathBuf, egui::ColorImage>,
+    // Images with an alpha channel, drawn over a checkerboard when enabled
+    transparent: HashSet<PathBuf>,
+    // Page counts of multi-page TIFFs, and the page of the current image
+    // shown in place of the first, once decoded
+    page_counts: HashMap<PathBuf, usize>,
+    // Images refused for their size, in pixels, and those to decode anyway
+    oversized: HashMap<PathBuf, u64>,
+    decode_anyway: HashSet<PathBuf>,
+    // Images that couldn't be decoded, so they aren't asked for again
+    undecodable: HashSet<PathBuf>,
+    // Whether queued files are still on disk as they were decoded
+    file_checks: FileChecks,
+    page: Option<(PathBuf, usize, Option<egui::TextureHandle>)>,
+    page_tx: Sender<(PathBuf, usize, Option<egui::ColorImage>)>,
+    page_rx: Receiver<(PathBuf, usize, Option<egui::ColorImage>)>,
     animations: Vec<Animation>,
+    // Image drawn in the center last frame, with its rect and uv
+    shown: Option<(PathBuf, egui::TextureHandle, egui::Rect, egui::Rect)>,
+    crossfade: Option<Crossfade>,
+    // Flipped to while the compare key is held
+    previous_image: Option<PreviousImage>,
     moves: Vec<MoveOperation>,
+    merges: Vec<MergeOperation>,
+    // Stars set or cleared this session, newest last, undone like moves
+    favorite_toggles: Vec<(PathBuf, Instant)>,
+    redo_stack: Vec<MoveOperation>,
+    // Moves taken back since, kept for the session report
+    undone: Vec<MoveOperation>,
     setup_done: bool,
     input_categories: String,
     last_image_pos: Option<egui::Pos2>,
     loading_progress: f32,
     is_loading: bool,
     loader: ImageLoader,
-    pending_loads: Vec<PathBuf>,
-    texture_rx: Receiver<(PathBuf, egui::TextureHandle)>,
-    texture_tx: Sender<(PathBuf, egui::TextureHandle)>,
+    notifications: Notifications,
+    load_rx: Receiver<LoadEvent>,
+    // Decoded images waiting for their turn to be uploaded
+    uploads: VecDeque<LoadedImage>,
+    // Current image waiting on a priority decode, and since when
+    priority_load: Option<(PathBuf, Instant)>,
+    // Current image, its index and the queue length the preload window was
+    // last filled for, and whether decoding was allowed then
+    window_filled: Option<(PathBuf, usize, usize, bool)>,
     total_images_to_load: usize,
     pending_moves: Vec<PendingMove>,
+    // Bucket under the pointer, its rect, and the preview popup's rect
+    bucket_preview: Option<(Option<String>, egui::Rect, egui::Rect)>,
+    review: Option<BucketReview>,
+    // Images decoded only for a thumbnail, outside the preload window
+    thumbnail_loads: HashSet<PathBuf>,
+    in_flight: InFlight,
+    journal: journal::Journal,
+    // Moves whose file is still on its way, by move id, with their category
+    // and start
+    moving: HashMap<u64, (String, Instant)>,
+    move_done_tx: Sender<u64>,
+    move_done_rx: Receiver<u64>,
+    // Category folders changed on disk and not yet re-read, those being read
+    // in the background and what the reads found
+    folder_watcher: Option<FolderWatcher>,
+    folder_change_rx: Receiver<PathBuf>,
+    changed_folders: HashSet<PathBuf>,
+    reading_folders: HashSet<PathBuf>,
+    folder_diff_tx: Sender<(PathBuf, watcher::FolderDiff)>,
+    folder_diff_rx: Receiver<(PathBuf, watcher::FolderDiff)>,
+    // Category folders first read to fill their buckets, with their files
+    listing_tx: Sender<(PathBuf, Vec<(PathBuf, u64)>)>,
+    listing_rx: Receiver<(PathBuf, Vec<(PathBuf, u64)>)>,
+    // Files the app's own operations touch, which re-reads leave alone
+    own_changes: watcher::OwnChanges,
+    watch_synced: Option<Instant>,
+    link_commit: Option<LinkCommit>,
+    // Date folders for the queue, while auto-sort by date reads them
+    date_sort_rx: Option<Receiver<Vec<(PathBuf, PathBuf, bool)>>>,
+    // Similar-image groups for the queue, while they're being worked out
+    group_rx: Option<Receiver<Vec<Vec<PathBuf>>>>,
+    // Each grouped image's place in its group, and the group's size
+    groups: HashMap<PathBuf, (usize, usize)>,
+    // Image extensions as typed in Settings
+    extensions_text: String,
+    // Star ratings read from or written to sidecars, and reads still running
+    ratings: HashMap<PathBuf, Option<u8>>,
+    rating_reads: HashSet<PathBuf>,
+    rating_tx: Sender<(PathBuf, Option<u8>)>,
+    rating_rx: Receiver<(PathBuf, Option<u8>)>,
+    // When closing was first asked for, while file operations were still running
+    closing_since: Option<Instant>,
+    // The window as it is now, saved on close; None until the first frame
+    // has checked where the restored window ended up
+    window: Option<WindowGeometry>,
+    // Closing has been cleared to go ahead
+    quitting: bool,
+    // Disk changes held back by dry run until committed
+    planned: Vec<FileOp>,
+    config: Config,
+    show_settings: bool,
+    show_help: bool,
+    rebinding: Option<Action>,
+    input_mode: InputMode,
+    // Where the current image was last drawn, and how far it's zoomed in
+    image_rect: Option<egui::Rect>,
+    zoom: Option<(PathBuf, f32)>,
+    // Wheel movement not yet turned into a navigation step
+    wheel_delta: f32,
+    // Image the slideshow timer is running for, and since when
+    slideshow_image: Option<PathBuf>,
+    slideshow_since: Instant,
+    // Name being typed for a category added mid-session
+    new_category: Option<String>,
+    scan_rx: Option<Receiver<ScanEvent>>,
+    scan_cancel: Option<CancellationToken>,
+    scan_started: bool,
+    // Window title last sent, so it's only updated when it changes
+    title: String,
+    taskbar: Taskbar,
+    gamepad: Gamepad,
+    // Controller button pressed this frame, handled like a key
+    gamepad_action: Option<Action>,
+    sounds: Sounds,
+    // The all-done fanfare has played for the queue as it is now
+    finished: bool,
+    // Action button in the top bar pressed this frame, handled like a key
+    control_action: Option<Action>,
+    // Count typed before a category key, to apply it to that many images
+    repeat_count: String,
+    // Size of the last batch, repeated by Shift with a category key
+    last_batch: usize,
+    batch: Option<BatchRun>,
+    // Batch that moves made right now belong to
+    current_batch: Option<u64>,
+    // Batch ids handed out so far
+    batches: u64,
+    // File operations that have failed this session
+    failures: Arc<AtomicUsize>,
+    // Set when a move fails for want of space, so the session pauses
+    disk_full: Arc<AtomicBool>,
+    disk_space: DiskSpace,
+    // Categories whose folder is on another filesystem than the base folder,
+    // so moving there copies the file and takes up space
+    cross_device: HashSet<String>,
+    // Destination folders to check, with the bytes about to go there
+    space_needed: HashMap<PathBuf, u64>,
+    // Warning shown while a destination is short of space
+    low_space: Option<String>,
+    // How far the current image is panned at actual size
+    pan: Option<(PathBuf, egui::Vec2)>,
+    // How far the current image has been dragged by a swipe in progress
+    swipe: egui::Vec2,
+    // Sorting input is ignored until resumed
+    paused: bool,
+    // Image to start from, where sorting this folder stopped last time
+    resume_at: Option<PathBuf>,
+    // Queue filter as typed
+    filter_input: FilterInput,
+    show_filter_bar: bool,
+    // Category and drop point of the first move, held until confirmed
+    confirm_move: Option<(usize, egui::Pos2)>,
+    moves_confirmed: bool,
+    dont_confirm_again: bool,
+    // Put the cursor in the name field the next time the filter bar is drawn
+    focus_filter: bool,
+    // Last keyboard, mouse or controller input, for auto-pause
+    last_input: Instant,
+    // Moves waiting on an answer, shown one at a time
+    collisions: VecDeque<Collision>,
+    // Answer given with "apply to all", used instead of the configured policy
+    // for the rest of the session
+    collision_answer: Option<CollisionPolicy>,
+    collision_apply_all: bool,
+    // Category picked for "Move all remaining", waiting for confirmation
+    move_all: Option<usize>,
+    show_edit_categories: bool,
+    show_history: bool,
+    show_stats: bool,
+    // Width in pixels being picked for an exported board image
+    board_export: Option<u32>,
+    next_move_id: u64,
+    show_overview: bool,
+    // Images picked in the overview, filed together by the next category key
+    selected: HashSet<PathBuf>,
+    // Where the last plain click in the overview landed, for shift-click runs
+    selection_anchor: Option<usize>,
+    // Why the categories typed on the setup screen can't be used
+    setup_error: Option<String>,
+    // Files to sort instead of the folder's contents, from --from-list or
+    // --from-stdin; the folder then only holds the categories
+    source_list: Option<Vec<PathBuf>>,
+    // Enough of each listed path to tell files with the same name apart
+    short_names: HashMap<PathBuf, String>,
+    // Why the folder couldn't be scanned, until another one is picked
+    scan_error: Option<String>,
+    scan_tally: ScanTally,
+    big_folder: BigFolder,
+    occupied: Option<OccupiedFolders>,
+    // Background decoding stopped by the user; a scan asked for meanwhile
+    // starts on resume
+    loading_paused: bool,
+    scan_deferred: bool,
+    // Light or dark as reported by the OS, if it says
+    system_theme: Option<eframe::Theme>,
+    folder_rx: Option<Receiver<PathBuf>>,
+    // Category whose name is being edited, and the text typed so far
+    editing_category: Option<(String, String)>,
+    edit_new_category: String,
+    // Folder rename running in the background: old name, new name and outcome
+    rename_rx: Option<Receiver<(String, String, std::io::Result<()>)>>,
+    // A rename asked for while files were still on their way into the
+    // category, started once they have landed
+    held_rename: Option<(String, String)>,
+    // Merge running in the background
+    merge_rx: Option<Receiver<Merged>>,
+    // Like `held_rename`, a merge waiting on moves into either category
+    held_merge: Option<(String, String)>,
+    // Moves waiting on a look at their file in the background, with where
+    // their card starts from
+    held_moves: Vec<(PendingMove, egui::Pos2)>,
+}
+
+// A move held back because its file name is taken in the category folder,
+// waiting for the user to decide
+struct Collision {
+    from: PathBuf,
+    category: String,
+    existing: PathBuf,
 }
 
 #[derive(Clone)]
 struct PendingMove {
     from: PathBuf,
     to: PathBuf,
+    category: Option<String>,
 }
 
 impl ImageSorter {
-    fn new(base_dir: PathBuf) -> Self {
-        let (texture_tx, texture_rx) = channel();
+    fn new(base_dir: PathBuf, ctx: &egui::Context) -> Self {
+        let (load_tx, load_rx) = channel();
+        let (rating_tx, rating_rx) = channel();
+        let (page_tx, page_rx) = channel();
+        let (folder_change_tx, folder_change_rx) = channel();
+        let (move_done_tx, move_done_rx) = channel();
+        let (folder_diff_tx, folder_diff_rx) = channel();
+        let (listing_tx, listing_rx) = channel();
+        let config = Config::load();
+        let notifications = Notifications::new(ctx);
+        let sounds = Sounds::new(SoundSettings::from_config(&config));
+        // Moves the last run was stopped in the middle of land before the
+        // scan looks at the folders
+        let (journal, recovered) = journal::Journal::open(journal::Journal::path().as_deref());
+        let mut finished = 0;
+        for recovered in recovered {
+            match recovered.result {
+                Ok(()) => finished += 1,
+                Err(e) => notifications.error(t!(
+                    "move-recovery-failed",
+                    path = path_arg(&recovered.from),
+                    to = path_arg(&recovered.to),
+                    error = e.to_string()
+                )),
+            }
+        }
+        if finished > 0 {
+            notifications.info(t!("moves-recovered", count = finished));
+        }
+        let session = Session::load(&base_dir);
+        let mut kept_bucket = CategoryBucket::new(KEPT_COLOR);
+        kept_bucket.set_files(
+            session
+                .kept
+                .iter()
+                .map(|name| {
+                    let path = base_dir.join(name);
+                    let size = file_size(&path);
+                    (path, size)
+                })
+                .collect(),
+        );
+        // Matches the title the window opens with
+        let title = format!("LeftRight - {}", base_dir.display());
+        let resume_at = config
+            .last_position
+            .as_ref()
+            .filter(|position| position.dir == base_dir)
+            .map(|position| position.image.clone());
         Self {
             base_dir,
 
-            images: Vec::new(),
+            images: queue::Queue::default(),
             categories: Vec::new(),
             category_buckets: HashMap::new(),
+            kept_bucket,
+            session,
             current_image: None,
             textures: HashMap::new(),
+            thumbnails: HashMap::new(),
+            thumbnail_images: HashMap::new(),
+            transparent: HashSet::new(),
             animations: Vec::new(),
+            page_counts: HashMap::new(),
+            oversized: HashMap::new(),
+            decode_anyway: HashSet::new(),
+            undecodable: HashSet::new(),
+            file_checks: FileChecks::default(),
+            page: None,
+            page_tx,
+            page_rx,
+            shown: None,
+            previous_image: None,
+            crossfade: None,
             moves: Vec::new(),
+            merges: Vec::new(),
+            favorite_toggles: Vec::new(),
+            redo_stack: Vec::new(),
+            undone: Vec::new(),
             setup_done: false,
             input_categories: String::new(),
             last_image_pos: None,
             loading_progress: 0.0,
             is_loading: false,
-            loader: ImageLoader::new(),
-            pending_loads: Vec::new(),
-            texture_rx,
-            texture_tx,
+            loader: ImageLoader::new(&config, notifications.notifier(), load_tx),
+            notifications,
+            load_rx,
+            uploads: VecDeque::new(),
+            priority_load: None,
+            window_filled: None,
             total_images_to_load: 0, // Add this field
             pending_moves: Vec::new(),
+            bucket_preview: None,
+            review: None,
+            thumbnail_loads: HashSet::new(),
+            in_flight: InFlight::default(),
+            journal,
+            moving: HashMap::new(),
+            move_done_tx,
+            move_done_rx,
+            folder_watcher: FolderWatcher::new(folder_change_tx, ctx.clone()),
+            folder_change_rx,
+            changed_folders: HashSet::new(),
+            reading_folders: HashSet::new(),
+            folder_diff_tx,
+            folder_diff_rx,
+            listing_tx,
+            listing_rx,
+            own_changes: watcher::OwnChanges::default(),
+            watch_synced: None,
+            link_commit: None,
+            date_sort_rx: None,
+            group_rx: None,
+            extensions_text: config.image_extensions.join(", "),
+            groups: HashMap::new(),
+            ratings: HashMap::new(),
+            rating_reads: HashSet::new(),
+            rating_tx,
+            rating_rx,
+            closing_since: None,
+            window: None,
+            quitting: false,
+            planned: Vec::new(),
+            config,
+            show_settings: false,
+            show_help: false,
+            rebinding: None,
+            input_mode: InputMode::Direct,
+            image_rect: None,
+            zoom: None,
+            wheel_delta: 0.0,
+            slideshow_image: None,
+            slideshow_since: Instant::now(),
+            new_category: None,
+            scan_rx: None,
+            scan_cancel: None,
+            scan_started: false,
+            title,
+            taskbar: Taskbar::disabled(),
+            gamepad: Gamepad::new(),
+            gamepad_action: None,
+            sounds,
+            finished: false,
+            control_action: None,
+            repeat_count: String::new(),
+            last_batch: 1,
+            batch: None,
+            current_batch: None,
+            batches: 0,
+            failures: Arc::default(),
+            disk_full: Arc::default(),
+            disk_space: DiskSpace::default(),
+            cross_device: HashSet::new(),
+            space_needed: HashMap::new(),
+            low_space: None,
+            pan: None,
+            swipe: egui::Vec2::ZERO,
+            paused: false,
+            resume_at,
+            filter_input: FilterInput::default(),
+            show_filter_bar: false,
+            confirm_move: None,
+            moves_confirmed: false,
+            dont_confirm_again: false,
+            focus_filter: false,
+            last_input: Instant::now(),
+            collisions: VecDeque::new(),
+            collision_answer: None,
+            collision_apply_all: false,
+            move_all: None,
+            show_edit_categories: false,
+            show_history: false,
+            board_export: None,
+            show_stats: false,
+            next_move_id: 0,
+            show_overview: false,
+            selected: HashSet::new(),
+            selection_anchor: None,
+            setup_error: None,
+            source_list: None,
+            short_names: HashMap::new(),
+            scan_error: None,
+            scan_tally: ScanTally::default(),
+            big_folder: BigFolder::Fine,
+            occupied: None,
+            loading_paused: false,
+            scan_deferred: false,
+            system_theme: None,
+            folder_rx: None,
+            editing_category: None,
+            edit_new_category: String::new(),
+            rename_rx: None,
+            held_rename: None,
+            merge_rx: None,
+            held_merge: None,
+            held_moves: Vec::new(),
         }
     }
 
     fn start_background_loading(&mut self, ctx: &egui::Context) {
-        self.images = std::fs::read_dir(".")
-            .unwrap()
-            .filter_map(Result::ok)
-            .filter(|entry| {
-                let ext = entry
-                    .path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext.to_lowercase());
-                matches!(
-                    ext.as_deref(),
-                    Some("jpg" | "jpeg" | "png" | "gif" | "webp")
-                )
-            })
-            .map(|entry| entry.path())
-            .collect();
+        if self.loading_paused {
+            self.scan_deferred = true;
+            return;
+        }
+        self.cancel_scan();
+        let (scan_tx, scan_rx) = channel();
+        let cancel = CancellationToken::new();
+        self.scan_rx = Some(scan_rx);
+        self.scan_cancel = Some(cancel.clone());
+        self.scan_error = None;
+        self.scan_tally = ScanTally::default();
+        self.images.clear();
+        self.current_image = None;
+
+        // Enumerate off the UI thread so huge or remote folders don't freeze the window
+        let ctx = ctx.clone();
+        let base_dir = self.base_dir.clone();
+        let source_list = self.source_list.clone();
+        let options = scan::ScanOptions {
+            extensions: self.config.image_extensions.clone(),
+            detect_by_content: self.config.detect_by_content,
+        };
+        self.loader.runtime.spawn_blocking(move || {
+            let found = |path: PathBuf| {
+                let info = scan::FileInfo::of(&path);
+                let sent = scan_tx.send(ScanEvent::Found(path, info)).is_ok();
+                ctx.request_repaint();
+                sent
+            };
+            let result = match &source_list {
+                Some(paths) => Ok(scan::scan_list(paths, &options, &cancel, found)),
+                None => scan::scan(&base_dir, &options, &cancel, found),
+            };
+            if cancel.is_cancelled() {
+                return;
+            }
+            let event = match result {
+                Ok(skipped) => ScanEvent::Done(skipped),
+                Err(e) => ScanEvent::Failed(t!(
+                    "folder-unreadable",
+                    path = path_arg(&base_dir),
+                    error = e.to_string()
+                )),
+            };
+            let _ = scan_tx.send(event);
+            ctx.request_repaint();
+        });
+    }
 
-        println!("Found {} images", self.images.len());
+    // Stop a running scan, e.g. before rescanning or switching folders
+    fn cancel_scan(&mut self) {
+        if let Some(cancel) = self.scan_cancel.take() {
+            cancel.cancel();
+        }
+        self.scan_rx = None;
+    }
 
-        if !self.images.is_empty() {
-            self.is_loading = true;
-            self.loading_progress = 0.0;
-            self.total_images_to_load = self.images.len();
-
-            // Start concurrent loading of ALL images
-            let chunk_size = 4; // Load 4 images at a time
-            for chunk in self.images.chunks(chunk_size) {
-                for path in chunk {
-                    if self.textures.contains_key(path) || self.pending_loads.contains(path) {
+    fn poll_scan(&mut self, ctx: &egui::Context) {
+        let Some(scan_rx) = &self.scan_rx else {
+            return;
+        };
+
+        let mut done = None;
+        let mut found = false;
+        loop {
+            match scan_rx.try_recv() {
+                Ok(ScanEvent::Found(path, info)) => {
+                    self.scan_tally.add(&path, info.size);
+                    if self.session.is_kept(&self.base_dir, &path) || self.in_category_folder(&path)
+                    {
                         continue;
                     }
+                    found |= self.images.push_found(path, info);
+                }
+                Ok(ScanEvent::Done(skipped)) => {
+                    done = Some(skipped);
+                    break;
+                }
+                Ok(ScanEvent::Failed(message)) => {
+                    log::error!("{}", message);
+                    self.scan_error = Some(message);
+                    done = Some(scan::Skipped::default());
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    done = Some(scan::Skipped::default());
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
 
-                    println!("Starting to load image: {}", path.display());
-                    self.pending_loads.push(path.clone());
+        if self.big_folder == BigFolder::Fine {
+            self.check_folder_size();
+        }
 
-                    let ctx = ctx.clone();
-                    let tx = self.texture_tx.clone();
-                    let path_clone = path.clone();
+        // Sorting may already have started; begin with whatever has arrived and
+        // let the rest append to the queue
+        if found && self.setup_done && self.current_image.is_none() {
+            self.current_image = Some(self.resume_position().unwrap_or(0));
+            self.start_window_loading(ctx);
+        }
+        // While the categories are still being typed, decode the first few as
+        // they turn up so sorting can start on them straight away
+        if found && !self.setup_done {
+            self.ensure_textures_loaded(0, ctx);
+        }
 
-                    self.loader.runtime.spawn(async move {
-                        if let Some((loaded_path, texture)) =
-                            ImageLoader::load_image(path_clone, ctx.clone()).await
-                        {
-                            println!("Finished loading image: {}", loaded_path.display());
-                            let _ = tx.send((loaded_path, texture));
-                            ctx.request_repaint();
-                        }
-                    });
+        if let Some(skipped) = done {
+            self.scan_rx = None;
+            self.scan_cancel = None;
+            log::info!(
+                "Found {} images in {}, skipped {:?}",
+                self.images.len(),
+                self.base_dir.display(),
+                skipped
+            );
+            if let Some(skipped) = skipped.describe() {
+                self.notifications.info(t!(
+                    "scan-skipped",
+                    count = self.images.len(),
+                    skipped = skipped
+                ));
+            }
+            if self.source_list.is_some() {
+                self.short_names = scan::short_names(self.images.all());
+            }
+            // The last image arrived after sorting started; jump there unless
+            // the user has already got going
+            if self.setup_done {
+                if self.moves.is_empty() && self.current_image == Some(0) {
+                    if let Some(idx) = self.resume_position() {
+                        self.current_image = Some(idx);
+                    }
                 }
+                self.resume_at = None;
+            }
+            if !self.setup_done {
+                self.start_window_loading(ctx);
             }
         }
     }
 
-    fn ensure_textures_loaded(&mut self, current_idx: usize, ctx: &egui::Context) {
-        // Preload current and next few images
-        for idx in current_idx..=(current_idx + 2).min(self.images.len() - 1) {
-            let path = match self.images.get(idx) {
-                // Fixed: use idx instead of current_idx
-                Some(p) => p.clone(),
-                None => continue, // Changed: continue instead of return to process all indices
-            };
-
-            // Don't reload if already loaded or pending
-            if self.textures.contains_key(&path) || self.pending_loads.contains(&path) {
-                continue; // Changed: continue instead of return to process all indices
-            }
+    // Stop decoding and ask before going on once the scan finds more images
+    // than the limit, or one bigger than it
+    fn check_folder_size(&mut self) {
+        let too_many = self.scan_tally.images > self.config.big_folder_images;
+        let too_big = self
+            .scan_tally
+            .largest
+            .as_ref()
+            .is_some_and(|(_, size)| *size > self.config.big_file_mb * 1024 * 1024);
+        if !too_many && !too_big {
+            return;
+        }
+        self.big_folder = BigFolder::Asking;
+        self.stop_decoding();
+    }
 
-            println!("Starting to load image: {}", path.display());
+    // Drop every queued and running decode
+    fn stop_decoding(&mut self) {
+        self.loader.cancel_all();
+        self.uploads.clear();
+        self.priority_load = None;
+        self.is_loading = false;
+    }
 
-            // Add to pending loads
-            self.pending_loads.push(path.clone());
+    fn may_decode(&self) -> bool {
+        matches!(self.big_folder, BigFolder::Fine | BigFolder::Allowed) && !self.loading_paused
+    }
 
-            // Start async load
-            let ctx = ctx.clone();
-            let tx = self.texture_tx.clone();
+    fn draw_big_folder(&mut self, ctx: &egui::Context) {
+        if !matches!(self.big_folder, BigFolder::Asking | BigFolder::Declined) {
+            return;
+        }
+        let limit = self.config.big_file_mb * 1024 * 1024;
+        let largest = self
+            .scan_tally
+            .largest
+            .clone()
+            .filter(|(_, size)| *size > limit);
 
-            self.loader.runtime.spawn(async move {
-                if let Some((loaded_path, texture)) =
-                    ImageLoader::load_image(path, ctx.clone()).await
-                {
-                    println!("Finished loading image: {}", loaded_path.display());
-                    let _ = tx.send((loaded_path, texture));
-                    ctx.request_repaint();
+        let mut answer = None;
+        egui::Window::new(t!("big-folder-title"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(380.0);
+                ui.label(t!(
+                    "big-folder-summary",
+                    count = self.scan_tally.images,
+                    size = format_bytes(self.scan_tally.bytes),
+                    path = path_arg(&self.base_dir)
+                ));
+                if self.scan_rx.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.weak(t!("big-folder-counting"));
+                    });
+                }
+                if let Some((path, size)) = &largest {
+                    ui.label(t!(
+                        "big-folder-largest",
+                        name = self.display_name(path),
+                        size = format_bytes(*size)
+                    ));
                 }
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("continue")).clicked() {
+                        answer = Some(BigFolder::Allowed);
+                    }
+                    if ui
+                        .button(t!("narrow-with-filters"))
+                        .on_hover_text(t!("narrow-with-filters-hint"))
+                        .clicked()
+                    {
+                        self.filter_input.max_mb = self.config.big_file_mb.to_string();
+                        self.apply_filter();
+                        self.show_filter_bar = true;
+                        self.focus_filter = true;
+                        answer = Some(BigFolder::Allowed);
+                    }
+                    if ui
+                        .add_enabled(
+                            self.folder_rx.is_none(),
+                            egui::Button::new(t!("open-folder")),
+                        )
+                        .clicked()
+                    {
+                        answer = Some(BigFolder::Declined);
+                    }
+                });
             });
+
+        match answer {
+            Some(BigFolder::Allowed) if self.big_folder == BigFolder::Declined => {
+                // The scan was stopped, so start it over
+                self.big_folder = BigFolder::Allowed;
+                self.start_background_loading(ctx);
+            }
+            Some(BigFolder::Allowed) => {
+                self.big_folder = BigFolder::Allowed;
+                self.start_window_loading(ctx);
+            }
+            Some(BigFolder::Declined) => {
+                self.big_folder = BigFolder::Declined;
+                self.cancel_scan();
+                self.stop_decoding();
+                self.choose_folder();
+            }
+            _ => {}
+        }
+    }
+
+    // Show only the queued files matching the filter box, staying on the
+    // current image if it still matches
+    fn apply_filter(&mut self) {
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+        self.images.set_filter(self.filter_input.parse());
+        self.current_image = current
+            .and_then(|current| self.images.iter().position(|path| *path == current))
+            .or((!self.images.is_empty()).then_some(0));
+    }
+
+    fn toggle_filter_bar(&mut self) {
+        self.show_filter_bar = !self.show_filter_bar;
+        self.focus_filter = self.show_filter_bar;
+    }
+
+    // Name, modified date and size fields under the top bar. Closing the bar
+    // keeps the filter; Clear drops it
+    fn draw_filter_bar(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("filter_bar").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                let error = ui.visuals().error_fg_color;
+                let mut changed = false;
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.filter_input.name)
+                            .hint_text(t!("filter-hint"))
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text(t!("filter-tooltip"));
+                if std::mem::take(&mut self.focus_filter) {
+                    response.request_focus();
+                }
+                changed |= response.changed();
+
+                ui.separator();
+                ui.label(t!("filter-modified"));
+                for (text, hint) in [
+                    (&mut self.filter_input.modified_from, t!("filter-from")),
+                    (&mut self.filter_input.modified_to, t!("filter-to")),
+                ] {
+                    let valid = text.trim().is_empty() || scan::parse_date(text).is_some();
+                    let mut edit = egui::TextEdit::singleline(text)
+                        .hint_text(hint)
+                        .desired_width(90.0);
+                    if !valid {
+                        edit = edit.text_color(error);
+                    }
+                    changed |= ui.add(edit).on_hover_text(t!("filter-date-hint")).changed();
+                }
+
+                ui.separator();
+                ui.label(t!("filter-size"));
+                for (text, hint) in [
+                    (&mut self.filter_input.min_mb, t!("filter-min")),
+                    (&mut self.filter_input.max_mb, t!("filter-max")),
+                ] {
+                    let valid = text.trim().is_empty() || scan::parse_megabytes(text).is_some();
+                    let mut edit = egui::TextEdit::singleline(text)
+                        .hint_text(hint)
+                        .desired_width(50.0);
+                    if !valid {
+                        edit = edit.text_color(error);
+                    }
+                    changed |= ui.add(edit).changed();
+                }
+                ui.label("MB");
+
+                ui.separator();
+                if ui.button(t!("filter-clear")).clicked() {
+                    self.filter_input = FilterInput::default();
+                    changed = true;
+                }
+                if changed {
+                    self.apply_filter();
+                }
+            });
+            ui.add_space(4.0);
+        });
+    }
+
+    // Queue index of the image this folder was left on, if it's still queued.
+    // Once the scan is over it won't turn up, so it's forgotten either way
+    fn resume_position(&mut self) -> Option<usize> {
+        let path = self.resume_at.as_ref()?;
+        let idx = self.images.iter().position(|image| image == path);
+        if idx.is_some() || self.scan_rx.is_none() {
+            self.resume_at = None;
+        }
+        idx
+    }
+
+    fn start_window_loading(&mut self, ctx: &egui::Context) {
+        if !self.images.is_empty() {
+            let current_idx = self.current_image.unwrap_or(0);
+            let (start, end) = self.load_window(current_idx);
+            self.ensure_textures_loaded(current_idx, ctx);
+
+            // Images decoded during setup may already fill the window
+            self.total_images_to_load = end - start + 1;
+            self.loading_progress =
+                (self.textures.len() as f32 / self.total_images_to_load as f32).min(1.0);
+            self.is_loading = self.textures.len() < self.total_images_to_load;
+        }
+    }
+
+    // Range of queue indices whose textures should be resident around `current_idx`
+    fn load_window(&self, current_idx: usize) -> (usize, usize) {
+        let last = self.images.len().saturating_sub(1);
+        let current_idx = current_idx.min(last);
+        (
+            current_idx.saturating_sub(self.config.keep_behind),
+            (current_idx + self.config.preload_ahead).min(last),
+        )
+    }
+
+    fn request_load(&mut self, path: PathBuf, ctx: &egui::Context, priority: bool) {
+        log::debug!("Starting to load image: {}", path.display());
+
+        // A thumbnail already on its way comes with the full texture now
+        self.thumbnail_loads.remove(&path);
+        let max_pixels = self.decode_limit(&path);
+        self.loader.request(path, priority, max_pixels, ctx);
+    }
+
+    // Decode the images around the current one again, e.g. after the preview
+    // size changes. The old textures stay up until the new ones arrive
+    fn reload_visible(&mut self, ctx: &egui::Context) {
+        let Some(current_idx) = self.current_image else {
+            return;
+        };
+        if self.images.is_empty() {
+            return;
+        }
+        let (start, end) = self.load_window(current_idx);
+        let current = self.images.get(current_idx).cloned();
+        let to_load: Vec<PathBuf> = self
+            .images
+            .iter()
+            .skip(start)
+            .take(end + 1 - start)
+            .filter(|path| self.textures.contains_key(*path) && !self.loader.is_pending(path))
+            .cloned()
+            .collect();
+        for path in to_load {
+            let priority = current.as_ref() == Some(&path);
+            self.request_load(path, ctx, priority);
+        }
+    }
+
+    fn decode_limit(&self, path: &Path) -> Option<u64> {
+        (!self.decode_anyway.contains(path))
+            .then_some(self.config.max_megapixels as u64 * 1_000_000)
+    }
+
+    // The current image jumps ahead of preloads still waiting for a decode slot
+    fn prioritize_current(&mut self, current_idx: usize, ctx: &egui::Context) {
+        if !self.may_decode() {
+            return;
+        }
+        let Some(path) = self.images.get(current_idx).cloned() else {
+            self.priority_load = None;
+            return;
+        };
+        if self.textures.contains_key(&path)
+            || self.oversized.contains_key(&path)
+            || self.undecodable.contains(&path)
+        {
+            self.priority_load = None;
+            return;
+        }
+        if self.priority_load.as_ref().is_some_and(|(p, _)| *p == path) {
+            return;
+        }
+
+        // Moves a waiting request for it to the front
+        self.request_load(path.clone(), ctx, true);
+        self.priority_load = Some((path, Instant::now()));
+    }
+
+    fn cancel_load(&mut self, path: &Path) {
+        self.loader.cancel(path);
+    }
+
+    fn ensure_textures_loaded(&mut self, current_idx: usize, ctx: &egui::Context) {
+        if self.images.is_empty() || !self.may_decode() {
+            return;
+        }
+        let (start, end) = self.load_window(current_idx);
+
+        // Preload current and next few images, then the ones just behind
+        let current_idx = current_idx.min(end);
+        let order = (current_idx..=end).chain((start..current_idx).rev());
+        let mut to_load = Vec::new();
+        for idx in order {
+            let path = &self.images[idx];
+
+            // Don't reload if already loaded, pending or too big
+            if self.textures.contains_key(path)
+                || self.loader.is_pending(path)
+                || self.oversized.contains_key(path)
+                || self.undecodable.contains(path)
+            {
+                continue;
+            }
+            to_load.push(path.clone());
+        }
+        for path in to_load {
+            self.request_load(path, ctx, false);
+        }
+
+        // Evict everything that slid out of the window, except cards still in flight
+        let window: HashSet<&PathBuf> = self
+            .images
+            .iter()
+            .skip(start)
+            .take(end + 1 - start)
+            .collect();
+        let evicted: Vec<PathBuf> = self
+            .textures
+            .keys()
+            .filter(|path| {
+                !window.contains(path) && !self.pending_moves.iter().any(|pm| &pm.from == *path)
+            })
+            .cloned()
+            .collect();
+        // Thumbnails for the buckets are wanted wherever their files are
+        let stale_loads: Vec<PathBuf> = self
+            .loader
+            .pending()
+            .into_iter()
+            .filter(|path| !window.contains(path) && !self.thumbnail_loads.contains(path))
+            .collect();
+
+        for path in evicted {
+            self.drop_textures(&path);
+        }
+        for path in stale_loads {
+            self.cancel_load(&path);
+        }
+    }
+
+    fn revert_last_move(&mut self) {
+        if let Some((path, starred)) = self.favorite_toggles.last().cloned() {
+            let older = |time: Option<Instant>| time.is_none_or(|time| time < starred);
+            if older(self.moves.last().map(|op| op.timestamp))
+                && older(self.merges.last().map(|merge| merge.timestamp))
+            {
+                self.favorite_toggles.pop();
+                self.set_favorite(&path, !self.is_favorite(&path));
+                return;
+            }
+        }
+
+        let merge_is_newer = match (self.merges.last(), self.moves.last()) {
+            (Some(merge), Some(op)) => merge.timestamp > op.timestamp,
+            (merge, _) => merge.is_some(),
+        };
+        if merge_is_newer {
+            self.revert_last_merge();
+            return;
+        }
+
+        if let Some(id) = self.moves.last().map(|op| op.id) {
+            if self.still_moving(id) {
+                return;
+            }
+        }
+        if self.moves.last().is_some_and(is_deleted) {
+            let op = self.moves.pop().unwrap();
+            self.report_deleted(&op);
+            return;
+        }
+        if let Some(last_move) = self.moves.pop() {
+            self.undone.push(last_move.clone());
+            let position = self.current_image.unwrap_or(self.images.len());
+            self.revert_move(&last_move, position);
+
+            let batch = last_move.batch;
+            self.redo_stack.push(last_move);
+
+            // The rest of a batch goes with it
+            if batch.is_some() && self.moves.last().is_some_and(|op| op.batch == batch) {
+                self.revert_last_move();
+            }
+        }
+    }
+
+    // Undo one move from anywhere in the history, putting the image back in
+    // the queue just after the current one
+    fn revert_move_by_id(&mut self, id: u64) {
+        let Some(idx) = self.moves.iter().position(|op| op.id == id) else {
+            return;
+        };
+        if self.still_moving(id) {
+            return;
+        }
+        let op = &self.moves[idx];
+        if is_deleted(op) {
+            let op = self.moves.remove(idx);
+            self.report_deleted(&op);
+            return;
+        }
+        if op.mode == SortMode::Move && op.category.is_some() && op.from.exists() {
+            self.notifications.warn(t!(
+                "revert-blocked",
+                path = path_arg(&op.to),
+                existing = path_arg(&op.from)
+            ));
+            return;
+        }
+
+        let op = self.moves.remove(idx);
+        self.undone.push(op.clone());
+        let position = self
+            .current_image
+            .map_or(self.images.len(), |current| current + 1);
+        self.revert_move(&op, position);
+    }
+
+    // A move that can't be undone because the file was deleted from its
+    // category folder by something else; it leaves the history
+    fn report_deleted(&mut self, op: &MoveOperation) {
+        self.notifications.error(t!(
+            "undo-deleted",
+            path = path_arg(&op.from),
+            category = op.category.as_deref().unwrap_or_default()
+        ));
+    }
+
+    // Put a filed image back in the queue at `position` and take it off its pile
+    fn revert_move(&mut self, op: &MoveOperation, position: usize) {
+        let from_ui = op.from.clone();
+        let to_ui = op.to.clone();
+
+        match &op.category {
+            Some(category) => {
+                if op.mode == SortMode::Move {
+                    self.follow_favorite(&to_ui, &from_ui);
+                }
+                self.run_file_op(FileOp::Unsort(sorter::Move {
+                    from: from_ui.clone(),
+                    to: to_ui.clone(),
+                    category: category.clone(),
+                    mode: op.mode,
+                }))
+            }
+            // Kept files never moved, so just forget they were kept
+            None => self.set_kept(&from_ui, false),
+        }
+        self.sounds.play(Cue::Undo);
+
+        // Update UI state immediately
+        let position = position.min(self.images.len());
+        self.images.insert(position, from_ui.clone());
+        if self.current_image.is_none() {
+            self.current_image = Some(position);
+        }
+
+        // Where the card lies on its pile, for it to fly back from there
+        let card_offset = match &op.category {
+            Some(category) => self.category_buckets.get(category),
+            None => Some(&self.kept_bucket),
+        }
+        .and_then(|bucket| bucket.files.iter().find(|card| card.path == to_ui))
+        .map(|card| card.offset);
+
+        for bucket in self.category_buckets.values_mut() {
+            bucket.remove_file(&to_ui);
+        }
+        self.kept_bucket.remove_file(&to_ui);
+
+        // If the card is still in flight its texture hasn't been rekeyed yet,
+        // so drop the pending move instead of letting it land and move the
+        // texture out from under the restored image. The card turns around
+        // where it is and flies back the way it came. One that landed has its
+        // thumbnail under where it went
+        let landed = self.thumbnails.contains_key(&to_ui);
+        self.follow_thumbnail(&to_ui, &from_ui);
+        if let Some(idx) = self.pending_moves.iter().position(|pm| pm.from == from_ui) {
+            self.pending_moves.remove(idx);
+            for anim in self
+                .animations
+                .iter_mut()
+                .filter(|anim| anim.path == from_ui && !anim.returning)
+            {
+                let elapsed = anim.start_time.elapsed().as_secs_f32().min(anim.duration);
+                let remaining = std::time::Duration::from_secs_f32(anim.duration - elapsed);
+                let now = Instant::now();
+                anim.start_time = now.checked_sub(remaining).unwrap_or(now);
+                anim.end_pos = anim.start_pos;
+                std::mem::swap(&mut anim.start_scale, &mut anim.end_scale);
+                anim.returning = true;
+            }
+        } else if landed {
+            // The card flies back to the center, where the image shows once
+            // it lands. Input carries on meanwhile, as while sorting
+            if let (Some(card_offset), Some(center), false) =
+                (card_offset, self.last_image_pos, self.config.reduce_motion)
+            {
+                self.animations.push(Animation {
+                    path: from_ui,
+                    bucket: op.category.clone(),
+                    start_pos: center,
+                    end_pos: center,
+                    start_time: Instant::now(),
+                    duration: self.config.animation_duration,
+                    start_scale: 0.6,
+                    end_scale: 1.2,
+                    easing: self.config.easing,
+                    returning: true,
+                    card_offset,
+                });
+            }
+        }
+    }
+
+    fn redo_last_move(&mut self, center_pos: egui::Pos2, ctx: &egui::Context) {
+        let Some(redo) = self.redo_stack.pop() else {
+            return;
+        };
+        let Some(idx) = self.images.iter().position(|path| *path == redo.from) else {
+            return;
+        };
+
+        self.current_batch = redo.batch;
+        match redo.category {
+            Some(category) => {
+                if let Some(direction) = self.categories.iter().position(|c| *c == category) {
+                    self.current_image = Some(idx);
+                    self.move_image(direction, center_pos, ctx);
+                }
+            }
+            None => {
+                self.current_image = Some(idx);
+                self.file_image(None, center_pos, ctx);
+            }
+        }
+        self.current_batch = None;
+
+        if redo.batch.is_some()
+            && self
+                .redo_stack
+                .last()
+                .is_some_and(|op| op.batch == redo.batch)
+        {
+            self.redo_last_move(center_pos, ctx);
+        }
+    }
+
+    // File the next `count` images into the category at `idx`, one card at a time
+    fn start_batch(&mut self, idx: usize, count: usize) {
+        self.last_batch = count;
+        self.batches += 1;
+        self.batch = Some(BatchRun {
+            category: idx,
+            remaining: count.min(self.images.len()),
+            id: self.batches,
+            next_at: Instant::now(),
+            failures: self.failures.load(Ordering::SeqCst),
+        });
+    }
+
+    fn advance_batch(&mut self, center_pos: egui::Pos2, ctx: &egui::Context) {
+        let Some(batch) = &mut self.batch else {
+            return;
+        };
+        if self.paused {
+            return;
+        }
+        // Stop at the first failed move rather than carry on filing images
+        // whose predecessor is stuck
+        if self.failures.load(Ordering::SeqCst) > batch.failures {
+            let done = self
+                .moves
+                .iter()
+                .filter(|op| op.batch == Some(batch.id))
+                .count();
+            self.notifications.warn(t!("batch-stopped", count = done));
+            self.batch = None;
+            return;
+        }
+        if batch.remaining == 0 || self.current_image.is_none() {
+            self.batch = None;
+            return;
+        }
+        let wait = batch.next_at.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            ctx.request_repaint_after(wait);
+            return;
+        }
+
+        let category = batch.category;
+        if self.hold_for_confirmation(category, center_pos) {
+            return;
+        }
+        let Some(batch) = &mut self.batch else {
+            return;
+        };
+        batch.remaining -= 1;
+        batch.next_at = Instant::now() + BATCH_STAGGER;
+        let id = batch.id;
+        self.current_batch = Some(id);
+        self.move_image(category, center_pos, ctx);
+        self.current_batch = None;
+        ctx.request_repaint();
+    }
+
+    // Carry out `op` in the background, or only note it down during a dry run
+    fn run_file_op(&mut self, op: FileOp) {
+        self.run_tracked_file_op(op, None);
+    }
+
+    // `run_file_op` for the move with id `move_id` into `category`: its bucket
+    // shows it's busy, and undoing it waits, until the file has landed
+    fn run_move_op(&mut self, op: FileOp, move_id: u64, category: String) {
+        self.run_tracked_file_op(op, Some((move_id, category)));
+    }
+
+    fn run_tracked_file_op(&mut self, op: FileOp, tracked: Option<(u64, String)>) {
+        if self.config.dry_run {
+            // Undoing a step that was only planned just forgets it
+            match self.planned.iter().rposition(|planned| op.undoes(planned)) {
+                Some(idx) => {
+                    self.planned.remove(idx);
+                }
+                None => self.planned.push(op),
+            }
+            return;
+        }
+
+        let task = self.file_op_task(op, tracked);
+        self.spawn_file_task(async move {
+            task.await;
+        });
+    }
+
+    // Get `op` ready to run: the window notes what it's doing now, and the
+    // returned task does it, reporting any failure. Resolves to whether it
+    // worked
+    fn file_op_task(
+        &mut self,
+        op: FileOp,
+        tracked: Option<(u64, String)>,
+    ) -> impl std::future::Future<Output = bool> + Send + 'static {
+        let notifier = self.notifications.notifier();
+        let sounds = self.sounds.clone();
+        let failures = self.failures.clone();
+        let disk_full = self.disk_full.clone();
+        let done = tracked.map(|(id, category)| {
+            self.moving.insert(id, (category, Instant::now()));
+            (id, self.move_done_tx.clone())
+        });
+        let own_changes = self.own_changes.clone();
+        let paths = op.paths();
+        own_changes.start(paths.iter().map(PathBuf::as_path));
+        // Written down before the task can start, so a move the window shows
+        // is finished next time if the app dies first
+        let journal = self.journal.clone();
+        let entry = op.rename().map(|(from, to)| journal.begin(from, to));
+        async move {
+            let description = op.describe();
+            let result = op.run().await;
+            if let Some(id) = entry {
+                journal.finish(id);
+            }
+            own_changes.finish(paths.iter().map(PathBuf::as_path));
+            let ok = result.is_ok();
+            if let Err(e) = result {
+                failures.fetch_add(1, Ordering::SeqCst);
+                sounds.play(Cue::Error);
+                if disk_space::is_disk_full(&e) {
+                    disk_full.store(true, Ordering::SeqCst);
+                    notifier.error(t!("disk-full", operation = description.as_str()));
+                } else {
+                    notifier.error(t!(
+                        "op-failed",
+                        operation = description.as_str(),
+                        error = e.to_string()
+                    ));
+                }
+            }
+            if let Some((id, tx)) = done {
+                let _ = tx.send(id);
+            }
+            ok
+        }
+    }
+
+    fn poll_moves_done(&mut self, ctx: &egui::Context) {
+        for id in self.move_done_rx.try_iter() {
+            self.moving.remove(&id);
+        }
+        if !self.moving.is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+
+    // Whether files are still on their way into any of `categories`
+    fn moving_into(&self, categories: &[&str]) -> bool {
+        self.moving
+            .values()
+            .any(|(category, _)| categories.contains(&category.as_str()))
+    }
+
+    // Whether the category folders are being reorganized, or will be once
+    // the moves into them have landed
+    fn reorganizing(&self) -> bool {
+        self.rename_rx.is_some()
+            || self.held_rename.is_some()
+            || self.merge_rx.is_some()
+            || self.held_merge.is_some()
+    }
+
+    // Whether the move with id `move_id` is still under way, saying so if it is
+    fn still_moving(&mut self, move_id: u64) -> bool {
+        let Some(op) = self.moves.iter().find(|op| op.id == move_id) else {
+            return false;
+        };
+        // A link being turned into a move counts as moving too
+        let replacing = self.link_commit.is_some() && op.mode.is_link();
+        if !self.moving.contains_key(&move_id) && !replacing {
+            return false;
+        }
+        self.notifications
+            .info(t!("still-moving", name = self.display_name(&op.from)));
+        true
+    }
+
+    // Block until every file operation started so far has finished, so what's
+    // on disk matches what the window shows. Whether they all did in time
+    fn wait_for_pending_moves(&self, timeout: std::time::Duration) -> bool {
+        self.in_flight.wait(timeout)
+    }
+
+    fn spawn_file_task(&self, task: impl std::future::Future<Output = ()> + Send + 'static) {
+        let in_flight = self.in_flight.start();
+        self.loader.runtime.spawn(async move {
+            task.await;
+            drop(in_flight);
+        });
+    }
+
+    // Work out every queued image's date folder in the background; the moves
+    // happen in `poll_date_sort` once all dates are known
+    fn auto_sort_by_date(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        self.date_sort_rx = Some(rx);
+        let images = self.images.to_vec();
+        let layout = self.config.date_layout;
+        let ctx = ctx.clone();
+        self.loader.runtime.spawn_blocking(move || {
+            let dated: Vec<(PathBuf, PathBuf, bool)> = images
+                .into_iter()
+                .filter_map(|path| {
+                    let (date, from_exif) = capture_date::capture_date(&path)?;
+                    Some((path, layout.folder(date), from_exif))
+                })
+                .collect();
+            let _ = tx.send(dated);
+            ctx.request_repaint();
+        });
+    }
+
+    // Fingerprint every queued image in the background so `poll_grouping` can
+    // put lookalikes taken close together next to each other
+    fn group_similar(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        self.group_rx = Some(rx);
+        let images = self.images.to_vec();
+        let cache = self.loader.cache.clone();
+        let max_pixels = Some(self.config.max_megapixels as u64 * 1_000_000);
+        let variant = self.loader.preview().cache_variant();
+        let ctx = ctx.clone();
+        self.loader.runtime.spawn_blocking(move || {
+            let fingerprints = images
+                .into_iter()
+                .map(|path| {
+                    let image = cache
+                        .as_ref()
+                        .and_then(|cache| cache.load(&path, &variant))
+                        .or_else(|| loader::decode(&path, max_pixels).ok());
+                    similarity::Fingerprint {
+                        hash: image.as_ref().map(similarity::dhash),
+                        time: capture_date::capture_time(&path).map(|(time, _)| time),
+                        path,
+                    }
+                })
+                .collect();
+            let _ = tx.send(similarity::group(fingerprints));
+            ctx.request_repaint();
+        });
+    }
+
+    fn poll_grouping(&mut self) {
+        let Some(rx) = &self.group_rx else {
+            return;
+        };
+        let groups = match rx.try_recv() {
+            Ok(groups) => groups,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.group_rx = None;
+
+        // Images sorted meanwhile are gone from the queue, and any found
+        // meanwhile go at the end. Textures and loads are keyed by path, so
+        // only the current index has to follow the reorder
+        let queued: HashSet<&PathBuf> = self.images.iter().collect();
+        let mut order: Vec<PathBuf> = groups
+            .iter()
+            .flatten()
+            .filter(|path| queued.contains(path))
+            .cloned()
+            .collect();
+        let placed: HashSet<&PathBuf> = groups.iter().flatten().collect();
+        order.extend(
+            self.images
+                .iter()
+                .filter(|path| !placed.contains(path))
+                .cloned(),
+        );
+
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+        self.images.reorder(&order);
+        if let Some(current) = current {
+            self.current_image = self.images.iter().position(|path| *path == current);
+        }
+
+        self.groups.clear();
+        let mut grouped = 0;
+        let mut count = 0;
+        for group in groups.iter().filter(|group| group.len() > 1) {
+            for (idx, path) in group.iter().enumerate() {
+                self.groups.insert(path.clone(), (idx + 1, group.len()));
+            }
+            grouped += group.len();
+            count += 1;
+        }
+        self.notifications
+            .info(t!("grouped-similar", count = grouped, groups = count));
+    }
+
+    fn poll_date_sort(&mut self) {
+        let Some(rx) = &self.date_sort_rx else {
+            return;
+        };
+        let dated = match rx.try_recv() {
+            Ok(dated) => dated,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.date_sort_rx = None;
+
+        let mut taken: HashSet<PathBuf> =
+            self.pending_moves.iter().map(|pm| pm.to.clone()).collect();
+        let policy = self.collision_policy();
+        let mode = self.config.sort_mode;
+        let mut sorted = 0;
+        let mut from_mtime = 0;
+        for (from, folder, from_exif) in dated {
+            // Sorted by hand while the dates were being read
+            let Some(idx) = self.images.iter().position(|path| *path == from) else {
+                continue;
+            };
+            let Some(name) = from.file_name() else {
+                continue;
+            };
+            // The folder itself is made by the move, once it runs
+            let dir = self.base_dir.join(&folder);
+            let to = match sorter::resolve(&dir, name, policy, &taken) {
+                Resolution::To(to) => to,
+                // No one is asked in the middle of a batch; the image stays
+                // queued to be sorted by hand
+                Resolution::Skip(existing) | Resolution::Ask(existing) => {
+                    self.notifications.warn(t!(
+                        "collision-skipped",
+                        path = path_arg(&from),
+                        existing = path_arg(&existing)
+                    ));
+                    continue;
+                }
+            };
+            taken.insert(to.clone());
+            let category = folder.to_string_lossy().to_string();
+
+            self.images.remove(idx);
+            self.cancel_load(&from);
+            self.drop_textures(&from);
+            self.run_file_op(FileOp::Sort(sorter::Move {
+                from: from.clone(),
+                to: to.clone(),
+                category: category.clone(),
+                mode,
+            }));
+            self.next_move_id += 1;
+            self.moves.push(MoveOperation {
+                id: self.next_move_id,
+                from,
+                to,
+                category: Some(category),
+                mode,
+                timestamp: Instant::now(),
+                time: SystemTime::now(),
+                batch: None,
+            });
+            sorted += 1;
+            if !from_exif {
+                from_mtime += 1;
+            }
+        }
+
+        self.redo_stack.clear();
+        self.current_image = if self.images.is_empty() {
+            None
+        } else {
+            Some(self.current_image.unwrap_or(0).min(self.images.len() - 1))
+        };
+        self.notifications.info(t!(
+            "sorted-by-date",
+            count = sorted,
+            by_file_date = from_mtime
+        ));
+    }
+
+    // Carry out everything recorded during the dry run, in order
+    fn commit_planned(&mut self) {
+        let planned = std::mem::take(&mut self.planned);
+        let count = planned.len();
+        let tasks: Vec<_> = planned
+            .into_iter()
+            .map(|op| self.file_op_task(op, None))
+            .collect();
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            let mut applied = 0;
+            for task in tasks {
+                if task.await {
+                    applied += 1;
+                }
+            }
+            notifier.info(t!("planned-applied", applied = applied, count = count));
+        });
+    }
+
+    // Linked files that have landed, as (move id, link, original, mode)
+    fn committable_links(&self) -> Vec<(u64, PathBuf, PathBuf, SortMode)> {
+        self.moves
+            .iter()
+            .filter(|op| op.mode.is_link() && op.category.is_some())
+            .filter(|op| {
+                !self.moving.contains_key(&op.id)
+                    && !self.pending_moves.iter().any(|pm| pm.to == op.to)
+            })
+            .map(|op| (op.id, op.to.clone(), op.from.clone(), op.mode))
+            .collect()
+    }
+
+    // Replace every link made this session with the file it points to, so
+    // the originals leave the source folder after all
+    fn commit_links(&mut self) {
+        let links = self.committable_links();
+        if links.is_empty() || self.link_commit.is_some() {
+            return;
+        }
+        let (tx, rx) = channel();
+        self.link_commit = Some(LinkCommit {
+            rx,
+            total: links.len(),
+            done: 0,
+            failed: 0,
+        });
+        self.spawn_file_task(async move {
+            for (id, link, original, mode) in links {
+                let result = replace_link(&original, &link, mode).await;
+                let _ = tx.send((id, result));
+            }
+        });
+    }
+
+    fn poll_link_commit(&mut self, ctx: &egui::Context) {
+        let Some(commit) = &mut self.link_commit else {
+            return;
+        };
+        let results: Vec<_> = commit.rx.try_iter().collect();
+        commit.done += results.len();
+        for (id, result) in results {
+            let Some(op) = self.moves.iter_mut().find(|op| op.id == id) else {
+                continue;
+            };
+            match result {
+                // Undone from here on like any other move
+                Ok(()) => op.mode = SortMode::Move,
+                Err(e) => {
+                    commit.failed += 1;
+                    self.notifications.error(t!(
+                        "op-failed",
+                        operation = t!(
+                            "op-replace-link",
+                            link = path_arg(&op.to),
+                            original = path_arg(&op.from)
+                        ),
+                        error = e.to_string()
+                    ));
+                }
+            }
+        }
+        if commit.done < commit.total {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        self.notifications.info(t!(
+            "links-committed",
+            applied = commit.total - commit.failed,
+            count = commit.total
+        ));
+        self.link_commit = None;
+    }
+
+    fn draw_link_commit(&self, ctx: &egui::Context) {
+        let Some(commit) = &self.link_commit else {
+            return;
+        };
+        egui::Window::new(t!("commit-links"))
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -40.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::ProgressBar::new(commit.done as f32 / commit.total as f32).text(t!(
+                        "committing-links",
+                        done = commit.done,
+                        count = commit.total
+                    )),
+                );
+            });
+    }
+
+    // Write every tag from this session to a CSV in the source folder
+    fn export_tags(&self) {
+        let mut csv = String::from("file,tag\n");
+        for op in &self.moves {
+            if let (Some(category), SortMode::Tag) = (&op.category, op.mode) {
+                let file = op.from.strip_prefix(&self.base_dir).unwrap_or(&op.from);
+                csv.push_str(&format!(
+                    "{},{}\n",
+                    report::csv_field(&file.to_string_lossy()),
+                    report::csv_field(category)
+                ));
+            }
+        }
+
+        let path = self.base_dir.join("leftright-tags.csv");
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            match tokio::fs::write(&path, csv).await {
+                Ok(()) => notifier.info(t!("tags-exported", path = path_arg(&path))),
+                Err(e) => notifier.error(t!("tags-export-failed", error = e.to_string())),
+            }
+        });
+    }
+
+    fn set_kept(&mut self, path: &Path, kept: bool) {
+        let entry = Session::entry(&self.base_dir, path);
+        if kept {
+            self.session.kept.insert(entry);
+        } else {
+            self.session.kept.remove(&entry);
+        }
+        self.save_session();
+    }
+
+    fn is_favorite(&self, path: &Path) -> bool {
+        self.session.is_favorite(&self.base_dir, path)
+    }
+
+    fn set_favorite(&mut self, path: &Path, favorite: bool) {
+        let entry = Session::entry(&self.base_dir, path);
+        if favorite {
+            self.session.favorites.insert(entry);
+        } else {
+            self.session.favorites.remove(&entry);
+        }
+        self.save_session();
+    }
+
+    // Star or unstar the current image without filing it
+    fn toggle_favorite(&mut self) {
+        let Some(path) = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned()
+        else {
+            return;
+        };
+        self.set_favorite(&path, !self.is_favorite(&path));
+        self.favorite_toggles.push((path, Instant::now()));
+    }
+
+    // A file moved from `from` to `to`; its thumbnail goes with it, uploaded
+    // or not
+    fn follow_thumbnail(&mut self, from: &Path, to: &Path) {
+        if let Some(thumbnail) = self.thumbnails.remove(from) {
+            self.thumbnails.insert(to.to_path_buf(), thumbnail);
+        }
+        if let Some(thumbnail) = self.thumbnail_images.remove(from) {
+            self.thumbnail_images.insert(to.to_path_buf(), thumbnail);
+        }
+    }
+
+    // Everything decoded for `path`, once it's gone from the queue
+    fn drop_textures(&mut self, path: &Path) {
+        self.textures.remove(path);
+        self.thumbnails.remove(path);
+        self.thumbnail_images.remove(path);
+    }
+
+    // A file moved from `from` to `to`; its star goes with it
+    fn follow_favorite(&mut self, from: &Path, to: &Path) {
+        for (path, _) in &mut self.favorite_toggles {
+            if path == from {
+                *path = to.to_path_buf();
+            }
+        }
+        if self
+            .session
+            .favorites
+            .remove(&Session::entry(&self.base_dir, from))
+        {
+            self.set_favorite(to, true);
+        }
+    }
+
+    // Starred files that still exist, by name
+    fn favorites(&self) -> Vec<PathBuf> {
+        let mut favorites: Vec<PathBuf> = self
+            .session
+            .favorites
+            .iter()
+            .map(|entry| self.base_dir.join(entry))
+            .filter(|path| path.exists())
+            .collect();
+        favorites.sort_by_key(|path| path.file_name().map(|name| name.to_os_string()));
+        favorites
+    }
+
+    fn copy_favorites(&self) {
+        let favorites = self.favorites();
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("copy-favorites-to"))
+            .set_directory(&self.base_dir);
+        let notifier = self.notifications.notifier();
+        self.loader.runtime.spawn(async move {
+            let Some(folder) = dialog.pick_folder().await else {
+                return;
+            };
+            let dir = folder.path().to_path_buf();
+            let mut taken = HashSet::new();
+            let mut copied = 0;
+            for path in favorites {
+                let Some(name) = path.file_name() else {
+                    continue;
+                };
+                let to = unique_destination(&dir, name, &taken);
+                match tokio::fs::copy(&path, &to).await {
+                    Ok(_) => copied += 1,
+                    Err(e) => notifier.error(t!(
+                        "copy-failed",
+                        path = path_arg(&path),
+                        error = e.to_string()
+                    )),
+                }
+                taken.insert(to);
+            }
+            notifier.info(t!(
+                "favorites-copied",
+                count = copied,
+                path = path_arg(&dir)
+            ));
+        });
+    }
+
+    fn save_session(&self) {
+        if let Err(e) = self.session.save(&self.base_dir) {
+            self.notifications
+                .error(t!("session-save-failed", error = e.to_string()));
+        }
+    }
+
+    fn save_config(&self) {
+        if let Err(e) = self.config.save() {
+            self.notifications
+                .error(t!("settings-save-failed", error = e.to_string()));
+        }
+    }
+
+    // Drop moves that can no longer be undone; the report still lists them as undone
+    fn forget_moves(&mut self, forget: impl Fn(&MoveOperation) -> bool) {
+        let (forgotten, moves): (Vec<_>, Vec<_>) = std::mem::take(&mut self.moves)
+            .into_iter()
+            .partition(forget);
+        self.moves = moves;
+        self.undone.extend(forgotten);
+    }
+
+    // Ask how wide to make the picture of the board before saving it
+    fn draw_board_export(&mut self, ctx: &egui::Context) {
+        let Some(mut width) = self.board_export.take() else {
+            return;
+        };
+        let screen = ctx.screen_rect().size() + egui::vec2(0.0, BOARD_FOOTER);
+        let mut save = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("export-board-title"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t!("export-board-width"));
+                    ui.add(
+                        egui::DragValue::new(&mut width)
+                            .clamp_range(320..=16_384)
+                            .suffix(" px"),
+                    );
+                    let height = (width as f32 * screen.y / screen.x).round();
+                    ui.weak(format!("× {} px", height));
+                });
+                ui.horizontal(|ui| {
+                    save = ui.button(t!("save")).clicked();
+                    cancelled = ui.button(t!("cancel")).clicked();
+                });
+                cancelled |= ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
+
+        if save {
+            self.export_board(ctx, width);
+        } else if !cancelled {
+            self.board_export = Some(width);
+        }
+    }
+
+    // Save a picture of the board as it is now, `width` pixels wide, with the
+    // session's numbers underneath. The buckets are drawn as in the window;
+    // the center image is decoded again from its file. Drawing and encoding
+    // happen in the background
+    fn export_board(&self, ctx: &egui::Context, width: u32) {
+        let screen = ctx.screen_rect();
+        let palette = self.palette();
+        let visuals = ctx.style().visuals.clone();
+        let kept = self.moves.iter().filter(|op| op.category.is_none()).count();
+        let buckets = self
+            .categories
+            .iter()
+            .enumerate()
+            .filter_map(|(i, category)| {
+                let bucket = self.category_buckets.get(category)?;
+                let hint = self.key_hint(ctx, Action::Category(i));
+                Some((
+                    bucket.rect,
+                    bucket.color,
+                    bucket_label(&hint, Some(category), bucket),
+                ))
+            })
+            .chain(std::iter::once((
+                self.kept_bucket.rect,
+                self.kept_bucket.color,
+                bucket_label(&self.key_hint(ctx, Action::Keep), None, &self.kept_bucket),
+            )))
+            .filter(|(rect, _, _)| rect.is_positive())
+            .collect();
+        let mut status = vec![(
+            t!("images-left", count = self.images.len()),
+            visuals.text_color(),
+        )];
+        for (name, color, count) in self.bucket_counts(ctx) {
+            status.push((name, color));
+            status.push((count.to_string(), visuals.text_color()));
+        }
+        let snapshot = BoardSnapshot {
+            size: screen.size() + egui::vec2(0.0, BOARD_FOOTER),
+            palette,
+            panel: visuals.panel_fill,
+            weak_text: visuals.weak_text_color(),
+            image: self
+                .shown
+                .as_ref()
+                .map(|(path, _, rect, _)| (path.clone(), rect.translate(-screen.min.to_vec2()))),
+            max_pixels: Some(self.config.max_megapixels as u64 * 1_000_000),
+            buckets,
+            label_size: 16.0
+                * layout::ui_scale(screen.size())
+                * accessibility::text_scale(&ctx.style()),
+            status,
+            footer: t!(
+                "board-footer",
+                moved = self.moves.len() - kept,
+                kept = kept,
+                left = self.images.len(),
+                time = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+            ),
+        };
+        let scale = width as f32 / screen.width();
+
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("export-board-title"))
+            .set_directory(&self.base_dir)
+            .set_file_name("leftright-board.png")
+            .add_filter("PNG", &["png"]);
+        let notifier = self.notifications.notifier();
+        self.loader.runtime.spawn(async move {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let result = tokio::task::spawn_blocking(move || {
+                let image = render_board(&snapshot, scale);
+                let pixels = image
+                    .pixels
+                    .iter()
+                    .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+                    .collect();
+                let [width, height] = image.size;
+                image::RgbaImage::from_raw(width as u32, height as u32, pixels)
+                    .expect("one RGBA pixel per point of the board")
+                    .save_with_format(&path, image::ImageFormat::Png)
+                    .map(|_| path)
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+            match result {
+                Ok(Ok(path)) => notifier.info(t!("board-exported", path = path_arg(&path))),
+                Ok(Err(e)) => notifier.error(t!("board-export-failed", error = e)),
+                Err(e) => notifier.error(t!("board-export-failed", error = e.to_string())),
+            }
+        });
+    }
+
+    // Ask where to save a report of every move this session, then write it in
+    // the background (CSV or JSON, by extension)
+    fn export_report(&self) {
+        let mut ops: Vec<(&MoveOperation, bool)> = self
+            .moves
+            .iter()
+            .map(|op| (op, false))
+            .chain(self.undone.iter().map(|op| (op, true)))
+            .filter(|(_, undone)| self.config.report_undone || !undone)
+            .collect();
+        ops.sort_by_key(|(op, _)| op.timestamp);
+        let entries: Vec<report::Entry> = ops
+            .into_iter()
+            .map(|(op, undone)| report::Entry {
+                from: op.from.clone(),
+                to: op.to.clone(),
+                category: op.category.clone(),
+                timestamp: op
+                    .time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+                undone,
+            })
+            .collect();
+
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("export-report-title"))
+            .set_directory(&self.base_dir)
+            .set_file_name("leftright-report.csv")
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"]);
+        let notifier = self.notifications.notifier();
+        self.loader.runtime.spawn(async move {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let result =
+                tokio::task::spawn_blocking(move || report::write(&path, &entries).map(|_| path))
+                    .await;
+            match result {
+                Ok(Ok(path)) => notifier.info(t!("report-exported", path = path_arg(&path))),
+                Ok(Err(e)) => notifier.error(t!("report-export-failed", error = e.to_string())),
+                Err(e) => notifier.error(t!("report-export-failed", error = e.to_string())),
+            }
+        });
+    }
+
+    fn choose_folder(&mut self) {
+        let (tx, rx) = channel();
+        self.folder_rx = Some(rx);
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("open-folder-title"))
+            .set_directory(&self.base_dir);
+        self.loader.runtime.spawn(async move {
+            if let Some(folder) = dialog.pick_folder().await {
+                let _ = tx.send(folder.path().to_path_buf());
+            }
+        });
+    }
+
+    fn poll_folder(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.folder_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(dir) => {
+                self.folder_rx = None;
+                self.open_folder(dir, ctx);
+            }
+            Err(TryRecvError::Disconnected) => self.folder_rx = None,
+            Err(TryRecvError::Empty) => {}
+        }
+    }
+
+    // Start over in `dir`, back at the setup screen
+    fn open_folder(&mut self, dir: PathBuf, ctx: &egui::Context) {
+        if self.in_flight.count() > 0 || !self.planned.is_empty() {
+            self.notifications.warn(t!("open-folder-busy"));
+            return;
+        }
+        let dir = match dir.canonicalize() {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.notifications.error(t!(
+                    "folder-open-failed",
+                    path = path_arg(&dir),
+                    error = e.to_string()
+                ));
+                return;
+            }
+        };
+        if let Err(e) = std::env::set_current_dir(&dir) {
+            self.notifications.error(t!(
+                "folder-open-failed",
+                path = path_arg(&dir),
+                error = e.to_string()
+            ));
+            return;
+        }
+        self.cancel_scan();
+        self.loader.cancel_all();
+
+        // The loader, its runtime and the window bits outlive the folder
+        let mut fresh = ImageSorter::new(dir, ctx);
+        std::mem::swap(&mut fresh.loader, &mut self.loader);
+        std::mem::swap(&mut fresh.load_rx, &mut self.load_rx);
+        std::mem::swap(&mut fresh.notifications, &mut self.notifications);
+        std::mem::swap(&mut fresh.taskbar, &mut self.taskbar);
+        std::mem::swap(&mut fresh.gamepad, &mut self.gamepad);
+        std::mem::swap(&mut fresh.sounds, &mut self.sounds);
+        std::mem::swap(&mut fresh.title, &mut self.title);
+        std::mem::swap(&mut fresh.window, &mut self.window);
+        fresh.system_theme = self.system_theme;
+        fresh.show_stats = self.show_stats;
+        fresh.loader.stats.reset();
+        *self = fresh;
+    }
+
+    // Files and folders dropped onto the window from a file manager. A folder
+    // opens like one picked from the menu; images join the end of the queue
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect()
+        });
+        if dropped.is_empty() {
+            return;
+        }
+        if let Some(dir) = dropped.iter().find(|path| path.is_dir()) {
+            self.open_folder(dir.clone(), ctx);
+            return;
+        }
+
+        let options = scan::ScanOptions {
+            extensions: self.config.image_extensions.clone(),
+            detect_by_content: self.config.detect_by_content,
+        };
+        let mut found = Vec::new();
+        let skipped = scan::scan_list(&dropped, &options, &CancellationToken::new(), |path| {
+            found.push(path);
+            true
+        });
+        let mut added = 0;
+        for path in found {
+            if self.images.is_queued(&path) || self.in_category_folder(&path) {
+                continue;
+            }
+            let info = scan::FileInfo::of(&path);
+            if self.images.push_found(path, info) {
+                added += 1;
+            }
+        }
+        match skipped.describe() {
+            Some(skipped) => {
+                self.notifications
+                    .info(t!("scan-skipped", count = added, skipped = skipped))
+            }
+            None if added == 0 => self.notifications.warn(t!("dropped-nothing")),
+            None => self.notifications.info(t!("dropped-images", count = added)),
+        }
+        if added == 0 {
+            return;
+        }
+
+        // Dropped files can come from anywhere, so names may need their folder
+        self.short_names = scan::short_names(self.images.all());
+        if !self.setup_done {
+            self.ensure_textures_loaded(0, ctx);
+        } else if self.current_image.is_none() {
+            self.current_image = Some(self.images.len() - added);
+            self.start_window_loading(ctx);
+        }
+    }
+
+    // Outline the window while files are dragged over it
+    fn draw_drop_target(&self, ctx: &egui::Context) {
+        if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("drop_target"),
+        ));
+        let visuals = ctx.style().visuals.clone();
+        let accent = visuals.selection.stroke.color;
+        let rect = ctx.screen_rect().shrink(6.0);
+        painter.rect(
+            rect,
+            12.0,
+            visuals.extreme_bg_color.gamma_multiply(0.85),
+            egui::Stroke::new(3.0, accent),
+        );
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            t!("drop-hint"),
+            egui::FontId::proportional(20.0),
+            visuals.strong_text_color(),
+        );
+    }
+
+    // Put every kept file back in the queue
+    fn reset_kept(&mut self) {
+        self.session.kept.clear();
+        self.save_session();
+
+        // Undoing a keep would now queue the file twice
+        self.forget_moves(|op| op.category.is_none());
+        self.redo_stack.retain(|op| op.category.is_some());
+        let kept: Vec<PathBuf> = self.kept_bucket.paths().cloned().collect();
+        self.kept_bucket.set_files(Vec::new());
+        for path in kept {
+            if path.exists() && !self.images.contains(&path) {
+                self.images.push(path);
+            }
+        }
+        if self.current_image.is_none() && !self.images.is_empty() {
+            self.current_image = Some(0);
+        }
+    }
+
+    fn previous_image(&mut self) {
+        if let Some(current_idx) = self.current_image {
+            if !self.images.is_empty() {
+                self.current_image =
+                    Some((current_idx + self.images.len() - 1) % self.images.len());
+            }
+        }
+    }
+
+    // Action bound to a mouse button or wheel step this frame. The wheel
+    // zooms instead while over the current image, and fast scrolling is
+    // coalesced into one step per frame, none while cards are flying
+    fn mouse_action(&mut self, ui: &egui::Ui) -> Option<Action> {
+        let (back, forward, scroll, pointer) = ui.input(|i| {
+            (
+                i.pointer.button_pressed(egui::PointerButton::Extra1),
+                i.pointer.button_pressed(egui::PointerButton::Extra2),
+                i.scroll_delta.y,
+                i.pointer.hover_pos(),
+            )
+        });
+
+        let over_image = pointer
+            .zip(self.image_rect)
+            .is_some_and(|(pointer, rect)| rect.contains(pointer));
+        // Scrolling inside a window or popup is that window's business
+        let scroll = match pointer.and_then(|pointer| ui.ctx().layer_id_at(pointer)) {
+            Some(layer) if layer != ui.layer_id() => 0.0,
+            _ => scroll,
+        };
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+        let mut wheel = None;
+        if over_image && scroll != 0.0 {
+            if let Some(path) = current {
+                let zoom = match &self.zoom {
+                    Some((zoomed, zoom)) if *zoomed == path => *zoom,
+                    _ => 1.0,
+                };
+                let zoom = (zoom * (scroll / 200.0).exp()).clamp(1.0, MAX_ZOOM);
+                self.zoom = Some((path, zoom));
+            }
+            self.wheel_delta = 0.0;
+        } else if !self.animations.is_empty() {
+            self.wheel_delta = 0.0;
+        } else {
+            self.wheel_delta += scroll;
+            if self.wheel_delta.abs() >= WHEEL_STEP {
+                wheel = Some(if self.wheel_delta > 0.0 {
+                    MouseInput::WheelUp
+                } else {
+                    MouseInput::WheelDown
+                });
+                self.wheel_delta = 0.0;
+            }
+        }
+
+        let input = if back {
+            Some(MouseInput::Back)
+        } else if forward {
+            Some(MouseInput::Forward)
+        } else {
+            wheel
+        };
+        input.and_then(|input| self.config.key_bindings.mouse.get(&input).copied())
+    }
+
+    fn skip_image(&mut self) {
+        if let Some(current_idx) = self.current_image {
+            if !self.images.is_empty() {
+                self.current_image = Some((current_idx + 1) % self.images.len());
+            }
+        }
+    }
+
+    // Rate the current image in its XMP sidecar and move on to the next one;
+    // None clears the rating
+    fn rate_image(&mut self, rating: Option<u8>) {
+        let Some(path) = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned()
+        else {
+            return;
+        };
+        self.ratings.insert(path.clone(), rating);
+        self.run_file_op(FileOp::Rate { path, rating });
+        self.skip_image();
+    }
+
+    // Pick up sidecar ratings read in the background, and start reading the
+    // current image's if it hasn't been yet
+    fn poll_ratings(&mut self, ctx: &egui::Context) {
+        while let Ok((path, rating)) = self.rating_rx.try_recv() {
+            self.rating_reads.remove(&path);
+            // A rating given while the read was running is newer
+            self.ratings.entry(path).or_insert(rating);
+        }
+
+        if !self.config.rate_with_numbers {
+            return;
+        }
+        let Some(path) = self.current_image.and_then(|idx| self.images.get(idx)) else {
+            return;
+        };
+        if self.ratings.contains_key(path) || !self.rating_reads.insert(path.clone()) {
+            return;
+        }
+        let path = path.clone();
+        let tx = self.rating_tx.clone();
+        let ctx = ctx.clone();
+        self.loader.runtime.spawn_blocking(move || {
+            let rating = xmp::rating(&path);
+            let _ = tx.send((path, rating));
+            ctx.request_repaint();
+        });
+    }
+
+    // Step through the pages of a multi-page TIFF without sorting it. Pages
+    // past the first are decoded on demand; the one on screen stays until
+    // the next is ready
+    fn turn_page(&mut self, forward: bool, ctx: &egui::Context) {
+        let Some(path) = self.current_image.and_then(|idx| self.images.get(idx)) else {
+            return;
+        };
+        let Some(&pages) = self.page_counts.get(path) else {
+            return;
+        };
+        let (page, texture) = match self.page.take() {
+            Some((paged, page, texture)) if &paged == path => (page, texture),
+            _ => (0, None),
+        };
+        let page = if forward {
+            (page + 1).min(pages - 1)
+        } else {
+            page.saturating_sub(1)
+        };
+        if page == 0 {
+            return;
+        }
+        let path = path.clone();
+        self.page = Some((path.clone(), page, texture));
+        self.loader.load_page(path, page, ctx, self.page_tx.clone());
+    }
+
+    fn poll_pages(&mut self, ctx: &egui::Context) {
+        while let Ok((path, page, image)) = self.page_rx.try_recv() {
+            // Pages turned past, or of an image that's been sorted, are dropped
+            let Some((paged, wanted, texture)) = &mut self.page else {
+                continue;
+            };
+            if *paged != path || *wanted != page {
+                continue;
+            }
+            match image {
+                Some(image) => {
+                    let name = format!("{} (page {})", path.to_string_lossy(), page + 1);
+                    *texture = Some(loader::upload(ctx, name, image));
+                }
+                None => {
+                    self.page = None;
+                    self.notifications.error(t!(
+                        "page-decode-failed",
+                        page = page + 1,
+                        name = self.display_name(&path)
+                    ));
+                }
+            }
+        }
+    }
+
+    fn delete_image(&mut self) {
+        let Some(current_idx) = self.current_image else {
+            return;
+        };
+        if current_idx >= self.images.len() {
+            return;
+        }
+
+        let path = self.images.remove(current_idx);
+        self.cancel_load(&path);
+        self.drop_textures(&path);
+        if !self.images.is_empty() {
+            self.current_image = Some(current_idx.min(self.images.len() - 1));
+        } else {
+            self.current_image = None;
+        }
+
+        // Trash rather than unlink so a mistaken delete can be recovered from the OS
+        let notifier = self.notifications.notifier();
+        let in_flight = self.in_flight.start();
+        self.loader.runtime.spawn_blocking(move || {
+            let _in_flight = in_flight;
+            if let Err(e) = trash::delete(&path) {
+                notifier.error(t!(
+                    "trash-failed",
+                    path = path_arg(&path),
+                    error = e.to_string()
+                ));
+            }
+        });
+    }
+
+    fn palette(&self) -> Palette {
+        let [r, g, b] = self.config.background_color;
+        Palette::new(self.theme(), egui::Color32::from_rgb(r, g, b))
+    }
+
+    fn central_frame(&self, ctx: &egui::Context) -> egui::Frame {
+        egui::Frame::central_panel(&ctx.style()).fill(self.palette().background)
+    }
+
+    fn apply_theme(&self, ctx: &egui::Context) {
+        let [r, g, b] = self.config.background_color;
+        theme::apply(ctx, self.theme(), egui::Color32::from_rgb(r, g, b));
+    }
+
+    fn theme(&self) -> Theme {
+        self.config.theme.resolve(self.system_theme)
+    }
+
+    // Follow the OS switching between light and dark while running
+    fn follow_system_theme(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let system_theme = frame.info().system_theme;
+        if system_theme != self.system_theme {
+            self.system_theme = system_theme;
+            if self.config.theme == Theme::System {
+                self.apply_theme(ctx);
+            }
+        }
+    }
+
+    fn key_hint(&self, ctx: &egui::Context, action: Action) -> String {
+        let key = match action {
+            Action::Category(idx) => self.category_hint(ctx, idx, &self.categories),
+            _ => self
+                .config
+                .key_bindings
+                .get(action)
+                .map(|binding| ctx.format_shortcut(&binding.shortcut()))
+                .unwrap_or_else(|| "—".to_string()),
+        };
+        match self.config.key_bindings.mouse_input(action) {
+            Some(input) => format!("{} / {}", key, input.label()),
+            None => key,
+        }
+    }
+
+    // Direct key for the category at `idx`, or its chord if it has none
+    fn category_hint(&self, ctx: &egui::Context, idx: usize, categories: &[String]) -> String {
+        let bindings = &self.config.key_bindings;
+        if let Some(binding) = bindings.get(Action::Category(idx)) {
+            return ctx.format_shortcut(&binding.shortcut());
+        }
+        match chord_letters(categories).get(idx) {
+            Some(letter) => format!(
+                "{} {}",
+                ctx.format_shortcut(&bindings.chord.shortcut()),
+                letter.to_ascii_uppercase()
+            ),
+            None => "—".to_string(),
+        }
+    }
+
+    // Indices of the categories shown in the chord picker for `filter`
+    fn chord_matches(&self, filter: &str) -> Vec<usize> {
+        self.categories
+            .iter()
+            .enumerate()
+            .filter(|(_, category)| fuzzy_match(filter, category))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    // Advance the chord state machine with this frame's input, returning the
+    // category picked, if any
+    fn handle_chord_input(&mut self, ctx: &egui::Context) -> Option<usize> {
+        // Don't leave a chord half-typed when the window loses focus
+        if !ctx.input(|i| i.focused) {
+            self.input_mode = InputMode::Direct;
+            return None;
+        }
+
+        let (texts, escape, enter, backspace, up, down) = ctx.input_mut(|i| {
+            let texts: Vec<String> = i
+                .events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Text(text) => Some(text.clone()),
+                    _ => None,
+                })
+                .collect();
+            (
+                texts,
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            )
+        });
+
+        if escape {
+            self.input_mode = InputMode::Direct;
+            return None;
+        }
+
+        let letters = chord_letters(&self.categories);
+        for c in texts.iter().flat_map(|text| text.chars()) {
+            match &mut self.input_mode {
+                InputMode::Chord if c == '/' => {
+                    self.input_mode = InputMode::ChordSearch(String::new());
+                }
+                InputMode::Chord => {
+                    let c = c.to_ascii_lowercase();
+                    if let Some(idx) = letters.iter().position(|letter| *letter == c) {
+                        self.input_mode = InputMode::Direct;
+                        return Some(idx);
+                    }
+                }
+                InputMode::ChordSearch(filter) => {
+                    filter.push(c);
+                    let filter = filter.clone();
+                    if let [idx] = self.chord_matches(&filter)[..] {
+                        self.input_mode = InputMode::Direct;
+                        return Some(idx);
+                    }
+                }
+                InputMode::Palette { filter, selected } => {
+                    filter.push(c);
+                    *selected = 0;
+                }
+                InputMode::Direct => {}
+            }
+        }
+
+        if let InputMode::Palette { filter, selected } = &mut self.input_mode {
+            if backspace {
+                filter.pop();
+                *selected = 0;
+            }
+            let matches: Vec<usize> = self
+                .categories
+                .iter()
+                .enumerate()
+                .filter(|(_, category)| fuzzy_match(filter, category))
+                .map(|(idx, _)| idx)
+                .collect();
+            if up {
+                *selected = selected.saturating_sub(1);
+            }
+            if down {
+                *selected += 1;
+            }
+            *selected = (*selected).min(matches.len().saturating_sub(1));
+            // With nothing matching the palette stays open to fix the typing
+            if enter && !matches.is_empty() {
+                let idx = matches[*selected];
+                self.input_mode = InputMode::Direct;
+                return Some(idx);
+            }
+            return None;
+        }
+
+        if let InputMode::ChordSearch(filter) = &mut self.input_mode {
+            if backspace {
+                filter.pop();
+            }
+            if enter {
+                let filter = filter.clone();
+                self.input_mode = InputMode::Direct;
+                return self.chord_matches(&filter).first().copied();
+            }
+        }
+        None
+    }
+
+    fn action_label(&self, action: Action) -> String {
+        match action {
+            Action::Category(idx) => match self.categories.get(idx) {
+                Some(category) => category.clone(),
+                None => direction_category(idx),
+            },
+            _ => action.label(),
+        }
+    }
+
+    // Saved color for each category, with unsaved ones auto-assigned from the palette
+    fn category_colors(&self, categories: &[String]) -> Vec<egui::Color32> {
+        let saved: Vec<Option<egui::Color32>> = categories
+            .iter()
+            .map(|category| {
+                self.config
+                    .category_colors
+                    .get(category)
+                    .map(|[r, g, b]| egui::Color32::from_rgb(*r, *g, *b))
+            })
+            .collect();
+
+        let mut taken: Vec<egui::Color32> = saved.iter().flatten().copied().collect();
+        saved
+            .into_iter()
+            .map(|color| {
+                color.unwrap_or_else(|| {
+                    let color = theme::next_palette_color(&taken);
+                    taken.push(color);
+                    color
+                })
+            })
+            .collect()
+    }
+
+    fn set_category_color(&mut self, category: &str, color: egui::Color32) {
+        self.config
+            .category_colors
+            .insert(category.to_string(), [color.r(), color.g(), color.b()]);
+    }
+
+    // Create the buckets and their folders; false, with the error shown, if a
+    // folder can't be made
+    fn setup_categories(&mut self, ctx: &egui::Context) -> bool {
+        let colors = self.category_colors(&self.categories);
+        for (category, color) in self.categories.clone().iter().zip(colors) {
+            // Remember auto-assigned colors so the category looks the same next time
+            self.set_category_color(category, color);
+
+            // Tag mode never moves anything, so it doesn't need the folders
+            if self.config.sort_mode.uses_folders() {
+                let category_path = self.base_dir.join(category);
+                if let Err(e) = std::fs::create_dir_all(&category_path) {
+                    self.notifications.error(t!(
+                        "category-folder-failed",
+                        name = path_arg(&category_path),
+                        error = e.to_string()
+                    ));
+                    self.category_buckets.clear();
+                    return false;
+                }
+            }
+            self.category_buckets
+                .insert(category.clone(), CategoryBucket::new(color));
+        }
+
+        self.save_config();
+
+        self.find_cross_device();
+        for category in self.cross_device.clone() {
+            self.check_space(&category, self.scan_tally.bytes);
+        }
+
+        self.refresh_images(ctx);
+        true
+    }
+
+    // Note which category folders live on another filesystem, e.g. behind a
+    // link to an external drive. Only moves there use up space
+    fn find_cross_device(&mut self) {
+        self.cross_device.clear();
+        if self.config.sort_mode != SortMode::Move {
+            return;
+        }
+        for category in &self.categories {
+            let dir = self.base_dir.join(category);
+            if dir.is_dir() && !disk_space::same_filesystem(&self.base_dir, &dir) {
+                self.cross_device.insert(category.clone());
+            }
+        }
+    }
+
+    // Have the free space in `category`'s folder compared against `bytes`
+    // about to be moved there, once `poll_disk_space` knows it
+    fn check_space(&mut self, category: &str, bytes: u64) {
+        let needed = self
+            .space_needed
+            .entry(self.base_dir.join(category))
+            .or_default();
+        *needed = (*needed).max(bytes);
+    }
+
+    fn poll_disk_space(&mut self, ctx: &egui::Context) {
+        self.disk_space.poll();
+        let margin = self.config.low_space_margin_mb * 1024 * 1024;
+        let checks: Vec<_> = self.space_needed.drain().collect();
+        for (dir, needed) in checks {
+            let Some(free) = self.disk_space.get(&dir) else {
+                self.disk_space.request(&dir, &self.loader.runtime, ctx);
+                self.space_needed.insert(dir, needed);
+                continue;
+            };
+            if free < needed.saturating_add(margin) {
+                self.low_space = Some(t!(
+                    "low-space",
+                    name = path_arg(&dir),
+                    free = format_bytes(free),
+                    needed = format_bytes(needed)
+                ));
+            }
+        }
+
+        if self.disk_full.swap(false, Ordering::SeqCst) && !self.paused {
+            self.set_paused(true);
+        }
+    }
+
+    fn draw_low_space(&mut self, ctx: &egui::Context) {
+        let Some(warning) = &self.low_space else {
+            return;
+        };
+        let mut dismissed = false;
+        egui::TopBottomPanel::top("low_space").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", warning));
+                dismissed = ui.small_button("✕").clicked();
+            });
+        });
+        if dismissed {
+            self.low_space = None;
+        }
+    }
+
+    // Categories whose folder already exists with something in it, and how
+    // many entries it has. Hidden files such as `.DS_Store` don't count
+    fn occupied_folders(&self) -> Vec<(String, usize)> {
+        if !self.config.sort_mode.uses_folders() {
+            return Vec::new();
+        }
+        self.categories
+            .iter()
+            .filter_map(|category| {
+                let entries = std::fs::read_dir(self.base_dir.join(category)).ok()?;
+                let count = entries
+                    .flatten()
+                    .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                    .count();
+                (count > 0).then(|| (category.clone(), count))
+            })
+            .collect()
+    }
+
+    fn draw_occupied_folders(&mut self, ctx: &egui::Context) {
+        let Some(occupied) = &mut self.occupied else {
+            return;
+        };
+        if let Some(rx) = &occupied.emptying {
+            match rx.try_recv() {
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100))
+                }
+                Ok(Ok(())) | Err(TryRecvError::Disconnected) => {
+                    self.occupied = None;
+                    self.setup_done = self.setup_categories(ctx);
+                    return;
+                }
+                Ok(Err(error)) => {
+                    occupied.emptying = None;
+                    self.notifications
+                        .error(t!("empty-folders-failed", error = error));
+                }
+            }
+        }
+
+        enum Answer {
+            Merge,
+            Rename,
+            Empty,
+        }
+        let mut answer = None;
+        egui::Window::new(t!("occupied-title"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(380.0);
+                ui.label(t!("occupied-summary"));
+                ui.add_space(5.0);
+                for (category, count) in &occupied.folders {
+                    ui.label(t!(
+                        "occupied-folder",
+                        name = category.as_str(),
+                        count = *count
+                    ));
+                }
+                ui.add_space(5.0);
+                if occupied.emptying.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.weak(t!("emptying-folders"));
+                    });
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(t!("occupied-merge"))
+                        .on_hover_text(t!("occupied-merge-hint"))
+                        .clicked()
+                    {
+                        answer = Some(Answer::Merge);
+                    }
+                    if ui.button(t!("occupied-rename")).clicked() {
+                        answer = Some(Answer::Rename);
+                    }
+                    if ui
+                        .button(t!("occupied-empty"))
+                        .on_hover_text(t!("occupied-empty-hint"))
+                        .clicked()
+                    {
+                        answer = Some(Answer::Empty);
+                    }
+                });
+            });
+
+        match answer {
+            Some(Answer::Merge) => {
+                self.occupied = None;
+                self.setup_done = self.setup_categories(ctx);
+            }
+            Some(Answer::Rename) => self.occupied = None,
+            Some(Answer::Empty) => {
+                // To the trash, like deleting an image, so it can be undone
+                // from the OS
+                let entries: Vec<PathBuf> = occupied
+                    .folders
+                    .iter()
+                    .filter_map(|(category, _)| {
+                        std::fs::read_dir(self.base_dir.join(category)).ok()
+                    })
+                    .flat_map(|entries| entries.flatten())
+                    .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                    .map(|entry| entry.path())
+                    .collect();
+                let (tx, rx) = channel();
+                occupied.emptying = Some(rx);
+                let in_flight = self.in_flight.start();
+                self.loader.runtime.spawn_blocking(move || {
+                    let _in_flight = in_flight;
+                    let _ = tx.send(trash::delete_all(&entries).map_err(|e| e.to_string()));
+                });
+            }
+            None => {}
+        }
+    }
+
+    // Add a bucket mid-session; the queue and current image are left alone
+    fn add_category(&mut self, name: &str) {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        if let Err(error) = category_name::check(&name) {
+            self.notifications.warn(error);
+            return;
+        }
+        if self.category_buckets.contains_key(&name) {
+            self.notifications
+                .warn(t!("category-exists", name = name.as_str()));
+            return;
+        }
+        if self.categories.len() >= MAX_CHORD_CATEGORIES {
+            self.notifications.warn(t!(
+                "category-limit",
+                name = name.as_str(),
+                max = MAX_CHORD_CATEGORIES
+            ));
+            return;
+        }
+        if self.config.sort_mode.uses_folders() {
+            if let Err(e) = std::fs::create_dir_all(self.base_dir.join(&name)) {
+                self.notifications.error(t!(
+                    "category-folder-failed",
+                    name = name.as_str(),
+                    error = e.to_string()
+                ));
+                return;
+            }
+            let dir = self.base_dir.join(&name);
+            if self.config.sort_mode == SortMode::Move
+                && !disk_space::same_filesystem(&self.base_dir, &dir)
+            {
+                self.cross_device.insert(name.clone());
+            }
+        }
+
+        let mut categories = self.categories.clone();
+        categories.push(name.clone());
+        let color = *self.category_colors(&categories).last().unwrap();
+        self.set_category_color(&name, color);
+        self.save_config();
+
+        self.category_buckets
+            .insert(name.clone(), CategoryBucket::new(color));
+        self.categories.push(name);
+    }
+
+    // Skip the setup screen, e.g. when categories were given on the command line
+    fn start_with_categories(&mut self, categories: Vec<String>, ctx: &egui::Context) {
+        self.input_categories = categories.join(", ");
+        self.categories = categories;
+        self.scan_started = true;
+        self.start_background_loading(ctx);
+        // Otherwise the setup screen shows, with the categories filled in
+        self.setup_done = self.setup_categories(ctx);
+    }
+
+    // File name, or more of the path when files come from several folders
+    fn display_name(&self, path: &Path) -> String {
+        self.short_names.get(path).cloned().unwrap_or_else(|| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+
+    // Whether `path` was already sorted into one of the category folders
+    fn in_category_folder(&self, path: &Path) -> bool {
+        path.parent().is_some_and(|parent| {
+            self.categories
+                .iter()
+                .any(|category| self.base_dir.join(category) == parent)
+        })
+    }
+
+    fn refresh_images(&mut self, ctx: &egui::Context) {
+        // Images found before the categories were set up may be sorted ones
+        let mut images = std::mem::take(&mut self.images);
+        images.retain(|path| !self.in_category_folder(path));
+        self.images = images;
+
+        // A scan still in progress keeps appending to the queue; otherwise use
+        // what the finished scan found
+        if !self.images.is_empty() {
+            self.current_image = Some(self.resume_position().unwrap_or(0));
+        }
+        self.start_window_loading(ctx);
+
+        // Fill the buckets from their folders in the background, newest
+        // files first so they top the stack. Re-reads after changes wait
+        // until a folder's first read is in
+        let dirs: Vec<PathBuf> = self
+            .categories
+            .iter()
+            .map(|category| self.base_dir.join(category))
+            .collect();
+        self.reading_folders.extend(dirs.iter().cloned());
+        let extensions = self.config.image_extensions.clone();
+        let tx = self.listing_tx.clone();
+        let ctx = ctx.clone();
+        self.loader.runtime.spawn_blocking(move || {
+            for dir in dirs {
+                let files = scan::list_folder(&dir, &extensions);
+                if tx.send((dir, files)).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    // Fill buckets as their folders' first reads come in, and bring them in
+    // line with their folders after other programs changed them. Folders are
+    // read and compared in the background; files the app's own operations
+    // touched are left to the code that moved them
+    fn poll_folder_changes(&mut self, ctx: &egui::Context) {
+        while let Ok((dir, files)) = self.listing_rx.try_recv() {
+            self.reading_folders.remove(&dir);
+            if let Some(bucket) = self.bucket_for_dir(&dir) {
+                bucket.add_listed(files);
+            }
+        }
+
+        let Some(watcher) = &mut self.folder_watcher else {
+            return;
+        };
+        // Folders appear as categories are added or first used
+        if self
+            .watch_synced
+            .is_none_or(|synced| synced.elapsed() > WATCH_SYNC_INTERVAL)
+        {
+            let mut dirs = if self.setup_done && self.config.sort_mode.uses_folders() {
+                self.categories
+                    .iter()
+                    .map(|category| self.base_dir.join(category))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            // The queue's own folder, so a file deleted or replaced there is
+            // looked at again straight away
+            dirs.push(self.base_dir.clone());
+            watcher.set_dirs(dirs);
+            self.watch_synced = Some(Instant::now());
+        }
+
+        for dir in self.folder_change_rx.try_iter() {
+            self.file_checks.invalidate_dir(&dir);
+            self.changed_folders.insert(dir);
+        }
+
+        while let Ok((dir, diff)) = self.folder_diff_rx.try_recv() {
+            self.reading_folders.remove(&dir);
+            let Some(bucket) = self.bucket_for_dir(&dir) else {
+                continue;
+            };
+            // The pile may have changed during the read
+            let gone: Vec<PathBuf> = diff
+                .gone
+                .into_iter()
+                .filter(|path| bucket.contains(path))
+                .collect();
+            let added: Vec<(PathBuf, u64)> = diff
+                .added
+                .into_iter()
+                .filter(|(path, _)| !bucket.contains(path))
+                .collect();
+            if gone.is_empty() && added.is_empty() {
+                continue;
+            }
+            for path in &gone {
+                bucket.remove_file(path);
+            }
+            // Their thumbnails load when they reach the top of the pile
+            for (path, size) in added.into_iter().rev() {
+                bucket.add_file(path, Some(size));
+            }
+            bucket.external_time = Some(Instant::now());
+        }
+
+        // A folder rename moves everything in it at once
+        if self.reorganizing() {
+            return;
+        }
+        let changed: Vec<PathBuf> = self
+            .changed_folders
+            .iter()
+            .filter(|dir| !self.reading_folders.contains(*dir))
+            .cloned()
+            .collect();
+        for dir in changed {
+            self.changed_folders.remove(&dir);
+            let Some(bucket) = self.bucket_for_dir(&dir) else {
+                continue;
+            };
+            let known: HashSet<PathBuf> = bucket.paths().cloned().collect();
+            let extensions = self.config.image_extensions.clone();
+            let own_changes = self.own_changes.clone();
+            let tx = self.folder_diff_tx.clone();
+            let ctx = ctx.clone();
+            self.reading_folders.insert(dir.clone());
+            self.loader.runtime.spawn_blocking(move || {
+                let diff = watcher::diff_folder(&dir, &extensions, &known, &own_changes);
+                let _ = tx.send((dir, diff));
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    // The bucket of the category whose folder is `dir`
+    fn bucket_for_dir(&mut self, dir: &Path) -> Option<&mut CategoryBucket> {
+        let category = self
+            .categories
+            .iter()
+            .find(|category| self.base_dir.join(category) == dir)?;
+        self.category_buckets.get_mut(category)
+    }
+
+    // This folder's layout if one was picked for it, else the default
+    fn bucket_layout(&self) -> BucketLayout {
+        self.session.layout.unwrap_or(self.config.bucket_layout)
+    }
+
+    fn set_bucket_layout(&mut self, layout: BucketLayout) {
+        self.session.layout = Some(layout);
+        self.config.bucket_layout = layout;
+        self.save_session();
+        self.save_config();
+    }
+
+    // Draws the buckets, returning the one pressed as a button this frame
+    fn draw_buckets(
+        &mut self,
+        ui: &mut egui::Ui,
+        center: egui::Pos2,
+        panel_size: egui::Vec2,
+    ) -> Option<Action> {
+        let layout::Placement {
+            size: bucket_size,
+            categories: bucket_positions,
+            kept_size,
+            kept: kept_position,
+        } = layout::place(
+            self.bucket_layout(),
+            self.categories.len(),
+            center,
+            panel_size,
+        );
+        let scale = layout::ui_scale(panel_size);
+        // Real categories, then the kept pile, which is also the focus order
+        let slots: Vec<(Option<String>, Action, egui::Pos2, egui::Vec2)> = self
+            .categories
+            .iter()
+            .enumerate()
+            .map(|(i, category)| {
+                (
+                    Some(category.clone()),
+                    Action::Category(i),
+                    bucket_positions[i],
+                    bucket_size,
+                )
+            })
+            .chain(std::iter::once((
+                None,
+                Action::Keep,
+                kept_position,
+                kept_size,
+            )))
+            .collect();
+
+        let palette = self.palette();
+        let drop_zone = egui::Rect::from_center_size(center, panel_size * 0.4);
+        let mut dragging = false;
+        let mut returned = None;
+        let mut missing = Vec::new();
+        let mut pressed = None;
+        let can_sort = !self.paused && self.current_image.is_some();
+        for (category, action, position, size) in slots {
+            let key_hint = self.key_hint(ui.ctx(), action);
+            let bucket = match &category {
+                Some(category) => self.category_buckets.get_mut(category),
+                None => Some(&mut self.kept_bucket),
+            };
+            if let Some(bucket) = bucket {
+                // Squash, then overshoot a little and settle, as the card lands
+                let absorb = bucket
+                    .absorb_time
+                    .map(|time| time.elapsed().as_secs_f32() / ABSORB_PULSE)
+                    .filter(|t| *t < 1.0);
+                let pulse = match absorb {
+                    Some(t) => {
+                        ui.ctx().request_repaint();
+                        1.0 - 0.08 * (t * std::f32::consts::TAU).sin() * (1.0 - t)
+                    }
+                    None => {
+                        bucket.absorb_time = None;
+                        1.0
+                    }
+                };
+                bucket.rect = egui::Rect::from_center_size(position, size * pulse);
+
+                // Background tinted with the category color, and a border
+                // briefly thickened when a card lands
+                let flash = bucket
+                    .flash_time
+                    .map(|time| 1.0 - time.elapsed().as_secs_f32() / 0.4)
+                    .filter(|flash| *flash > 0.0);
+                if flash.is_none() {
+                    bucket.flash_time = None;
+                } else {
+                    ui.ctx().request_repaint();
+                }
+                let name = format!(
+                    "{}, {}",
+                    category.as_deref().unwrap_or(&kept_label()),
+                    bucket.summary()
+                );
+                let pressed_here = board::bucket(
+                    ui,
+                    bucket.rect,
+                    egui::Id::new(("bucket", &category)),
+                    &name,
+                    can_sort,
+                    &palette,
+                    board::BucketLook {
+                        color: bucket.color,
+                        flash: flash.unwrap_or(0.0),
+                    },
+                );
+                if pressed_here.clicked() {
+                    pressed = Some(action);
+                }
+
+                // A fading note when files came or went outside the app
+                let external = bucket
+                    .external_time
+                    .map(|time| 1.0 - time.elapsed().as_secs_f32() / EXTERNAL_FLASH)
+                    .filter(|fade| *fade > 0.0);
+                match external {
+                    Some(fade) => {
+                        ui.painter().text(
+                            bucket.rect.center_top() + egui::vec2(0.0, 4.0),
+                            egui::Align2::CENTER_TOP,
+                            t!("updated-externally"),
+                            egui::FontId::proportional(
+                                11.0 * scale * accessibility::text_scale(ui.style()),
+                            ),
+                            ui.visuals().text_color().gamma_multiply(fade),
+                        );
+                        ui.ctx().request_repaint();
+                    }
+                    None => bucket.external_time = None,
+                }
+
+                // Slow moves (network or external drives) show they're still going
+                let busy = category.as_ref().is_some_and(|category| {
+                    self.moving.values().any(|(moving, since)| {
+                        moving == category && since.elapsed() > MOVING_INDICATOR_DELAY
+                    })
+                });
+                if busy {
+                    let font = egui::FontId::proportional(
+                        11.0 * scale * accessibility::text_scale(ui.style()),
+                    );
+                    let spinner = egui::Rect::from_min_size(
+                        bucket.rect.left_top() + egui::vec2(6.0, 6.0),
+                        egui::Vec2::splat(font.size + 2.0),
+                    );
+                    egui::Spinner::new().paint_at(ui, spinner);
+                    ui.painter().text(
+                        spinner.right_center() + egui::vec2(4.0, 0.0),
+                        egui::Align2::LEFT_CENTER,
+                        t!("moving"),
+                        font,
+                        ui.visuals().text_color(),
+                    );
+                }
+
+                // The top few cards, each where it landed
+                let visible_cards = &bucket.files[..bucket.files.len().min(MAX_VISIBLE_CARDS)];
+                // Files from an earlier session have no texture yet
+                missing.extend(
+                    visible_cards
+                        .iter()
+                        .filter(|card| {
+                            !self.thumbnails.contains_key(&card.path)
+                                && !self.textures.contains_key(&card.path)
+                        })
+                        .map(|card| card.path.clone()),
+                );
+                let hidden = bucket.files.len() - visible_cards.len();
+                if hidden > 0 {
+                    ui.painter().text(
+                        bucket.rect.right_top() + egui::vec2(-6.0, 6.0),
+                        egui::Align2::RIGHT_TOP,
+                        t!("more-cards", count = hidden),
+                        egui::FontId::proportional(
+                            11.0 * scale * accessibility::text_scale(ui.style()),
+                        ),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+
+                for (stack_idx, card) in visible_cards.iter().enumerate().rev() {
+                    let file_path = &card.path;
+                    if let Some(texture) = self
+                        .thumbnails
+                        .get(file_path)
+                        .or_else(|| self.textures.get(file_path))
+                    {
+                        // 1 while the card that just landed is held big, easing to 0
+                        let pop = bucket
+                            .last_moved
+                            .as_ref()
+                            .filter(|(path, _)| path == file_path)
+                            .map(|(_, since)| {
+                                let relax = (since.elapsed().as_secs_f32() - LAST_MOVED_HOLD)
+                                    / LAST_MOVED_RELAX;
+                                Easing::CubicOut.apply(1.0 - relax.clamp(0.0, 1.0))
+                            })
+                            .filter(|pop| *pop > 0.0);
+                        let growth = if self.config.reduce_motion {
+                            1.0
+                        } else {
+                            1.0 + pop.unwrap_or(0.0) * LAST_MOVED_GROWTH
+                        };
+                        let mut rotation = card.rotation;
+                        let mut card_rect = egui::Rect::from_center_size(
+                            bucket.rect.center() + card.offset * scale,
+                            // Big drop zones still get ordinary cards
+                            (size * 0.8).min(egui::vec2(80.0, 120.0) * scale) * growth,
+                        );
+                        let mut painter = ui.painter().clone();
+
+                        // The top card can be dragged back onto the center to
+                        // return it to the queue
+                        if let (0, Some(category)) = (stack_idx, &category) {
+                            let response = ui.interact(
+                                card_rect,
+                                egui::Id::new(("bucket_top_card", category)),
+                                if self.paused {
+                                    egui::Sense::hover()
+                                } else {
+                                    egui::Sense::drag()
+                                },
+                            );
+                            let pointer = ui.ctx().pointer_latest_pos();
+
+                            if response.dragged() {
+                                dragging = true;
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+                                if let Some(pointer) = pointer {
+                                    card_rect =
+                                        egui::Rect::from_center_size(pointer, card_rect.size());
+                                }
+                                rotation = 0.0;
+                                painter = ui.ctx().layer_painter(egui::LayerId::new(
+                                    egui::Order::Foreground,
+                                    egui::Id::new("dragged_card"),
+                                ));
+                            } else if response.hovered() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                            }
+
+                            if response.drag_released()
+                                && pointer.is_some_and(|pointer| drop_zone.contains(pointer))
+                            {
+                                returned = Some((category.clone(), file_path.clone()));
+                            }
+                        }
+
+                        // Shadow, then the card, both turned by the card's tilt
+                        let turn = egui::emath::Rot2::from_angle(rotation);
+                        let mut shadow = egui::Mesh::default();
+                        shadow.add_colored_rect(
+                            card_rect.translate(egui::vec2(2.0, 2.0)),
+                            palette.shadow,
+                        );
+                        shadow.rotate(turn, card_rect.center());
+                        painter.add(shadow);
+                        let mut image = egui::Mesh::with_texture(texture.id());
+                        image.add_rect_with_uv(
+                            card_rect,
+                            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                            egui::Color32::WHITE,
+                        );
+                        image.rotate(turn, card_rect.center());
+                        painter.add(image);
+                        let corner = |point: egui::Pos2| {
+                            card_rect.center() + turn * (point - card_rect.center())
+                        };
+                        if let Some(pop) = pop {
+                            painter.add(egui::Shape::closed_line(
+                                [
+                                    card_rect.left_top(),
+                                    card_rect.right_top(),
+                                    card_rect.right_bottom(),
+                                    card_rect.left_bottom(),
+                                ]
+                                .map(corner)
+                                .to_vec(),
+                                egui::Stroke::new(
+                                    2.0,
+                                    ui.visuals().selection.stroke.color.gamma_multiply(pop),
+                                ),
+                            ));
+                            ui.ctx().request_repaint();
+                        }
+                        if self.session.is_favorite(&self.base_dir, file_path) {
+                            painter.text(
+                                corner(card_rect.right_top() + egui::vec2(-3.0, 3.0)),
+                                egui::Align2::RIGHT_TOP,
+                                "★",
+                                egui::FontId::proportional(14.0 * scale),
+                                FAVORITE_COLOR,
+                            );
+                        }
+                    }
+                }
+
+                board::bucket_label(
+                    &mut ui.painter().clone(),
+                    bucket.rect,
+                    bucket_label(&key_hint, category.as_deref(), bucket),
+                    egui::FontId::proportional(
+                        16.0 * scale * accessibility::text_scale(ui.style()),
+                    ),
+                    bucket.color,
+                );
+            }
+        }
+
+        if dragging {
+            ui.painter().rect_stroke(
+                drop_zone,
+                5.0,
+                egui::Stroke::new(2.0, ui.visuals().selection.bg_fill),
+            );
+        }
+
+        if let Some((category, path)) = returned {
+            self.return_to_queue(&category, path);
+        }
+        for path in missing {
+            self.thumbnail_for(&path, ui.ctx());
+        }
+
+        // Keep the preview up while the pointer is over its bucket or the
+        // preview itself, so it can be clicked and doesn't flicker
+        let pointer = ui.ctx().pointer_hover_pos();
+        let hovered = pointer.filter(|_| !dragging).and_then(|pointer| {
+            self.categories
+                .iter()
+                .filter_map(|category| {
+                    let rect = self.category_buckets.get(category)?.rect;
+                    Some((Some(category.clone()), rect))
+                })
+                .chain(std::iter::once((None, self.kept_bucket.rect)))
+                .find(|(_, rect)| rect.contains(pointer))
+        });
+        match hovered {
+            Some((slot, rect)) => self.bucket_preview = Some((slot, rect, egui::Rect::NOTHING)),
+            None => {
+                let over_popup = self
+                    .bucket_preview
+                    .as_ref()
+                    .zip(pointer)
+                    .is_some_and(|((_, _, popup), pointer)| popup.contains(pointer));
+                if !over_popup {
+                    self.bucket_preview = None;
+                }
+            }
+        }
+        if let Some((slot, anchor, _)) = self.bucket_preview.clone() {
+            let popup = self.draw_bucket_preview(ui.ctx(), &slot, anchor);
+            if let Some((_, _, rect)) = &mut self.bucket_preview {
+                *rect = popup;
+            }
+        }
+        pressed
+    }
+
+    // Thumbnail for an image outside the preload window (filed, or further
+    // down the queue), decoded in the background on first use. One sorted
+    // after its decode is uploaded from memory
+    fn thumbnail_for(&mut self, path: &Path, ctx: &egui::Context) -> Option<egui::TextureHandle> {
+        if let Some(texture) = self
+            .thumbnails
+            .get(path)
+            .or_else(|| self.textures.get(path))
+        {
+            return Some(texture.clone());
+        }
+        if let Some(image) = self.thumbnail_images.remove(path) {
+            let name = format!("{} (thumbnail)", path.to_string_lossy());
+            let thumbnail = loader::upload(ctx, name, image);
+            self.thumbnails
+                .insert(path.to_path_buf(), thumbnail.clone());
+            return Some(thumbnail);
+        }
+        if self.may_decode()
+            && scan::has_image_extension(path, &self.config.image_extensions)
+            && !self.loader.is_pending(path)
+            && self.thumbnail_loads.insert(path.to_path_buf())
+        {
+            let max_pixels = self.decode_limit(path);
+            self.loader
+                .request(path.to_path_buf(), false, max_pixels, ctx);
+        }
+        None
+    }
+
+    fn bucket_files(&self, slot: &Option<String>) -> Option<&CategoryBucket> {
+        match slot {
+            Some(category) => self.category_buckets.get(category),
+            None => Some(&self.kept_bucket),
+        }
+    }
+
+    // Whether the compare key is held with an earlier image to flip to. Once
+    // that image has been gone for a while it's let go
+    fn comparing(&mut self, ctx: &egui::Context) -> bool {
+        let binding = self.config.key_bindings.compare;
+        let held = self.input_mode == InputMode::Direct
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_down(binding.key) && i.modifiers.matches(binding.modifiers));
+        if !held
+            && self
+                .previous_image
+                .as_ref()
+                .is_some_and(|previous| previous.since.elapsed() > COMPARE_GRACE)
+        {
+            self.previous_image = None;
+        }
+        let current = self.current_image.and_then(|idx| self.images.get(idx));
+        held && self
+            .previous_image
+            .as_ref()
+            .is_some_and(|previous| Some(&previous.path) != current)
+    }
+
+    // The previous image in place of the current one, named so it's clear
+    // which of the two is up
+    fn draw_previous_image(&self, ui: &egui::Ui, center: egui::Pos2, panel_size: egui::Vec2) {
+        let Some(previous) = &self.previous_image else {
+            return;
+        };
+        let panel = egui::Rect::from_center_size(center, panel_size);
+        let (image_size, uv) = fit_image(
+            self.config.image_fit,
+            previous.texture.size_vec2(),
+            panel,
+            1.0,
+        );
+        let image_rect = egui::Rect::from_center_size(center, image_size);
+        ui.painter()
+            .image(previous.texture.id(), image_rect, uv, egui::Color32::WHITE);
+        draw_banner(
+            ui,
+            image_rect.center_top() + egui::vec2(0.0, 24.0),
+            t!(
+                "comparing-previous",
+                name = self.display_name(&previous.path)
+            ),
+        );
+    }
+
+    // Latest files in a bucket, shown next to it while hovered; returns the
+    // popup's rect
+    fn draw_bucket_preview(
+        &mut self,
+        ctx: &egui::Context,
+        slot: &Option<String>,
+        anchor: egui::Rect,
+    ) -> egui::Rect {
+        let Some(bucket) = self.bucket_files(slot) else {
+            return egui::Rect::NOTHING;
+        };
+        let count = bucket.files.len();
+        let files: Vec<PathBuf> = bucket.paths().take(6).cloned().collect();
+        let dir = match slot {
+            Some(category) => self.base_dir.join(category),
+            None => self.base_dir.clone(),
+        };
+        let thumbnails: Vec<Option<egui::TextureHandle>> = files
+            .iter()
+            .map(|path| self.thumbnail_for(path, ctx))
+            .collect();
+
+        // Open towards the middle of the window
+        let (pos, pivot) = if anchor.center().x > ctx.screen_rect().center().x {
+            (
+                anchor.left_top() - egui::vec2(8.0, 0.0),
+                egui::Align2::RIGHT_TOP,
+            )
+        } else {
+            (
+                anchor.right_top() + egui::vec2(8.0, 0.0),
+                egui::Align2::LEFT_TOP,
+            )
+        };
+        let mut clicked = None;
+        let response = egui::Area::new("bucket_preview")
+            .fixed_pos(pos)
+            .pivot(pivot)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.strong(t!(
+                        "bucket-preview",
+                        category = slot.clone().unwrap_or_else(kept_label),
+                        count = count
+                    ));
+                    ui.weak(dir.display().to_string());
+                    egui::Grid::new("bucket_preview_grid")
+                        .spacing([4.0, 4.0])
+                        .show(ui, |ui| {
+                            for (i, (path, thumbnail)) in files.iter().zip(&thumbnails).enumerate()
+                            {
+                                if file_card(ui, path, thumbnail.as_ref(), false).clicked() {
+                                    clicked = Some(path.clone());
+                                }
+                                if i % 3 == 2 {
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                });
+            })
+            .response;
+
+        if let Some(path) = clicked {
+            self.review = Some(BucketReview {
+                slot: slot.clone(),
+                focus: Some(path),
+                scroll: true,
+            });
+            self.bucket_preview = None;
+        }
+        response.rect
+    }
+
+    // Upcoming images along the bottom; clicking one jumps to it
+    fn draw_filmstrip(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some(current_idx) = self.current_image else {
+            return;
+        };
+        let upcoming: Vec<(usize, PathBuf)> = self
+            .images
+            .iter()
+            .enumerate()
+            .skip(current_idx + 1)
+            .take(FILMSTRIP_LEN)
+            .map(|(idx, path)| (idx, path.clone()))
+            .collect();
+        if upcoming.is_empty() {
+            return;
+        }
+
+        let size = egui::vec2(64.0, 64.0);
+        let panel = ui.max_rect();
+        // About eight thumbnails wide, scrolling for the rest
+        let width = (8.0 * (size.x + ui.spacing().item_spacing.x)).min(panel.width() * 0.6);
+        let strip = egui::Rect::from_center_size(
+            egui::pos2(panel.center().x, panel.bottom() - size.y / 2.0 - 16.0),
+            egui::vec2(width, size.y + 12.0),
+        );
+        let fill = self.palette().bucket_fill;
+
+        let mut jump = None;
+        let mut wanted = Vec::new();
+        ui.allocate_ui_at_rect(strip, |ui| {
+            egui::ScrollArea::horizontal()
+                .id_source("filmstrip")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for (idx, path) in &upcoming {
+                            let texture = self
+                                .thumbnails
+                                .get(path)
+                                .or_else(|| self.textures.get(path));
+                            let response = match texture {
+                                Some(texture) => ui.add_sized(
+                                    size,
+                                    egui::ImageButton::new(
+                                        egui::Image::from_texture(texture).max_size(size),
+                                    ),
+                                ),
+                                None => {
+                                    let (rect, response) =
+                                        ui.allocate_exact_size(size, egui::Sense::click());
+                                    ui.painter().rect_filled(rect, 3.0, fill);
+                                    // Only load what has scrolled into view
+                                    if ui.is_rect_visible(rect) {
+                                        wanted.push(path.clone());
+                                    }
+                                    response
+                                }
+                            };
+                            if response.clicked() {
+                                jump = Some(*idx);
+                            }
+                        }
+                    });
+                });
+        });
+
+        for path in wanted {
+            self.thumbnail_for(&path, ctx);
+        }
+        if let Some(idx) = jump {
+            self.current_image = Some(idx);
+        }
+    }
+
+    // File every selected image into the category at `idx` as one batch
+    fn file_selected(&mut self, idx: usize, center_pos: egui::Pos2, ctx: &egui::Context) {
+        if self.hold_for_confirmation(idx, center_pos) {
+            return;
+        }
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+        // In queue order, so undo puts them back the way they were
+        let selected: Vec<PathBuf> = self
+            .images
+            .iter()
+            .filter(|path| self.selected.contains(*path))
+            .cloned()
+            .collect();
+        self.selected.clear();
+        self.selection_anchor = None;
+
+        self.batches += 1;
+        self.current_batch = Some(self.batches);
+        for path in selected {
+            if let Some(position) = self.images.position(&path) {
+                self.current_image = Some(position);
+                self.move_image(idx, center_pos, ctx);
+            }
+        }
+        self.current_batch = None;
+
+        if let Some(position) = current.and_then(|c| self.images.iter().position(|p| *p == c)) {
+            self.current_image = Some(position);
+        }
+    }
+
+    // Every queued image as a grid of thumbnails; click to select, shift-click
+    // to select a run, then a category key files the lot
+    fn draw_overview(&mut self, ctx: &egui::Context) {
+        const COLUMNS: usize = 6;
+        const ROW_HEIGHT: f32 = 104.0;
+        let mut open = true;
+        let mut clicked = None;
+        let mut jump = None;
+        let rows = self.images.len().div_ceil(COLUMNS);
+        egui::Window::new(t!("overview"))
+            .open(&mut open)
+            .default_size([640.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if self.selected.is_empty() {
+                        ui.weak(t!("overview-hint"));
+                    } else {
+                        ui.label(t!("overview-selected", count = self.selected.len()));
+                        if ui.small_button(t!("select-none")).clicked() {
+                            self.selected.clear();
+                            self.selection_anchor = None;
+                        }
+                    }
+                });
+                ui.separator();
+                // Only the visible rows are laid out, however long the queue
+                egui::ScrollArea::vertical().show_rows(ui, ROW_HEIGHT, rows, |ui, rows| {
+                    for row in rows {
+                        ui.horizontal(|ui| {
+                            let start = row * COLUMNS;
+                            let end = (start + COLUMNS).min(self.images.len());
+                            for idx in start..end {
+                                let path = self.images[idx].clone();
+                                let thumbnail = self.thumbnail_for(&path, ctx);
+                                let selected = self.selected.contains(&path);
+                                let response = file_card(ui, &path, thumbnail.as_ref(), selected);
+                                if response.double_clicked() {
+                                    jump = Some(idx);
+                                } else if response.clicked() {
+                                    clicked = Some((idx, ui.input(|i| i.modifiers.shift)));
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+
+        if let Some((idx, shift)) = clicked {
+            match self.selection_anchor.filter(|_| shift) {
+                Some(anchor) => {
+                    let (from, to) = (anchor.min(idx), anchor.max(idx));
+                    let run = self.images.iter().skip(from).take(to + 1 - from);
+                    self.selected.extend(run.cloned());
+                }
+                None => {
+                    let path = self.images[idx].clone();
+                    if !self.selected.remove(&path) {
+                        self.selected.insert(path);
+                    }
+                    self.selection_anchor = Some(idx);
+                }
+            }
+        }
+        if let Some(idx) = jump {
+            self.current_image = Some(idx);
+        }
+        if !open {
+            self.show_overview = false;
+        }
+    }
+
+    // Every file in one bucket, opened from its hover preview
+    fn draw_review(&mut self, ctx: &egui::Context) {
+        let Some(review) = &self.review else {
+            return;
+        };
+        let slot = review.slot.clone();
+        let focus = review.focus.clone();
+        let scroll = review.scroll;
+        let Some(bucket) = self.bucket_files(&slot) else {
+            self.review = None;
+            return;
+        };
+        let files: Vec<PathBuf> = bucket.paths().cloned().collect();
+        let thumbnails: Vec<Option<egui::TextureHandle>> = files
+            .iter()
+            .map(|path| self.thumbnail_for(path, ctx))
+            .collect();
+
+        let mut open = true;
+        let mut returned = None;
+        egui::Window::new(t!(
+            "review-bucket",
+            category = slot.clone().unwrap_or_else(kept_label)
+        ))
+        .id(egui::Id::new("bucket_review"))
+        .open(&mut open)
+        .default_size([520.0, 400.0])
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("bucket_review_grid")
+                    .spacing([6.0, 6.0])
+                    .show(ui, |ui| {
+                        for (i, (path, thumbnail)) in files.iter().zip(&thumbnails).enumerate() {
+                            let focused = focus.as_ref() == Some(path);
+                            ui.vertical(|ui| {
+                                let response = file_card(ui, path, thumbnail.as_ref(), focused);
+                                if focused && scroll {
+                                    response.scroll_to_me(Some(egui::Align::Center));
+                                }
+                                if slot.is_some()
+                                    && ui.small_button(t!("return-to-queue")).clicked()
+                                {
+                                    returned = Some(path.clone());
+                                }
+                            });
+                            if i % 4 == 3 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+        });
+
+        if let Some(review) = &mut self.review {
+            review.scroll = false;
+        }
+        if !open {
+            self.review = None;
+        }
+        if let (Some(category), Some(path)) = (slot, returned) {
+            self.return_to_queue(&category, path);
+        }
+    }
+
+    // Move a filed card back to the source folder and make it the current image,
+    // independently of the chronological undo history
+    fn return_to_queue(&mut self, category: &str, path: PathBuf) {
+        let Some(file_name) = path.file_name() else {
+            return;
+        };
+        // Go back under the name it had before the move, which may have renamed it
+        let original = self.moves.iter().find(|op| op.to == path);
+        // A linked file never left the source folder either; only the link goes
+        let linked = original
+            .filter(|op| op.mode.is_link())
+            .map(|op| op.from.clone());
+        let dest = original
+            .map(|op| op.from.clone())
+            .unwrap_or_else(|| self.base_dir.join(file_name));
+        // A tagged file never left the source folder; it only loses its tag
+        let tagged = dest == path;
+        if dest.exists() && !tagged && linked.is_none() {
+            self.notifications.warn(t!(
+                "return-blocked",
+                path = path_arg(&path),
+                existing = path_arg(&dest)
+            ));
+            return;
+        }
+
+        if let Some(bucket) = self.category_buckets.get_mut(category) {
+            bucket.remove_file(&path);
+        }
+        // Undoing the original move would now fail, so drop it from the history
+        self.forget_moves(|op| op.to == path);
+
+        if tagged {
+            self.run_file_op(FileOp::Tag {
+                path: path.clone(),
+                category: category.to_string(),
+                add: false,
+            });
+        } else if linked.is_some() {
+            self.run_file_op(FileOp::Unlink(path.clone()));
+        } else {
+            self.follow_favorite(&path, &dest);
+            self.run_file_op(FileOp::Move {
+                from: path.clone(),
+                to: dest.clone(),
+            });
+        }
+
+        self.follow_thumbnail(&path, &dest);
+        if let Some(current_idx) = self.current_image {
+            self.images.insert(current_idx, dest);
+        } else {
+            self.images.push(dest);
+            self.current_image = Some(self.images.len() - 1);
+        }
+    }
+
+    // Drop a category: its files go back to the source folder and into the
+    // queue, then the emptied folder is deleted
+    fn remove_category(&mut self, category: &str) {
+        self.land_pending_moves(category);
+
+        let Some(bucket) = self.category_buckets.remove(category) else {
+            return;
+        };
+        self.categories.retain(|c| c != category);
+        // Link -> original for files linked rather than moved in
+        let mut links: HashMap<PathBuf, PathBuf> = self
+            .moves
+            .iter()
+            .filter(|op| op.mode.is_link() && op.category.as_deref() == Some(category))
+            .map(|op| (op.to.clone(), op.from.clone()))
+            .collect();
+        // Where moved files came from, which isn't always the source folder
+        // when sorting from a list
+        let mut originals: HashMap<PathBuf, PathBuf> = self
+            .moves
+            .iter()
+            .filter(|op| op.mode == SortMode::Move && op.category.as_deref() == Some(category))
+            .map(|op| (op.to.clone(), op.from.clone()))
+            .collect();
+        self.forget_moves(|op| op.category.as_deref() == Some(category));
+        self.redo_stack
+            .retain(|op| op.category.as_deref() != Some(category));
+
+        let mut returns = Vec::new();
+        let mut unlinks = Vec::new();
+        for BucketCard { path, .. } in bucket.files {
+            if let Some(original) = links.remove(&path) {
+                self.follow_thumbnail(&path, &original);
+                self.images.push(original);
+                unlinks.push(path);
+                continue;
+            }
+            let Some(file_name) = path.file_name() else {
+                continue;
+            };
+            let dest = originals
+                .remove(&path)
+                .unwrap_or_else(|| self.base_dir.join(file_name));
+            if dest == path {
+                // Tagged in place, so only the tag has to go
+                self.run_file_op(FileOp::Tag {
+                    path: path.clone(),
+                    category: category.to_string(),
+                    add: false,
+                });
+                self.images.push(path);
+                continue;
+            }
+            if dest.exists() {
+                self.notifications.warn(t!(
+                    "left-in-place",
+                    path = path_arg(&path),
+                    existing = path_arg(&dest)
+                ));
+                continue;
+            }
+            self.follow_thumbnail(&path, &dest);
+            if scan::has_image_extension(&dest, &self.config.image_extensions) {
+                self.images.push(dest.clone());
+            }
+            self.follow_favorite(&path, &dest);
+            returns.push((path, dest));
+        }
+        if self.current_image.is_none() && !self.images.is_empty() {
+            self.current_image = Some(0);
+        }
+
+        let dir = self.base_dir.join(category);
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            for link in unlinks {
+                if let Err(e) = tokio::fs::remove_file(&link).await {
+                    notifier.error(t!(
+                        "unlink-failed",
+                        link = path_arg(&link),
+                        error = e.to_string()
+                    ));
+                }
+            }
+            for (from, to) in returns {
+                if let Err(e) = move_file(&from, &to).await {
+                    notifier.error(t!(
+                        "return-failed",
+                        path = path_arg(&from),
+                        error = e.to_string()
+                    ));
+                }
+            }
+            // Only succeeds once the folder is empty, so nothing is lost
+            if !dir.exists() {
+                return;
+            }
+            if let Err(e) = tokio::fs::remove_dir(&dir).await {
+                notifier.error(t!(
+                    "folder-remove-failed",
+                    path = path_arg(&dir),
+                    error = e.to_string()
+                ));
+            }
+        });
+    }
+
+    // Rename a category's folder in the background, once the files moving
+    // into it have landed; the buckets and history follow in
+    // `poll_category_rename` once it has worked
+    fn rename_category(&mut self, old: &str, new: &str) {
+        let new = new.trim().to_string();
+        if new.is_empty() || new == old || self.reorganizing() {
+            return;
+        }
+        if let Err(error) = category_name::check(&new) {
+            self.notifications.warn(error);
+            return;
+        }
+        if self.category_buckets.contains_key(&new) {
+            self.notifications
+                .warn(t!("category-exists", name = new.as_str()));
+            return;
+        }
+        self.land_pending_moves(old);
+        if self.moving_into(&[old]) {
+            self.held_rename = Some((old.to_string(), new));
+            return;
+        }
+        self.start_rename(old.to_string(), new);
+    }
+
+    // Counted with the other file operations, so closing waits for it
+    fn start_rename(&mut self, old: String, new: String) {
+        let (tx, rx) = channel();
+        self.rename_rx = Some(rx);
+        let from = self.base_dir.join(&old);
+        let to = self.base_dir.join(&new);
+        self.spawn_file_task(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                if !from.exists() {
+                    // Tag mode never made the folder
+                    Ok(())
+                } else if to.exists() {
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        t!("already-exists", path = path_arg(&to)),
+                    ))
+                } else {
+                    std::fs::rename(&from, &to)
+                }
+            })
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+            let _ = tx.send((old, new, result));
+        });
+    }
+
+    fn poll_category_rename(&mut self) {
+        if self
+            .held_rename
+            .as_ref()
+            .is_some_and(|(old, _)| !self.moving_into(&[old]))
+        {
+            if let Some((old, new)) = self.held_rename.take() {
+                self.start_rename(old, new);
+            }
+        }
+        let Some(rx) = &self.rename_rx else {
+            return;
+        };
+        let (old, new, result) = match rx.try_recv() {
+            Ok(renamed) => renamed,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.rename_rx = None;
+                return;
+            }
+        };
+        self.rename_rx = None;
+        if let Err(e) = result {
+            self.notifications.error(t!(
+                "category-rename-failed",
+                old = old.as_str(),
+                new = new.as_str(),
+                error = e.to_string()
+            ));
+            return;
+        }
+
+        let old_dir = self.base_dir.join(&old);
+        let new_dir = self.base_dir.join(&new);
+        let moved = |path: &Path| match path.strip_prefix(&old_dir) {
+            Ok(rest) => new_dir.join(rest),
+            Err(_) => path.to_path_buf(),
+        };
+
+        let Some(mut bucket) = self.category_buckets.remove(&old) else {
+            return;
+        };
+        let mut tagged = Vec::new();
+        for file in bucket.paths().cloned().collect::<Vec<_>>() {
+            let renamed = moved(&file);
+            if renamed == file {
+                tagged.push(file);
+                continue;
+            }
+            self.follow_thumbnail(&file, &renamed);
+            self.follow_favorite(&file, &renamed);
+            bucket.rename_file(&file, renamed);
+        }
+        self.set_category_color(&new, bucket.color);
+        self.config.category_colors.remove(&old);
+        self.save_config();
+        self.category_buckets.insert(new.clone(), bucket);
+        for category in &mut self.categories {
+            if *category == old {
+                category.clone_from(&new);
+            }
+        }
+        self.input_categories = self.categories.join(", ");
+
+        for op in self
+            .moves
+            .iter_mut()
+            .chain(self.redo_stack.iter_mut())
+            .chain(self.undone.iter_mut())
+            .filter(|op| op.category.as_deref() == Some(old.as_str()))
+        {
+            op.to = moved(&op.to);
+            op.category = Some(new.clone());
+        }
+        for merge in &mut self.merges {
+            for name in [&mut merge.source, &mut merge.target] {
+                if *name == old {
+                    name.clone_from(&new);
+                }
+            }
+            for (from, to) in &mut merge.files {
+                *from = moved(from);
+                *to = moved(to);
+            }
+        }
+        for collision in &mut self.collisions {
+            if collision.category == old {
+                collision.category.clone_from(&new);
+                collision.existing = moved(&collision.existing);
+            }
+        }
+
+        // Files tagged rather than moved carry the name in their sidecar
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            for path in tagged {
+                retag(path, old.clone(), new.clone(), &notifier).await;
+            }
+        });
+    }
+
+    // Settle cards still flying into `category` so its file list is complete
+    fn land_pending_moves(&mut self, category: &str) {
+        let (landing, pending): (Vec<PendingMove>, Vec<PendingMove>) = self
+            .pending_moves
+            .drain(..)
+            .partition(|pm| pm.category.as_deref() == Some(category));
+        self.pending_moves = pending;
+        for pending_move in landing {
+            self.animations
+                .retain(|anim| anim.path != pending_move.from);
+            self.complete_move(pending_move);
+        }
+    }
+
+    // Fold `source` into `target`, once the files moving into either have
+    // landed: its files move into the target folder (renamed on collision),
+    // then the source bucket and folder go away in `poll_category_merge`
+    fn merge_categories(&mut self, source: &str, target: &str) {
+        if source == target
+            || !self.category_buckets.contains_key(source)
+            || !self.category_buckets.contains_key(target)
+            || self.reorganizing()
+        {
+            return;
+        }
+        self.land_pending_moves(source);
+        self.land_pending_moves(target);
+        if self.moving_into(&[source, target]) {
+            self.held_merge = Some((source.to_string(), target.to_string()));
+            return;
+        }
+        self.start_merge(source.to_string(), target.to_string());
+    }
+
+    // Picking free names and moving the files happen in one task, counted
+    // with the other file operations
+    fn start_merge(&mut self, source: String, target: String) {
+        let Some(bucket) = self.category_buckets.get(&source) else {
+            return;
+        };
+        let sources: Vec<PathBuf> = bucket.paths().cloned().collect();
+        let (tx, rx) = channel();
+        self.merge_rx = Some(rx);
+        let base_dir = self.base_dir.clone();
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            let source_dir = base_dir.join(&source);
+            let target_dir = base_dir.join(&target);
+            let files = tokio::task::spawn_blocking(move || {
+                let mut taken = HashSet::new();
+                let mut files = Vec::new();
+                for from in sources {
+                    let Some(name) = from.file_name() else {
+                        continue;
+                    };
+                    // Tagged files live in the base folder and only change tag
+                    let to = if from.parent() == Some(base_dir.as_path()) {
+                        from.clone()
+                    } else {
+                        unique_destination(&target_dir, name, &taken)
+                    };
+                    taken.insert(to.clone());
+                    files.push((from, to));
+                }
+                files
+            })
+            .await
+            .unwrap_or_default();
+
+            let mut merged = Vec::new();
+            for (from, to) in files {
+                if from == to {
+                    retag(from.clone(), source.clone(), target.clone(), &notifier).await;
+                } else if let Err(e) = move_file(&from, &to).await {
+                    notifier.error(t!(
+                        "move-failed",
+                        path = path_arg(&from),
+                        error = e.to_string()
+                    ));
+                    continue;
+                }
+                merged.push((from, to));
+            }
+            if source_dir.exists() {
+                if let Err(e) = tokio::fs::remove_dir(&source_dir).await {
+                    notifier.error(t!(
+                        "folder-remove-failed",
+                        path = path_arg(&source_dir),
+                        error = e.to_string()
+                    ));
+                }
+            }
+            let _ = tx.send(Merged {
+                source,
+                target,
+                files: merged,
+            });
+        });
+    }
+
+    fn poll_category_merge(&mut self) {
+        if self
+            .held_merge
+            .as_ref()
+            .is_some_and(|(source, target)| !self.moving_into(&[source, target]))
+        {
+            if let Some((source, target)) = self.held_merge.take() {
+                self.start_merge(source, target);
+            }
+        }
+        let Some(rx) = &self.merge_rx else {
+            return;
+        };
+        let Merged {
+            source,
+            target,
+            files,
+        } = match rx.try_recv() {
+            Ok(merged) => merged,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.merge_rx = None;
+                return;
+            }
+        };
+        self.merge_rx = None;
+
+        self.follow_category_files(&files, &source, &target);
+        let Some(index) = self.categories.iter().position(|c| *c == source) else {
+            return;
+        };
+        self.categories.remove(index);
+        let Some(bucket) = self.category_buckets.remove(&source) else {
+            return;
+        };
+        self.merges.push(MergeOperation {
+            source,
+            target,
+            color: bucket.color,
+            index,
+            files,
+            timestamp: Instant::now(),
+        });
+    }
+
+    fn revert_last_merge(&mut self) {
+        let Some(merge) = self.merges.pop() else {
+            return;
+        };
+
+        if self.config.sort_mode.uses_folders() || merge.files.iter().any(|(a, b)| a != b) {
+            if let Err(e) = std::fs::create_dir_all(self.base_dir.join(&merge.source)) {
+                self.notifications.error(t!(
+                    "folder-recreate-failed",
+                    name = merge.source.as_str(),
+                    error = e.to_string()
+                ));
+                self.merges.push(merge);
+                return;
+            }
+        }
+        self.land_pending_moves(&merge.target);
+        self.category_buckets
+            .insert(merge.source.clone(), CategoryBucket::new(merge.color));
+        let index = merge.index.min(self.categories.len());
+        self.categories.insert(index, merge.source.clone());
+
+        let files: Vec<(PathBuf, PathBuf)> = merge
+            .files
+            .into_iter()
+            .map(|(from, to)| (to, from))
+            .collect();
+        self.apply_category_files(&files, &merge.target, &merge.source, false);
+    }
+
+    // Carry files from one category to another in the window: buckets,
+    // thumbnails, favorites and the moves that undo from them
+    fn follow_category_files(
+        &mut self,
+        files: &[(PathBuf, PathBuf)],
+        from_category: &str,
+        to_category: &str,
+    ) {
+        for (from, to) in files {
+            self.follow_thumbnail(from, to);
+            self.follow_favorite(from, to);
+            let size = self
+                .category_buckets
+                .get_mut(from_category)
+                .and_then(|bucket| bucket.remove_file(from));
+            if let Some(bucket) = self.category_buckets.get_mut(to_category) {
+                bucket.add_file(to.clone(), size);
+            }
+            // Earlier moves now undo from the new location
+            for op in self
+                .moves
+                .iter_mut()
+                .filter(|op| &op.to == from && op.category.as_deref() == Some(from_category))
+            {
+                op.to = to.clone();
+                op.category = Some(to_category.to_string());
+            }
+        }
+        self.redo_stack.clear();
+    }
+
+    // Carry files from one category to another, in the UI right away and on
+    // disk in the background, optionally removing `from_category`'s emptied folder
+    fn apply_category_files(
+        &mut self,
+        files: &[(PathBuf, PathBuf)],
+        from_category: &str,
+        to_category: &str,
+        remove_dir: bool,
+    ) {
+        self.follow_category_files(files, from_category, to_category);
+
+        let files = files.to_vec();
+        let from_category = from_category.to_string();
+        let to_category = to_category.to_string();
+        let dir = self.base_dir.join(&from_category);
+        let notifier = self.notifications.notifier();
+        self.spawn_file_task(async move {
+            for (from, to) in files {
+                if from == to {
+                    retag(from, from_category.clone(), to_category.clone(), &notifier).await;
+                } else if let Err(e) = move_file(&from, &to).await {
+                    notifier.error(t!(
+                        "move-failed",
+                        path = path_arg(&from),
+                        error = e.to_string()
+                    ));
+                }
+            }
+            if remove_dir && dir.exists() {
+                if let Err(e) = tokio::fs::remove_dir(&dir).await {
+                    notifier.error(t!(
+                        "folder-remove-failed",
+                        path = path_arg(&dir),
+                        error = e.to_string()
+                    ));
+                }
+            }
+        });
+    }
+
+    // Everything the loader has finished with since the last frame. Decoded
+    // images join the upload queue; the rest no longer count as loading
+    fn handle_load_events(&mut self) {
+        while let Ok(event) = self.load_rx.try_recv() {
+            match event {
+                LoadEvent::Loaded(loaded) => {
+                    #[cfg(feature = "video")]
+                    if leftright::video::take_missing_ffmpeg() {
+                        self.notifications.warn(t!("ffmpeg-missing"));
+                    }
+                    self.uploads.push_back(loaded);
+                }
+                LoadEvent::TooLarge(path, pixels) => {
+                    self.give_up_load();
+                    if self.oversized.insert(path.clone(), pixels).is_none() {
+                        self.notifications.error(t!(
+                            "oversized-skipped",
+                            name = self.display_name(&path),
+                            megapixels = pixels / 1_000_000
+                        ));
+                    }
+                }
+                LoadEvent::Failed(path) => {
+                    self.give_up_load();
+                    if self.undecodable.insert(path.clone()) {
+                        self.notifications
+                            .error(t!("decode-failed", path = path_arg(&path)));
+                    }
+                }
+                LoadEvent::Cancelled(path) => {
+                    self.thumbnail_loads.remove(&path);
+                }
+            }
+        }
+    }
+
+    // Take every image that couldn't be decoded out of the queue. The current
+    // image stays if it loaded; otherwise the next one that did takes over
+    fn skip_failures(&mut self) {
+        let failed = |path: &PathBuf| self.undecodable.contains(path);
+        let before_current = self.current_image.map(|idx| {
+            self.images
+                .iter()
+                .take(idx)
+                .filter(|path| !failed(path))
+                .count()
+        });
+        let count = self.images.iter().filter(|path| failed(path)).count();
+        self.images.retain(|path| !self.undecodable.contains(path));
+        self.current_image = before_current
+            .filter(|_| !self.images.is_empty())
+            .map(|idx| idx.min(self.images.len() - 1));
+        self.notifications
+            .info(t!("skipped-failures", count = count));
+    }
+
+    // Notice when the current image was deleted, renamed or written over by
+    // something else, and act on what earlier checks found
+    fn check_current_file(&mut self, ctx: &egui::Context) {
+        if let Some(path) = self.current_image.and_then(|idx| self.images.get(idx)) {
+            self.file_checks.request(path, &self.loader.runtime, ctx);
+            // Without a watcher to wake the window, an idle one still looks
+            // every so often, so a file gone while nobody touched anything
+            // doesn't stay on screen
+            if self.folder_watcher.is_none() {
+                ctx.request_repaint_after(CURRENT_FILE_RECHECK);
+            }
+        }
+        for (path, change) in self.file_checks.poll() {
+            match change {
+                Change::Gone => self.drop_missing(&path),
+                Change::Replaced => self.reload_replaced(path, ctx),
+            }
+        }
+
+        // Moves that waited on a look at their file go ahead; one whose file
+        // was found gone has just left the queue
+        let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.held_moves)
+            .into_iter()
+            .partition(|(held, _)| self.file_checks.recent(&held.from).is_some());
+        self.held_moves = waiting;
+        for (held, center) in ready {
+            let Some(idx) = self.images.position(&held.from) else {
+                continue;
+            };
+            let current = self
+                .current_image
+                .and_then(|idx| self.images.get(idx))
+                .cloned();
+            self.current_image = Some(idx);
+            self.file_image_to(held.category, held.to, center, ctx);
+            if let Some(idx) = current.and_then(|current| self.images.position(&current)) {
+                self.current_image = Some(idx);
+            }
+        }
+    }
+
+    // Take a file that's no longer on disk out of the queue
+    fn drop_missing(&mut self, path: &Path) {
+        let Some(idx) = self.images.iter().position(|p| p == path) else {
+            return;
+        };
+        self.images.remove(idx);
+        self.cancel_load(path);
+        self.drop_textures(path);
+        self.file_checks.forget(path);
+        self.current_image = match self.current_image {
+            _ if self.images.is_empty() => None,
+            Some(current) if idx < current => Some(current - 1),
+            current => current.map(|current| current.min(self.images.len() - 1)),
+        };
+        self.notifications
+            .warn(t!("file-disappeared", name = self.display_name(path)));
+    }
+
+    // Decode a file again that was written over after it was decoded. The old
+    // texture stays up until the new one arrives
+    fn reload_replaced(&mut self, path: PathBuf, ctx: &egui::Context) {
+        if !self.images.contains(&path) {
+            return;
+        }
+        self.undecodable.remove(&path);
+        self.oversized.remove(&path);
+        let current = self.current_image.and_then(|idx| self.images.get(idx)) == Some(&path);
+        log::info!("{} changed on disk, loading it again", path.display());
+        self.request_load(path, ctx, current);
+    }
+
+    // A load that won't produce a texture stops holding up the loading screen
+    fn give_up_load(&mut self) {
+        if self.is_loading {
+            self.total_images_to_load = self.total_images_to_load.saturating_sub(1);
+            if self.textures.len() >= self.total_images_to_load {
+                self.is_loading = false;
+                self.log_load_summary();
+            }
+        }
+    }
+
+    fn update_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        // Upload finished decodes, the current image first, until this frame's
+        // budget runs out
+        self.handle_load_events();
+        // An undo or newly found files reopen the queue, so finishing it
+        // again plays the fanfare again
+        if !self.images.is_empty() {
+            self.finished = false;
+        }
+        if let Some(path) = self.current_image.and_then(|idx| self.images.get(idx)) {
+            if let Some(pos) = self.uploads.iter().position(|loaded| &loaded.path == path) {
+                let loaded = self.uploads.remove(pos).unwrap();
+                self.uploads.push_front(loaded);
+            }
+        }
+        let started = Instant::now();
+        while started.elapsed() < UPLOAD_BUDGET {
+            let Some(LoadedImage {
+                path,
+                image,
+                thumbnail,
+                has_alpha,
+                pages,
+                modified,
+            }) = self.uploads.pop_front()
+            else {
+                break;
+            };
+            if has_alpha {
+                self.transparent.insert(path.clone());
+            }
+            self.file_checks.decoded(&path, modified);
+            if pages > 1 {
+                self.page_counts.insert(path.clone(), pages);
+            }
+            // Images outside the preload window only need the thumbnail,
+            // and queued ones only the full texture until they're sorted
+            let name = path.to_string_lossy().to_string();
+            if self.thumbnail_loads.remove(&path) {
+                let thumbnail = self.loader.stats.time(Stage::Upload, || {
+                    loader::upload(ctx, format!("{} (thumbnail)", name), thumbnail)
+                });
+                self.thumbnails.insert(path.clone(), thumbnail);
+            } else {
+                let texture = self
+                    .loader
+                    .stats
+                    .time(Stage::Upload, || loader::upload(ctx, name, image));
+                self.textures.insert(path.clone(), texture);
+                // Goes with the full texture when that's evicted
+                self.thumbnail_images.insert(path.clone(), thumbnail);
+            }
+
+            if self.is_loading {
+                self.loading_progress =
+                    ((self.textures.len() as f32) / (self.total_images_to_load as f32)).min(1.0);
+                if self.textures.len() >= self.total_images_to_load {
+                    self.is_loading = false;
+                    self.log_load_summary();
+                }
+            }
+        }
+        if !self.uploads.is_empty() {
+            ctx.request_repaint();
+        }
+        self.check_current_file(ctx);
+        if self.is_loading {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 2.0 - 20.0);
+                ui.add(egui::ProgressBar::new(self.loading_progress).show_percentage());
+                ui.label(t!(
+                    "loading-images",
+                    loaded = self.textures.len(),
+                    total = self.total_images_to_load
+                ));
+            });
+            return;
+        }
+
+        // Make sure the current image is loaded, and the next few once the
+        // window has moved
+        if let Some(current_idx) = self.current_image {
+            self.prioritize_current(current_idx, ctx);
+            let filled = self.images.get(current_idx).map(|path| {
+                (
+                    path.clone(),
+                    current_idx,
+                    self.images.len(),
+                    self.may_decode(),
+                )
+            });
+            if filled != self.window_filled {
+                self.ensure_textures_loaded(current_idx, ctx);
+                self.window_filled = filled;
+            }
+        }
+
+        let panel_size = ui.available_size();
+        let center = ui.available_rect_before_wrap().center();
+
+        // Draw buckets first (background layer)
+        let bucket_action = self.draw_buckets(ui, center, panel_size);
+
+        // Draw current image (middle layer) only if not animating
+        self.image_rect = None;
+        let mut swiped = None;
+        let mut decode_anyway = None;
+        let mut decode_again = None;
+        let comparing = self.comparing(ctx);
+        if comparing && self.animations.is_empty() {
+            self.draw_previous_image(ui, center, panel_size);
+        } else if self.animations.is_empty() {
+            if let Some(current_idx) = self.current_image {
+                if let Some(path) = self.images.get(current_idx) {
+                    let page = match &self.page {
+                        Some((paged, page, texture)) if paged == path => {
+                            Some((*page, texture.as_ref()))
+                        }
+                        _ => None,
+                    };
+                    if let Some(texture) = page
+                        .and_then(|(_, texture)| texture)
+                        .or_else(|| self.textures.get(path))
+                        .or_else(|| self.thumbnails.get(path))
+                    {
+                        let zoom = match &self.zoom {
+                            Some((zoomed, zoom)) if zoomed == path => *zoom,
+                            _ => 1.0,
+                        };
+                        let panel = egui::Rect::from_center_size(center, panel_size);
+                        let (image_size, uv) =
+                            fit_image(self.config.image_fit, texture.size_vec2(), panel, zoom);
+
+                        // Images bigger than the window at actual size are
+                        // dragged around instead of swiped
+                        let overflow = ((image_size - panel_size) / 2.0).max(egui::Vec2::ZERO);
+                        let pannable = self.config.image_fit == ImageFit::Actual
+                            && overflow != egui::Vec2::ZERO;
+                        let pan = match &self.pan {
+                            Some((panned, pan)) if panned == path && pannable => {
+                                pan.clamp(-overflow, overflow)
+                            }
+                            _ => egui::Vec2::ZERO,
+                        };
+
+                        let image_rect =
+                            egui::Rect::from_center_size(center + self.swipe + pan, image_size);
+                        if self.config.checkerboard && self.transparent.contains(path) {
+                            theme::paint_checkerboard(ui.painter(), image_rect, &self.palette());
+                        }
+                        let tint = if self.paused {
+                            egui::Color32::from_gray(60)
+                        } else {
+                            egui::Color32::WHITE
+                        };
+
+                        // Fade over from the previous image instead of cutting. An
+                        // image that had to be waited for fades in from nothing
+                        if self.shown.as_ref().is_none_or(|(shown, ..)| shown != path) {
+                            let from = self
+                                .shown
+                                .as_ref()
+                                .filter(|(shown, ..)| self.images.contains(shown))
+                                .map(|(_, texture, rect, uv)| (texture.clone(), *rect, *uv));
+                            if !self.config.reduce_motion && self.config.crossfade_duration > 0.0 {
+                                self.crossfade = Some(Crossfade {
+                                    from,
+                                    start: Instant::now(),
+                                });
+                            }
+                            retire_shown(&mut self.shown, &mut self.previous_image);
+                        }
+                        self.shown = Some((path.clone(), texture.clone(), image_rect, uv));
+                        let fade = self
+                            .crossfade
+                            .as_ref()
+                            .map(|fade| {
+                                fade.start.elapsed().as_secs_f32() / self.config.crossfade_duration
+                            })
+                            .filter(|t| *t < 1.0)
+                            .map(|t| self.config.easing.apply(t).clamp(0.0, 1.0));
+                        if fade.is_none() {
+                            self.crossfade = None;
+                        }
+                        if let (Some(t), Some((old, old_rect, old_uv))) = (
+                            fade,
+                            self.crossfade.as_ref().and_then(|fade| fade.from.as_ref()),
+                        ) {
+                            ui.painter().image(
+                                old.id(),
+                                *old_rect,
+                                *old_uv,
+                                tint.gamma_multiply(1.0 - t),
+                            );
+                        }
+                        // Dragging the image (or swiping it on a touchscreen)
+                        // sorts it in that direction
+                        let response = board::card(
+                            ui,
+                            image_rect,
+                            texture.id(),
+                            uv,
+                            tint.gamma_multiply(fade.unwrap_or(1.0)),
+                            accessibility::image_name(
+                                &self.display_name(path),
+                                current_idx + 1,
+                                self.images.len(),
+                            ),
+                            !self.paused,
+                        );
+                        if fade.is_some() {
+                            ctx.request_repaint();
+                        }
+                        if pannable {
+                            if response.dragged() {
+                                let pan = (pan + response.drag_delta()).clamp(-overflow, overflow);
+                                self.pan = Some((path.clone(), pan));
+                            }
+                        } else if response.dragged() {
+                            self.swipe += response.drag_delta();
+                        } else if response.drag_released() {
+                            let velocity = ui.input(|i| i.pointer.velocity());
+                            swiped = swipe_direction(self.swipe, velocity)
+                                .filter(|idx| *idx < self.categories.len());
+                        }
+                        if swiped.is_none() && !response.dragged() && self.swipe != egui::Vec2::ZERO
+                        {
+                            // Snap back
+                            self.swipe *= 0.7;
+                            if self.swipe.length() < 1.0 {
+                                self.swipe = egui::Vec2::ZERO;
+                            }
+                            ctx.request_repaint();
+                        }
+
+                        let mut caption = Vec::new();
+                        if let Some(pages) = self.page_counts.get(path) {
+                            let page = page.map_or(0, |(page, _)| page);
+                            caption.push(t!("page-of", page = page + 1, pages = *pages));
+                        }
+                        if let Some((idx, len)) = self.groups.get(path) {
+                            caption.push(t!("group-position", position = *idx, count = *len));
+                        }
+                        if !caption.is_empty() {
+                            ui.painter().text(
+                                image_rect.center_bottom() + egui::vec2(0.0, 6.0),
+                                egui::Align2::CENTER_TOP,
+                                caption.join(" · "),
+                                egui::FontId::proportional(
+                                    14.0 * accessibility::text_scale(ui.style()),
+                                ),
+                                ui.visuals().weak_text_color(),
+                            );
+                        }
+                        if let Some(Some(rating)) = self.ratings.get(path) {
+                            let stars =
+                                "★".repeat(*rating as usize) + &"☆".repeat(5 - *rating as usize);
+                            draw_banner(
+                                ui,
+                                image_rect.center_bottom() - egui::vec2(0.0, 24.0),
+                                stars,
+                            );
+                        }
+
+                        // Star toggle in the corner, shown while hovered or
+                        // once starred
+                        let favorite = self.is_favorite(path);
+                        let star_rect = egui::Rect::from_center_size(
+                            image_rect.right_top() + egui::vec2(-22.0, 22.0),
+                            egui::vec2(28.0, 28.0),
+                        );
+                        let star = accessibility::button(
+                            ui,
+                            star_rect,
+                            egui::Id::new("favorite_star"),
+                            &if favorite {
+                                t!("unstar-image")
+                            } else {
+                                t!("star-image")
+                            },
+                            !self.paused,
+                        );
+                        if favorite || response.hovered() || star.hovered() || star.has_focus() {
+                            ui.painter().circle_filled(
+                                star_rect.center(),
+                                14.0,
+                                egui::Color32::from_black_alpha(120),
+                            );
+                            ui.painter().text(
+                                star_rect.center(),
+                                egui::Align2::CENTER_CENTER,
+                                if favorite { "★" } else { "☆" },
+                                egui::FontId::proportional(
+                                    20.0 * accessibility::text_scale(ui.style()),
+                                ),
+                                if favorite {
+                                    FAVORITE_COLOR
+                                } else {
+                                    egui::Color32::WHITE
+                                },
+                            );
+                        }
+                        if star.clicked() {
+                            self.control_action = Some(Action::Favorite);
+                        }
+
+                        self.last_image_pos = Some(image_rect.center());
+                        self.image_rect = Some(image_rect);
+                    } else if let Some(pixels) = self.oversized.get(path) {
+                        retire_shown(&mut self.shown, &mut self.previous_image);
+                        self.crossfade = None;
+                        ui.allocate_ui_at_rect(
+                            egui::Rect::from_center_size(center, egui::vec2(260.0, 60.0)),
+                            |ui| {
+                                ui.vertical_centered(|ui| {
+                                    ui.weak(t!("oversized", megapixels = pixels / 1_000_000));
+                                    if ui
+                                        .button(t!("decode-anyway"))
+                                        .on_hover_text(t!("decode-anyway-hint"))
+                                        .clicked()
+                                    {
+                                        decode_anyway = Some(path.clone());
+                                    }
+                                });
+                            },
+                        );
+                    } else if self.undecodable.contains(path) {
+                        retire_shown(&mut self.shown, &mut self.previous_image);
+                        self.crossfade = None;
+                        ui.allocate_ui_at_rect(
+                            egui::Rect::from_center_size(center, egui::vec2(260.0, 60.0)),
+                            |ui| {
+                                ui.vertical_centered(|ui| {
+                                    ui.weak(t!("undecodable"));
+                                    if ui.button(t!("decode-again")).clicked() {
+                                        decode_again = Some(path.clone());
+                                    }
+                                });
+                            },
+                        );
+                    } else if let Some((_, since)) = &self.priority_load {
+                        // Nothing to fade from once the next image finally shows,
+                        // so it fades in on its own
+                        retire_shown(&mut self.shown, &mut self.previous_image);
+                        self.crossfade = None;
+                        // Slow (e.g. network) files show they're working rather than hung
+                        let elapsed = since.elapsed().as_secs_f32();
+                        ui.allocate_ui_at_rect(
+                            egui::Rect::from_center_size(center, egui::vec2(300.0, 24.0)),
+                            |ui| {
+                                ui.horizontal_centered(|ui| {
+                                    ui.spinner();
+                                    ui.weak(t!(
+                                        "loading-this-image",
+                                        seconds = format!("{:.1}", elapsed)
+                                    ));
+                                });
+                            },
+                        );
+                        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    }
+                }
+            }
+        }
+
+        if self.paused {
+            let text = t!("paused-banner", key = self.key_hint(ctx, Action::Pause));
+            draw_banner(ui, center, text);
+        }
+        let above_image = center - egui::vec2(0.0, panel_size.y * 0.25);
+        if let Some(batch) = &self.batch {
+            let category = self.categories.get(batch.category).cloned();
+            let text = t!(
+                "batch-banner",
+                count = batch.remaining,
+                category = category.unwrap_or_default()
+            );
+            draw_banner(ui, above_image, text);
+        } else if !self.repeat_count.is_empty() {
+            let text = t!("repeat-banner", count = self.repeat_count.as_str());
+            draw_banner(ui, above_image, text);
+        }
+
+        if let Some(path) = decode_anyway {
+            self.oversized.remove(&path);
+            self.decode_anyway.insert(path.clone());
+            self.request_load(path, ctx, true);
+        }
+        if let Some(path) = decode_again {
+            self.undecodable.remove(&path);
+            self.request_load(path, ctx, true);
+        }
+
+        if let Some(idx) = swiped {
+            // The fly-away starts from where the swipe let go
+            self.redo_stack.clear();
+            self.move_image(idx, center, ctx);
+            self.swipe = egui::Vec2::ZERO;
+        }
+
+        self.draw_filmstrip(ui, ctx);
+
+        // Completion summary once the queue is empty
+        if self.images.is_empty() && self.images.hidden() > 0 {
+            draw_banner(
+                ui,
+                center,
+                t!("filter-no-matches", hidden = self.images.hidden()),
+            );
+        } else if self.images.is_empty() && self.scan_rx.is_none() && self.animations.is_empty() {
+            if !self.finished && !self.moves.is_empty() {
+                self.sounds.play(Cue::Done);
+            }
+            self.finished = true;
+            let kept = self.moves.iter().filter(|op| op.category.is_none()).count();
+            ui.painter().text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                t!("all-done", moved = self.moves.len() - kept, kept = kept),
+                egui::FontId::proportional(24.0 * accessibility::text_scale(ui.style())),
+                ui.visuals().text_color(),
+            );
+
+            let button_rect = egui::Rect::from_center_size(
+                center + egui::vec2(0.0, 50.0),
+                egui::vec2(220.0, 24.0),
+            );
+            if ui
+                .put(button_rect, egui::Button::new(t!("export-report")))
+                .clicked()
+            {
+                self.export_report();
+            }
+
+            if self.moves.iter().any(|op| op.mode == SortMode::Tag) {
+                let button_rect = button_rect.translate(egui::vec2(0.0, 30.0));
+                if ui
+                    .put(button_rect, egui::Button::new(t!("export-tags")))
+                    .clicked()
+                {
+                    self.export_tags();
+                }
+            }
+            if self.moves.iter().any(|op| op.mode.is_link())
+                && self.link_commit.is_none()
+                && !self.config.dry_run
+                && self.planned.is_empty()
+            {
+                let button_rect = button_rect.translate(egui::vec2(0.0, 60.0));
+                if ui
+                    .put(button_rect, egui::Button::new(t!("commit-links")))
+                    .on_hover_text(t!("commit-links-hint"))
+                    .clicked()
+                {
+                    self.commit_links();
+                }
+            }
+
+            let favorites = self.favorites();
+            if !favorites.is_empty() {
+                let area = egui::Rect::from_center_size(
+                    center + egui::vec2(0.0, 180.0),
+                    egui::vec2(320.0, 160.0),
+                );
+                ui.allocate_ui_at_rect(area, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.strong(t!("favorites-heading", count = favorites.len()));
+                        egui::ScrollArea::vertical()
+                            .max_height(80.0)
+                            .show(ui, |ui| {
+                                for path in &favorites {
+                                    ui.label(self.display_name(path))
+                                        .on_hover_text(path.display().to_string());
+                                }
+                            });
+                        ui.horizontal(|ui| {
+                            if ui.button(t!("copy-list")).clicked() {
+                                let list: Vec<String> = favorites
+                                    .iter()
+                                    .map(|path| path.display().to_string())
+                                    .collect();
+                                ctx.output_mut(|o| o.copied_text = list.join("\n"));
+                            }
+                            if ui.button(t!("copy-files")).clicked() {
+                                self.copy_favorites();
+                            }
+                        });
+                    });
+                });
+            }
+        }
+
+        // Draw animations (top layer)
+        self.update_animations(ui, panel_size);
+
+        // Bucket and top bar buttons count as input only where keys would
+        let control_action = self.control_action.take().or(bucket_action);
+
+        // Handle keyboard input, unless a key is being captured for rebinding
+        if self.input_mode != InputMode::Direct {
+            if let Some(idx) = self.handle_chord_input(ctx) {
+                self.redo_stack.clear();
+                self.move_image(idx, center, ctx);
+            }
+        } else if self.rebinding.is_none()
+            && self.new_category.is_none()
+            && self.collisions.is_empty()
+            && self.move_all.is_none()
+            && self.confirm_move.is_none()
+            && !matches!(self.big_folder, BigFolder::Asking | BigFolder::Declined)
+            && !self.reorganizing()
+            && self.editing_category.is_none()
+        {
+            let mouse_action = self.mouse_action(ui);
+            // Keys move between controls while one has keyboard focus
+            let focused = ctx.memory(|m| m.focus().is_some());
+            let bindings = &self.config.key_bindings;
+            let (action, repeat, digits, question_mark, slash, escape) = ui.input_mut(|i| {
+                // Shift with a category key repeats the last batch size
+                let repeat = bindings.categories.iter().position(|binding| {
+                    i.consume_shortcut(&egui::KeyboardShortcut::new(
+                        binding.modifiers | egui::Modifiers::SHIFT,
+                        binding.key,
+                    ))
+                });
+                let action = bindings.actions().into_iter().find(|action| {
+                    bindings
+                        .get(*action)
+                        .is_some_and(|binding| i.consume_shortcut(&binding.shortcut()))
+                });
+                let digits: String = i
+                    .events
+                    .iter()
+                    .filter_map(|e| match e {
+                        egui::Event::Text(text) if text.chars().all(|c| c.is_ascii_digit()) => {
+                            Some(text.as_str())
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let (action, repeat, digits) = if focused {
+                    (None, None, String::new())
+                } else {
+                    (action, repeat, digits)
+                };
+                (
+                    action
+                        .or(control_action)
+                        .or(mouse_action)
+                        .or(self.gamepad_action),
+                    repeat,
+                    digits,
+                    i.events
+                        .iter()
+                        .any(|e| matches!(e, egui::Event::Text(text) if text == "?")),
+                    !focused
+                        && i.events
+                            .iter()
+                            .any(|e| matches!(e, egui::Event::Text(text) if text == "/")),
+                    i.key_pressed(egui::Key::Escape),
+                )
+            });
+
+            // While paused only resuming gets through, and a running batch
+            // takes nothing but Escape to stop it
+            let busy = self.paused || self.batch.is_some();
+            let action = action.filter(|action| !busy || *action == Action::Pause);
+            if self.batch.is_some() && escape {
+                self.batch = None;
+            }
+            if !busy {
+                if let Some(idx) = repeat.filter(|idx| *idx < self.categories.len()) {
+                    self.redo_stack.clear();
+                    self.start_batch(idx, self.last_batch);
+                }
+                if self.config.rate_with_numbers {
+                    // 1-5 stars, 0 to clear
+                    if let Some(rating) = digits.chars().last().and_then(|c| c.to_digit(10)) {
+                        if rating <= 5 {
+                            self.rate_image((rating > 0).then_some(rating as u8));
+                        }
+                    }
+                } else if self.repeat_count.len() + digits.len() <= 3 {
+                    // A few digits is plenty; a stuck key shouldn't queue thousands
+                    self.repeat_count.push_str(&digits);
+                }
+            }
+
+            let count = match action {
+                Some(Action::Category(_)) => std::mem::take(&mut self.repeat_count).parse().ok(),
+                _ => None,
+            };
+            if action.is_some() || escape {
+                self.repeat_count.clear();
+            }
+            match action {
+                Some(Action::Category(idx)) if idx < self.categories.len() => {
+                    self.redo_stack.clear();
+                    match count {
+                        _ if !self.selected.is_empty() => self.file_selected(idx, center, ctx),
+                        Some(count) if count > 1 => self.start_batch(idx, count),
+                        _ => self.move_image(idx, center, ctx),
+                    }
+                }
+                Some(Action::Undo) => self.revert_last_move(),
+                Some(Action::Redo) => self.redo_last_move(center, ctx),
+                Some(Action::Skip) => self.skip_image(),
+                Some(Action::Previous) => self.previous_image(),
+                Some(Action::Delete) => self.delete_image(),
+                Some(Action::Keep) => {
+                    self.redo_stack.clear();
+                    self.file_image(None, center, ctx);
+                }
+                Some(Action::Help) => self.show_help = !self.show_help,
+                Some(Action::History) => self.show_history = !self.show_history,
+                Some(Action::Stats) => self.show_stats = !self.show_stats,
+                Some(Action::Filter) => self.toggle_filter_bar(),
+                Some(Action::Favorite) => self.toggle_favorite(),
+                Some(Action::PreviousPage) => self.turn_page(false, ctx),
+                Some(Action::NextPage) => self.turn_page(true, ctx),
+                Some(Action::Chord) if !self.categories.is_empty() => {
+                    self.input_mode = InputMode::Chord;
+                }
+                Some(Action::NewCategory) => self.new_category = Some(String::new()),
+                Some(Action::Pause) => self.set_paused(!self.paused),
+                Some(Action::Fit) => {
+                    self.config.image_fit = self.config.image_fit.next();
+                    self.save_config();
+                }
+                _ => {}
+            }
+
+            if action.is_some() {
+                self.slideshow_since = Instant::now();
+            }
+            if slash && !busy && !self.categories.is_empty() {
+                self.input_mode = InputMode::Palette {
+                    filter: String::new(),
+                    selected: 0,
+                };
+            }
+            if question_mark {
+                self.show_help = !self.show_help;
+            } else if escape {
+                self.show_help = false;
+                self.selected.clear();
+            }
+        }
+
+        self.advance_batch(center, ctx);
+        self.advance_slideshow(ctx);
+
+        if self.show_help {
+            self.draw_help(ctx);
+        }
+        if self.show_overview {
+            self.draw_overview(ctx);
+        }
+
+        if self.input_mode != InputMode::Direct {
+            self.draw_chord_picker(ctx);
+        }
+
+        self.draw_new_category(ctx);
+        self.draw_board_export(ctx);
+        self.draw_collision(ctx);
+        self.draw_move_all(ctx);
+        self.draw_confirm_move(ctx);
+        self.draw_edit_categories(ctx);
+        self.draw_review(ctx);
+
+        // Request repaint if there are active animations
+        if !self.animations.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+
+    // Skip to the next image once it has been on screen for the slideshow
+    // interval without anything happening
+    fn advance_slideshow(&mut self, ctx: &egui::Context) {
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+        if current != self.slideshow_image {
+            self.slideshow_image = current;
+            self.slideshow_since = Instant::now();
+        }
+
+        // The timer doesn't run while paused
+        if self.paused {
+            self.slideshow_since = Instant::now();
+            return;
+        }
+        let Some(interval) = self.config.slideshow_interval else {
+            return;
+        };
+        // Hold still while something else has the user's attention
+        if self.slideshow_image.is_none()
+            || !self.animations.is_empty()
+            || self.input_mode != InputMode::Direct
+            || self.new_category.is_some()
+            || !self.collisions.is_empty()
+            || self.rebinding.is_some()
+        {
+            self.slideshow_since = Instant::now();
+            return;
+        }
+
+        let elapsed = self.slideshow_since.elapsed().as_secs_f32();
+        if elapsed >= interval {
+            self.skip_image();
+            self.slideshow_since = Instant::now();
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(interval - elapsed));
+        }
+    }
+
+    // Where `from` goes in `category`'s folder: under its own name or the
+    // rename template's, numbered if that's taken on disk or by a card in flight
+    fn destination(&self, from: &Path, category: &str) -> PathBuf {
+        let name = self.destination_name(from, category);
+        let taken: HashSet<PathBuf> = self.pending_moves.iter().map(|pm| pm.to.clone()).collect();
+        unique_destination(&self.base_dir.join(category), &name, &taken)
+    }
+
+    fn destination_name(&self, from: &Path, category: &str) -> OsString {
+        match &self.config.rename_template {
+            Some(template) => {
+                let in_flight = self
+                    .pending_moves
+                    .iter()
+                    .filter(|pm| pm.category.as_deref() == Some(category))
+                    .count();
+                let filed = self
+                    .category_buckets
+                    .get(category)
+                    .map_or(0, |bucket| bucket.files.len());
+                let date = rename::needs_date(template)
+                    .then(|| capture_date::capture_date(from))
+                    .flatten()
+                    .map(|(date, _)| date);
+                let context = RenameContext {
+                    category,
+                    index: filed + in_flight + 1,
+                    date,
+                };
+                OsString::from(rename::expand(template, from, &context))
+            }
+            None => from.file_name().unwrap_or_default().to_owned(),
+        }
+    }
+
+    fn collision_policy(&self) -> CollisionPolicy {
+        self.collision_answer
+            .unwrap_or(self.config.collision_policy)
+    }
+
+    // Destination for `from` in `category` under the collision policy, or None
+    // when the move doesn't happen now: skipped, or held until the user answers
+    fn resolve_destination(&mut self, from: &Path, category: &str) -> Option<PathBuf> {
+        let name = self.destination_name(from, category);
+        let taken: HashSet<PathBuf> = self.pending_moves.iter().map(|pm| pm.to.clone()).collect();
+        let dir = self.base_dir.join(category);
+        match sorter::resolve(&dir, &name, self.collision_policy(), &taken) {
+            Resolution::To(to) => Some(to),
+            Resolution::Skip(existing) => {
+                self.notifications.warn(t!(
+                    "collision-skipped",
+                    path = path_arg(from),
+                    existing = path_arg(&existing)
+                ));
+                self.batch = None;
+                None
+            }
+            Resolution::Ask(existing) => {
+                // The image stays queued; sorting carries on with the next one
+                if !self.collisions.iter().any(|c| c.from == from) {
+                    self.collisions.push_back(Collision {
+                        from: from.to_path_buf(),
+                        category: category.to_string(),
+                        existing,
+                    });
+                }
+                self.skip_image();
+                None
+            }
+        }
+    }
+
+    // Carry out the user's answer to a held move
+    fn settle_collision(
+        &mut self,
+        collision: Collision,
+        answer: CollisionPolicy,
+        ctx: &egui::Context,
+    ) {
+        let Some(idx) = self.images.iter().position(|p| *p == collision.from) else {
+            return;
+        };
+        let current = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned();
+
+        match answer {
+            CollisionPolicy::Skip | CollisionPolicy::Ask => {
+                // Back to the front of the queue, ready to be sorted again
+                let path = self.images.remove(idx);
+                let front = current
+                    .and_then(|current| self.images.iter().position(|p| *p == current))
+                    .unwrap_or(0);
+                self.images.insert(front, path);
+                self.current_image = Some(front);
+            }
+            CollisionPolicy::Rename | CollisionPolicy::Overwrite => {
+                let to = if answer == CollisionPolicy::Overwrite {
+                    collision.existing
+                } else {
+                    self.destination(&collision.from, &collision.category)
+                };
+                self.current_image = Some(idx);
+                let center = self.last_image_pos.unwrap_or_default();
+                self.file_image_to(Some(collision.category), to, center, ctx);
+                if let Some(idx) = current.and_then(|c| self.images.iter().position(|p| *p == c)) {
+                    self.current_image = Some(idx);
+                }
+            }
+        }
+    }
+
+    fn move_image(&mut self, direction: usize, center_pos: egui::Pos2, ctx: &egui::Context) {
+        if self.hold_for_confirmation(direction, center_pos) {
+            return;
+        }
+        if let Some(category) = self.categories.get(direction) {
+            self.file_image(Some(category.clone()), center_pos, ctx);
+        }
+    }
+
+    // Take the current image off the queue and into `category`'s bucket, or
+    // into the kept pile without touching the disk when `category` is None
+    fn file_image(
+        &mut self,
+        category: Option<String>,
+        center_pos: egui::Pos2,
+        ctx: &egui::Context,
+    ) {
+        let Some(from) = self
+            .current_image
+            .and_then(|idx| self.images.get(idx))
+            .cloned()
+        else {
+            return;
+        };
+        let to = match &category {
+            Some(category) if self.config.sort_mode.uses_folders() => {
+                match self.resolve_destination(&from, category) {
+                    Some(to) => to,
+                    None => return,
+                }
+            }
+            // Tagged and kept files stay where they are
+            _ => from,
+        };
+        self.file_image_to(category, to, center_pos, ctx);
+    }
+
+    fn file_image_to(
+        &mut self,
+        category: Option<String>,
+        to: PathBuf,
+        center_pos: egui::Pos2,
+        ctx: &egui::Context,
+    ) {
+        if let Some(current_idx) = self.current_image {
+            if self.images.is_empty() || current_idx >= self.images.len() {
+                return;
+            }
+
+            let from = self.images[current_idx].clone();
+            let mode = self.config.sort_mode;
+
+            // Moving a file that's gone would fail, and undoing it would
+            // bring back a file that isn't there. Without a recent look at
+            // the file, the move waits for one in the background
+            match self.file_checks.recent(&from) {
+                None => {
+                    if !self.held_moves.iter().any(|(held, _)| held.from == from) {
+                        self.file_checks.request(&from, &self.loader.runtime, ctx);
+                        let held = PendingMove { from, to, category };
+                        self.held_moves.push((held, center_pos));
+                    }
+                    return;
+                }
+                Some(FileState::Missing) => {
+                    self.drop_missing(&from);
+                    return;
+                }
+                Some(FileState::Present(_)) => {}
+            }
+            self.file_checks.forget(&from);
+
+            // A decode that hasn't finished yet is no longer needed
+            self.cancel_load(&from);
+            // Sorted again while still flying back from an undo
+            self.animations.retain(|anim| anim.path != from);
+
+            // Create animation BEFORE moving the file
+            let has_texture =
+                self.textures.contains_key(&from) || self.thumbnails.contains_key(&from);
+            let mut pending_move = None;
+            let bucket = match &category {
+                Some(category) => self.category_buckets.get_mut(category),
+                None => Some(&mut self.kept_bucket),
+            };
+            if let Some(bucket) = bucket {
+                let start_pos = self.last_image_pos.unwrap_or(center_pos);
+                let end_pos = bucket.rect.center();
+
+                log::debug!(
+                    "Creating animation: start={:?}, end={:?}",
+                    start_pos,
+                    end_pos
+                );
+
+                // Ensure we have the texture before creating the animation
+                if self.config.reduce_motion || !has_texture {
+                    // No animation to wait for, so settle the move right away
+                    pending_move = Some(PendingMove {
+                        from: from.clone(),
+                        to: to.clone(),
+                        category: category.clone(),
+                    });
+                } else {
+                    let animation = Animation {
+                        path: from.clone(),
+                        bucket: category.clone(),
+                        start_pos,
+                        end_pos,
+                        start_time: Instant::now(),
+                        duration: self.config.animation_duration,
+                        start_scale: 1.2, // Start larger
+                        end_scale: 0.6,   // End smaller
+                        easing: self.config.easing,
+                        returning: false,
+                        card_offset: egui::Vec2::ZERO,
+                    };
+                    self.animations.push(animation);
+
+                    // Add to pending moves instead of moving immediately
+                    self.pending_moves.push(PendingMove {
+                        from: from.clone(),
+                        to: to.clone(),
+                        category: category.clone(),
+                    });
+
+                    log::debug!("Added animation and pending move");
+                }
+            }
+
+            if let Some(pending_move) = pending_move {
+                self.complete_move(pending_move);
+            }
+            self.sounds.play(Cue::Sort);
+
+            let move_id = self.next_move_id + 1;
+            match &category {
+                Some(category) => {
+                    if mode == SortMode::Move {
+                        self.follow_favorite(&from, &to);
+                        if self.cross_device.contains(category) {
+                            let size = std::fs::metadata(&from).map_or(0, |m| m.len());
+                            if size >= SPACE_CHECK_BYTES {
+                                self.check_space(category, size);
+                            }
+                        }
+                    }
+                    let sort = FileOp::Sort(sorter::Move {
+                        from: from.clone(),
+                        to: to.clone(),
+                        category: category.clone(),
+                        mode,
+                    });
+                    // Tagging is quick and in place, so nothing waits on it
+                    if mode.uses_folders() {
+                        self.run_move_op(sort, move_id, category.clone());
+                    } else {
+                        self.run_file_op(sort);
+                    }
+                }
+                None => self.set_kept(&from, true),
+            }
+
+            // Record the move operation
+            self.next_move_id = move_id;
+            self.moves.push(MoveOperation {
+                id: move_id,
+                from: from.clone(),
+                to,
+                category: category.clone(),
+                mode,
+                timestamp: Instant::now(),
+                time: SystemTime::now(),
+                batch: self.current_batch,
+            });
+
+            // Remove from images list but keep texture until animation completes
+            self.images.remove(current_idx);
+            if !self.images.is_empty() {
+                self.current_image = Some(current_idx.min(self.images.len() - 1));
+            } else {
+                self.current_image = None;
+            }
+        }
+    }
+
+    fn update_animations(&mut self, ui: &mut egui::Ui, panel_size: egui::Vec2) {
+        let mut completed_animations = Vec::new();
+        let palette = self.palette();
+
+        let kept_target = self.kept_bucket.rect.center();
+        let category_buckets = &self.category_buckets;
+        let scale = layout::ui_scale(panel_size);
+        self.animations.retain_mut(|anim| {
+            let bucket_pos = match &anim.bucket {
+                Some(category) => category_buckets
+                    .get(category)
+                    .map(|bucket| bucket.rect.center()),
+                None => Some(kept_target),
+            };
+            match (bucket_pos, anim.returning) {
+                (Some(pos), true) => anim.start_pos = pos + anim.card_offset * scale,
+                (Some(pos), false) => anim.end_pos = pos,
+                (None, _) => {}
+            }
+            let elapsed = anim.start_time.elapsed().as_secs_f32();
+            let progress = (elapsed / anim.duration).min(1.0);
+
+            let eased_progress = anim.easing.apply(progress);
+
+            // Calculate current position with Y animation
+            let current_pos = egui::Pos2 {
+                x: anim.start_pos.x + (anim.end_pos.x - anim.start_pos.x) * eased_progress,
+                y: anim.start_pos.y + (anim.end_pos.y - anim.start_pos.y) * eased_progress,
+            };
+
+            let current_scale =
+                anim.start_scale + (anim.end_scale - anim.start_scale) * eased_progress;
+
+            if let Some(texture) = self
+                .textures
+                .get(&anim.path)
+                .or_else(|| self.thumbnails.get(&anim.path))
+            {
+                // Calculate size based on the original image aspect ratio
+                let aspect = texture.aspect_ratio();
+                let base_height = panel_size.y * 0.4;
+                let base_size = egui::vec2(base_height * aspect, base_height);
+                let size = base_size * current_scale;
+
+                // Draw shadow and image
+                let shadow_rect =
+                    egui::Rect::from_center_size(current_pos + egui::vec2(2.0, 2.0), size);
+                let shadow = if anim.returning {
+                    progress
+                } else {
+                    1.0 - progress
+                };
+                ui.painter()
+                    .rect_filled(shadow_rect, 3.0, palette.shadow.gamma_multiply(shadow));
+
+                let image_rect = egui::Rect::from_center_size(current_pos, size);
+                ui.painter().image(
+                    texture.id(),
+                    image_rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                );
+            }
+
+            if progress >= 1.0 {
+                completed_animations.push((anim.path.clone(), anim.returning));
+            }
+
+            progress < 1.0
+        });
+
+        // Handle completed animations
+        for (completed_path, returned) in completed_animations {
+            // Back in the center, where it fades in as the current image
+            // instead of crossfading from the one it displaced
+            if returned {
+                retire_shown(&mut self.shown, &mut self.previous_image);
+                continue;
+            }
+            if let Some(idx) = self
+                .pending_moves
+                .iter()
+                .position(|pm| pm.from == completed_path)
+            {
+                let pending_move = self.pending_moves.remove(idx);
+                let bucket = match &pending_move.category {
+                    Some(category) => self.category_buckets.get_mut(category),
+                    None => Some(&mut self.kept_bucket),
+                };
+                if let Some(bucket) = bucket {
+                    bucket.absorb_time = Some(Instant::now());
+                }
+                self.complete_move(pending_move);
+            }
+        }
+    }
+
+    // Apply the state changes for a move whose animation has landed
+    fn complete_move(&mut self, pending_move: PendingMove) {
+        // The card only needs its thumbnail from here on, so free the full
+        // texture. The thumbnail is uploaded when the card is first drawn
+        self.textures.remove(&pending_move.from);
+        self.follow_thumbnail(&pending_move.from, &pending_move.to);
+
+        // Put the card on top of its pile and flash the bucket border
+        let bucket = match &pending_move.category {
+            Some(category) => self.category_buckets.get_mut(category),
+            None => Some(&mut self.kept_bucket),
+        };
+        if let Some(bucket) = bucket {
+            // The file may not have been moved on disk yet
+            let size = [&pending_move.from, &pending_move.to]
+                .into_iter()
+                .find(|path| path.exists())
+                .map(|path| file_size(path));
+            bucket.last_moved = Some((pending_move.to.clone(), Instant::now()));
+            bucket.add_file(pending_move.to, size);
+            bucket.flash_time = Some(Instant::now());
+        }
+    }
+
+    // Returns whether the mode changed
+    fn draw_sort_mode_picker(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        egui::ComboBox::from_label(t!("choosing-a-category"))
+            .selected_text(self.config.sort_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in SortMode::ALL {
+                    changed |= ui
+                        .selectable_value(&mut self.config.sort_mode, mode, mode.label())
+                        .changed();
+                }
+            });
+        changed
+    }
+
+    fn draw_layout_picker(&mut self, ui: &mut egui::Ui) {
+        let mut layout = self.bucket_layout();
+        egui::ComboBox::from_label(t!("bucket-layout"))
+            .selected_text(layout.label())
+            .show_ui(ui, |ui| {
+                for option in BucketLayout::ALL {
+                    ui.selectable_value(&mut layout, option, option.label());
+                }
+            });
+        if layout != self.bucket_layout() {
+            self.set_bucket_layout(layout);
+        }
+    }
+
+    fn draw_file_menu(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(
+                self.folder_rx.is_none(),
+                egui::Button::new(t!("open-folder")),
+            )
+            .clicked()
+        {
+            self.choose_folder();
+            ui.close_menu();
+        }
+        ui.separator();
+        let sorting_by_date = self.date_sort_rx.is_some();
+        if ui
+            .add_enabled(
+                // Tag mode has no folders to sort into
+                self.setup_done
+                    && !sorting_by_date
+                    && !self.images.is_empty()
+                    && self.config.sort_mode.uses_folders(),
+                egui::Button::new(t!("auto-sort-by-date")),
+            )
+            .on_hover_text(t!(
+                "auto-sort-by-date-hint",
+                layout = self.config.date_layout.label()
+            ))
+            .clicked()
+        {
+            self.auto_sort_by_date(ui.ctx());
+            ui.close_menu();
+        }
+        let grouping = self.group_rx.is_some();
+        if ui
+            .add_enabled(
+                self.setup_done && !grouping && self.images.len() > 1,
+                egui::Button::new(if grouping {
+                    t!("grouping-similar")
+                } else {
+                    t!("group-similar")
+                }),
+            )
+            .on_hover_text(t!("group-similar-hint"))
+            .clicked()
+        {
+            self.group_similar(ui.ctx());
+            ui.close_menu();
+        }
+        ui.add_enabled_ui(self.setup_done && !self.images.is_empty(), |ui| {
+            ui.menu_button(t!("move-all-remaining-to"), |ui| {
+                for (idx, category) in self.categories.iter().enumerate() {
+                    if ui.button(category).clicked() {
+                        self.move_all = Some(idx);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        ui.separator();
+        if ui.button(t!("export-report")).clicked() {
+            self.export_report();
+            ui.close_menu();
+        }
+        if ui
+            .add_enabled(self.setup_done, egui::Button::new(t!("export-board")))
+            .clicked()
+        {
+            let size = ui.ctx().screen_rect().size() * ui.ctx().pixels_per_point();
+            self.board_export = Some(size.x.round() as u32);
+            ui.close_menu();
+        }
+        if ui
+            .checkbox(&mut self.config.report_undone, t!("include-undone"))
+            .changed()
+        {
+            self.save_config();
+        }
+        if self.moves.iter().any(|op| op.mode == SortMode::Tag) {
+            ui.separator();
+            if ui.button(t!("export-tags")).clicked() {
+                self.export_tags();
+                ui.close_menu();
+            }
+        }
+        if self.moves.iter().any(|op| op.mode.is_link()) {
+            ui.separator();
+            if ui
+                .add_enabled(
+                    self.link_commit.is_none() && !self.config.dry_run && self.planned.is_empty(),
+                    egui::Button::new(t!("commit-links")),
+                )
+                .on_hover_text(t!("commit-links-hint"))
+                .clicked()
+            {
+                self.commit_links();
+                ui.close_menu();
+            }
         }
     }
 
-    fn revert_last_move(&mut self) {
-        if let Some(last_move) = self.moves.pop() {
-            // Clone paths for both async operation and UI update
-            let from_async = last_move.from.clone();
-            let to_async = last_move.to.clone();
-            let from_ui = last_move.from.clone();
-            let to_ui = last_move.to;
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.last_input = Instant::now();
+    }
 
-            // Spawn file operation in background
-            self.loader.runtime.spawn(async move {
-                if let Err(e) = tokio::fs::rename(&to_async, &from_async).await {
-                    eprintln!("Failed to revert move: {}", e);
-                }
-            });
+    // Pause once there's been no input for the configured number of minutes
+    fn auto_pause(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.events.is_empty()) || self.gamepad_action.is_some() {
+            self.last_input = Instant::now();
+        }
+        let Some(minutes) = self.config.auto_pause_minutes else {
+            return;
+        };
+        if self.paused || !self.setup_done {
+            return;
+        }
 
-            // Update UI state immediately
-            if let Some(current_idx) = self.current_image {
-                self.images.insert(current_idx, from_ui.clone());
-            } else {
-                self.images.push(from_ui.clone());
-                self.current_image = Some(self.images.len() - 1);
-            }
+        let limit = minutes * 60.0;
+        let idle = self.last_input.elapsed().as_secs_f32();
+        if idle >= limit {
+            self.set_paused(true);
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(limit - idle));
+        }
+    }
+
+    // Keep the window title and taskbar progress in step with the queue
+    fn update_window_state(&mut self, ctx: &egui::Context) {
+        let title = if self.is_loading {
+            t!(
+                "title-loading",
+                percent = format!("{:.0}", self.loading_progress * 100.0)
+            )
+        } else if let Some(path) = self.current_image.and_then(|idx| self.images.get(idx)) {
+            t!(
+                "title-sorting",
+                name = self.display_name(path),
+                count = self.images.len()
+            )
+        } else {
+            format!("LeftRight - {}", self.base_dir.display())
+        };
+        if title != self.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
+
+        let total = self.moves.len() + self.images.len();
+        let progress = if self.is_loading {
+            Some(self.loading_progress)
+        } else if self.setup_done && total > 0 {
+            Some(self.moves.len() as f32 / total as f32)
+        } else {
+            None
+        };
+        self.taskbar.set_progress(progress);
 
-            // Keep the texture around since we'll need it again
-            if let Some(texture) = self.textures.remove(&to_ui) {
-                self.textures.insert(from_ui, texture);
+        let (inner, outer, monitor, maximized, fullscreen) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.inner_rect,
+                viewport.outer_rect,
+                viewport.monitor_size,
+                viewport.maximized.unwrap_or(false),
+                viewport.fullscreen.unwrap_or(false),
+            )
+        });
+        let (Some(inner), Some(monitor)) = (inner, monitor) else {
+            return;
+        };
+        let window = self.window.get_or_insert_with(|| {
+            // First frame: bring back a window saved on a monitor that's gone
+            let saved = self.config.window;
+            if let Some((position, size)) =
+                saved.and_then(|saved| saved.misplaced_on(monitor.into()))
+            {
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size.into()));
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(position.into()));
             }
+            saved.unwrap_or(WindowGeometry {
+                size: inner.size().into(),
+                position: None,
+                monitor: None,
+                maximized,
+                fullscreen,
+            })
+        });
+        window.maximized = maximized;
+        window.fullscreen = fullscreen;
+        window.monitor = Some(monitor.into());
+        // Remember the normal size to go back to
+        if !maximized && !fullscreen {
+            window.size = inner.size().into();
+            window.position = outer.map(|outer| outer.min.into());
         }
     }
 
-    fn setup_categories(&mut self, ctx: &egui::Context) {
-        for category in &self.categories {
-            let category_path = self.base_dir.join(category);
-            std::fs::create_dir_all(&category_path).unwrap();
-            self.category_buckets.insert(
-                category.clone(),
-                CategoryBucket {
-                    files: Vec::new(),
-                    rect: egui::Rect::NOTHING,
-                    stack_offset: 3.0,
-                    next_stack_position: 0.0,
-                },
-            );
+    fn toggle_fullscreen(&self, ctx: &egui::Context) {
+        let fullscreen = ctx.input(|i| i.viewport().fullscreen.unwrap_or(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+    }
+
+    // Hold the window open until background file operations finish, so a
+    // move the UI already shows isn't cut off. Returns true while waiting,
+    // during which nothing else is drawn or accepts input
+    fn handle_close(&mut self, ctx: &egui::Context) -> bool {
+        if ctx.input(|i| i.viewport().close_requested()) && !self.quitting {
+            if self.closing_since.is_none() {
+                // Cards still flying already have their file operation running
+                for pending_move in std::mem::take(&mut self.pending_moves) {
+                    self.complete_move(pending_move);
+                }
+                self.animations.clear();
+                // The journal is written as each move starts and ends, so
+                // only the session and config are left to save
+                self.save_session();
+                self.config.last_position = self
+                    .current_image
+                    .and_then(|idx| self.images.get(idx))
+                    .map(|image| LastPosition {
+                        dir: self.base_dir.clone(),
+                        image: image.clone(),
+                    });
+                if self.window.is_some() {
+                    self.config.window = self.window;
+                }
+                self.save_config();
+            }
+            if self.in_flight.count() > 0 || !self.planned.is_empty() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.closing_since.get_or_insert_with(Instant::now);
+            }
         }
 
-        self.refresh_images(ctx);
-    }
+        let Some(since) = self.closing_since else {
+            return false;
+        };
+        let running = self.in_flight.count();
+        if running == 0 && self.planned.is_empty() {
+            self.quitting = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return true;
+        }
 
-    fn refresh_images(&mut self, ctx: &egui::Context) {
-        self.images = std::fs::read_dir(&self.base_dir)
-            .unwrap()
-            .filter_map(Result::ok)
-            .filter(|entry| {
-                let ext = entry
-                    .path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext.to_lowercase());
-                matches!(
-                    ext.as_deref(),
-                    Some("jpg" | "jpeg" | "png" | "gif" | "webp")
-                )
-            })
-            .map(|entry| entry.path())
-            .collect();
+        let mut quit = false;
+        let mut commit = false;
+        let frame = self.central_frame(ctx);
+        egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 2.0 - 40.0);
+                if running > 0 {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("finishing-operations", count = running));
+                    });
+                }
+                if !self.planned.is_empty() {
+                    ui.label(t!("planned-uncommitted", count = self.planned.len()));
+                    commit = ui.button(t!("commit-them")).clicked();
+                }
+                if !self.planned.is_empty() || since.elapsed() >= SHUTDOWN_GRACE {
+                    quit = ui.button(t!("quit-anyway")).clicked();
+                }
+            });
+        });
 
-        println!(
-            "Found {} images in {}",
-            self.images.len(),
-            self.base_dir.display()
-        );
+        if commit {
+            self.commit_planned();
+        }
+        if quit {
+            self.quitting = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        true
+    }
 
-        if !self.images.is_empty() {
-            self.current_image = Some(0);
-            self.is_loading = true;
-            self.loading_progress = 0.0;
-            self.total_images_to_load = self.images.len();
-
-            // Start concurrent loading of ALL images
-            let chunk_size = 4; // Load 4 images at a time
-            for chunk in self.images.chunks(chunk_size) {
-                for path in chunk {
-                    // Skip if already loaded or pending
-                    if self.textures.contains_key(path) || self.pending_loads.contains(path) {
-                        continue;
+    fn draw_planned_menu(&mut self, ui: &mut egui::Ui) {
+        let mut commit = false;
+        ui.menu_button(t!("planned-menu", count = self.planned.len()), |ui| {
+            if self.planned.is_empty() {
+                ui.weak(t!("planned-empty"));
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for op in &self.planned {
+                        ui.label(op.describe());
                     }
+                });
+            ui.separator();
+            if ui.button(t!("commit")).clicked() {
+                commit = true;
+                ui.close_menu();
+            }
+        });
+        if commit {
+            self.commit_planned();
+        }
+    }
 
-                    println!("Starting to load image: {}", path.display());
-                    self.pending_loads.push(path.clone());
+    // Write how loading went to the log: how many, how fast, and where the
+    // time went
+    fn log_load_summary(&self) {
+        let stats = &self.loader.stats;
+        log::info!(
+            "Loaded {} images in {:.1}s ({:.1}/s, {} failed); per image: {}",
+            stats.loaded(),
+            stats.elapsed().as_secs_f32(),
+            stats.images_per_second(),
+            stats.failures(),
+            stats.stage_summary()
+        );
+    }
 
-                    // Start async load
-                    let ctx = ctx.clone();
-                    let tx = self.texture_tx.clone();
-                    let path_clone = path.clone();
+    // Approximate GPU memory held by full-size textures and thumbnails, at four
+    // bytes a pixel
+    fn texture_bytes(&self) -> u64 {
+        self.textures
+            .values()
+            .chain(self.thumbnails.values())
+            .map(|texture| (texture.size()[0] * texture.size()[1] * 4) as u64)
+            .sum()
+    }
 
-                    self.loader.runtime.spawn(async move {
-                        if let Some((loaded_path, texture)) =
-                            ImageLoader::load_image(path_clone, ctx.clone()).await
-                        {
-                            println!("Finished loading image: {}", loaded_path.display());
-                            let _ = tx.send((loaded_path, texture));
-                            ctx.request_repaint();
+    // Where loading time goes, to tell a slow disk from slow decoding or
+    // uploading
+    fn draw_stats(&mut self, ctx: &egui::Context) {
+        let stats = &self.loader.stats;
+        let texture_bytes = self.texture_bytes();
+        let failed = self
+            .images
+            .iter()
+            .filter(|path| self.undecodable.contains(*path))
+            .count();
+        let mut skip_failures = false;
+        let mut open = true;
+        egui::Window::new(t!("load-statistics"))
+            .open(&mut open)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 40.0))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("load_stats").striped(true).show(ui, |ui| {
+                    ui.label(t!("images-per-second"));
+                    ui.monospace(format!("{:.1}", stats.images_per_second()));
+                    ui.end_row();
+                    for stage in Stage::ALL {
+                        ui.label(t!("stage-average", stage = stage.label()));
+                        ui.monospace(format!("{:.1} ms", stats.average_ms(stage)));
+                        ui.end_row();
+                    }
+                    ui.label(t!("waiting-to-decode"));
+                    ui.monospace(self.loader.queue_depth().to_string());
+                    ui.end_row();
+                    ui.label(t!("waiting-to-upload"));
+                    ui.monospace(self.uploads.len().to_string());
+                    ui.end_row();
+                    ui.label(t!("texture-memory"));
+                    ui.monospace(format_bytes(texture_bytes));
+                    ui.end_row();
+                    ui.label(t!("failed-decodes"));
+                    ui.monospace(stats.failures().to_string());
+                    ui.end_row();
+                });
+                if failed > 0 {
+                    ui.add_space(5.0);
+                    ui.label(t!("failed-to-load", count = failed));
+                    skip_failures = ui
+                        .button(t!("skip-failures"))
+                        .on_hover_text(t!("skip-failures-hint"))
+                        .clicked();
+                }
+            });
+        if skip_failures {
+            self.skip_failures();
+        }
+        if !open {
+            self.show_stats = false;
+        }
+        // Keep the numbers moving while images load in the background
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    // Every move, newest first, each with its own revert button
+    fn draw_history(&mut self, ctx: &egui::Context) {
+        const ROW_HEIGHT: f32 = 56.0;
+        let mut reverted = None;
+        egui::SidePanel::right("history_panel")
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(t!("history"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✖").clicked() {
+                            self.show_history = false;
                         }
                     });
+                });
+                ui.separator();
+                if self.moves.is_empty() {
+                    ui.weak(t!("nothing-sorted"));
+                    return;
                 }
-            }
+                // Only the visible rows are laid out, however long the session
+                egui::ScrollArea::vertical().show_rows(
+                    ui,
+                    ROW_HEIGHT,
+                    self.moves.len(),
+                    |ui, rows| {
+                        for row in rows {
+                            let op = &self.moves[self.moves.len() - 1 - row];
+                            let (id, path) = (op.id, op.to.clone());
+                            let mut name = self.display_name(&op.from);
+                            if self.is_favorite(&path) {
+                                name = format!("★ {}", name);
+                            }
+                            let category = op.category.clone().unwrap_or(kept_label());
+                            let time = chrono::DateTime::<chrono::Local>::from(op.time)
+                                .format("%H:%M:%S")
+                                .to_string();
+                            let thumbnail = self.thumbnail_for(&path, ctx);
+                            ui.allocate_ui(egui::vec2(ui.available_width(), ROW_HEIGHT), |ui| {
+                                ui.horizontal(|ui| {
+                                    let size = egui::vec2(48.0, 48.0);
+                                    match &thumbnail {
+                                        Some(texture) => {
+                                            ui.add(
+                                                egui::Image::from_texture(texture).max_size(size),
+                                            );
+                                        }
+                                        None => {
+                                            ui.allocate_space(size);
+                                        }
+                                    }
+                                    ui.vertical(|ui| {
+                                        ui.label(&name).on_hover_text(path.display().to_string());
+                                        ui.weak(format!("{} · {}", category, time));
+                                    });
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            if ui
+                                                .small_button(t!("revert"))
+                                                .on_hover_text(t!("revert-hint"))
+                                                .clicked()
+                                            {
+                                                reverted = Some(id);
+                                            }
+                                        },
+                                    );
+                                });
+                            });
+                        }
+                    },
+                );
+            });
+
+        if let Some(id) = reverted {
+            self.revert_move_by_id(id);
         }
+    }
 
-        // Refresh category buckets
-        for (category, bucket) in self.category_buckets.iter_mut() {
-            bucket.files = std::fs::read_dir(category)
-                .unwrap_or_else(|_| std::fs::read_dir(".").unwrap())
-                .filter_map(Result::ok)
-                .map(|entry| entry.path())
-                .collect();
+    fn draw_status_bar(&mut self, ctx: &egui::Context) {
+        let mut toggle_loading = false;
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("images-left", count = self.images.len()));
+                if self.scan_rx.is_some() {
+                    ui.spinner();
+                    ui.label(t!("scanning"));
+                }
+                if self.loading_paused {
+                    ui.weak(t!("loading-paused"));
+                    toggle_loading = ui.small_button(t!("resume-loading")).clicked();
+                } else if !self.loader.pending().is_empty() || self.scan_rx.is_some() {
+                    toggle_loading = ui
+                        .small_button(t!("pause-loading"))
+                        .on_hover_text(t!("pause-loading-hint"))
+                        .clicked();
+                }
+                for (name, color, count) in self.bucket_counts(ctx) {
+                    ui.separator();
+                    ui.label(egui::RichText::new(name).color(color));
+                    ui.label(count.to_string());
+                }
+            });
+        });
+        if toggle_loading {
+            self.set_loading_paused(!self.loading_paused, ctx);
         }
     }
 
-    fn draw_buckets(&mut self, ui: &mut egui::Ui, center: egui::Pos2, panel_size: egui::Vec2) {
-        let bucket_size = egui::vec2(100.0, 150.0);
-        let spacing = panel_size.x * 0.25;
-        let directions = ["←", "→", "↑", "↓"];
-        let bucket_positions = [
-            center + egui::vec2(-spacing, 0.0),
-            center + egui::vec2(spacing, 0.0),
-            center + egui::vec2(0.0, -spacing),
-            center + egui::vec2(0.0, spacing),
-        ];
+    // Each bucket with its key, color and number of files, kept pile last
+    fn bucket_counts(&self, ctx: &egui::Context) -> Vec<(String, egui::Color32, usize)> {
+        let mut counts: Vec<_> = self
+            .categories
+            .iter()
+            .enumerate()
+            .filter_map(|(i, category)| {
+                let bucket = self.category_buckets.get(category)?;
+                let name = format!("{} {}", self.key_hint(ctx, Action::Category(i)), category);
+                Some((name, bucket.color, bucket.files.len()))
+            })
+            .collect();
+        let name = format!("{} {}", self.key_hint(ctx, Action::Keep), kept_label());
+        counts.push((name, KEPT_COLOR, self.kept_bucket.files.len()));
+        counts
+    }
 
-        for (i, category) in self.categories.iter().enumerate() {
-            if let Some(bucket) = self.category_buckets.get_mut(category) {
-                bucket.rect = egui::Rect::from_center_size(bucket_positions[i], bucket_size);
+    // Stop decoding to save power, keeping what's loaded. A scan already
+    // under way finishes, but a new one waits for the resume
+    fn set_loading_paused(&mut self, paused: bool, ctx: &egui::Context) {
+        self.loading_paused = paused;
+        if paused {
+            self.stop_decoding();
+        } else if std::mem::take(&mut self.scan_deferred) {
+            self.start_background_loading(ctx);
+        } else {
+            self.start_window_loading(ctx);
+        }
+    }
 
-                // Draw bucket background
-                ui.painter()
-                    .rect_filled(bucket.rect, 5.0, egui::Color32::from_gray(40));
-
-                // Draw stacked cards in bucket with proper offset
-                let max_visible_cards = 5;
-                let visible_files: Vec<_> = bucket.files.iter().take(max_visible_cards).collect();
-
-                for (stack_idx, file_path) in visible_files.iter().enumerate().rev() {
-                    if let Some(texture) = self.textures.get(*file_path) {
-                        let offset = stack_idx as f32 * bucket.stack_offset;
-                        let card_rect = egui::Rect::from_center_size(
-                            bucket.rect.center() + egui::vec2(offset, offset),
-                            bucket_size * 0.8,
-                        );
+    fn draw_new_category(&mut self, ctx: &egui::Context) {
+        let Some(mut name) = self.new_category.take() else {
+            return;
+        };
 
-                        // Draw card shadow
-                        ui.painter().rect_filled(
-                            card_rect.translate(egui::vec2(2.0, 2.0)),
-                            3.0,
-                            egui::Color32::from_black_alpha(40),
-                        );
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("new-category"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.text_edit_singleline(&mut name);
+                if ui.memory(|m| m.focus().is_none()) {
+                    response.request_focus();
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    confirmed = true;
+                }
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button(t!("add")).clicked();
+                    cancelled |= ui.button(t!("cancel")).clicked();
+                });
+                cancelled |= ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
 
-                        // Draw card
-                        ui.painter().image(
-                            texture.id(),
-                            card_rect,
-                            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                            egui::Color32::WHITE,
-                        );
-                    }
+        if confirmed {
+            self.add_category(&name);
+        } else if !cancelled {
+            self.new_category = Some(name);
+        }
+    }
+
+    fn draw_edit_categories(&mut self, ctx: &egui::Context) {
+        if !self.show_edit_categories {
+            return;
+        }
+
+        let mut open = true;
+        let mut renamed = None;
+        let mut removed = None;
+        let mut added = false;
+        // These work on the folders directly, which don't match the buckets
+        // while planned changes are outstanding
+        let rehearsing = self.config.dry_run || !self.planned.is_empty();
+        let renaming = self.reorganizing();
+        let next_key = if self.categories.len() < MAX_CATEGORIES {
+            self.key_hint(ctx, Action::Category(self.categories.len()))
+        } else {
+            t!("then-a-letter", key = self.key_hint(ctx, Action::Chord))
+        };
+        egui::Window::new(t!("edit-categories"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if rehearsing {
+                    ui.weak(t!("dry-run-blocks"));
+                }
+                ui.set_enabled(!rehearsing && !renaming);
+                for category in &self.categories {
+                    let files = self
+                        .category_buckets
+                        .get(category)
+                        .map_or(0, |bucket| bucket.files.len());
+                    ui.horizontal(|ui| match &mut self.editing_category {
+                        Some((editing, text)) if editing == category => {
+                            let response = ui.text_edit_singleline(text);
+                            let enter = response.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                            if ui.button(t!("save")).clicked() || enter {
+                                renamed = Some((category.clone(), text.clone()));
+                            }
+                            if ui.button(t!("cancel")).clicked() {
+                                renamed = Some((category.clone(), category.clone()));
+                            }
+                        }
+                        _ => {
+                            ui.label(t!(
+                                "category-files",
+                                category = category.as_str(),
+                                count = files
+                            ));
+                            if ui.button(t!("rename")).clicked() {
+                                self.editing_category = Some((category.clone(), category.clone()));
+                            }
+                            if ui
+                                .add_enabled(files == 0, egui::Button::new(t!("remove")))
+                                .on_disabled_hover_text(t!(
+                                    "remove-not-empty",
+                                    category = category.as_str(),
+                                    count = files
+                                ))
+                                .clicked()
+                            {
+                                removed = Some(category.clone());
+                            }
+                        }
+                    });
+                }
+                if renaming {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("renaming"));
+                    });
                 }
 
-                // Draw bucket label
-                ui.painter().text(
-                    bucket.rect.center() + egui::vec2(0.0, bucket_size.y * 0.4),
-                    egui::Align2::CENTER_CENTER,
-                    format!(
-                        "{} {}\n{} files",
-                        directions[i],
-                        category,
-                        bucket.files.len()
-                    ),
-                    egui::FontId::proportional(16.0),
-                    egui::Color32::WHITE,
-                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let response = ui.text_edit_singleline(&mut self.edit_new_category);
+                    added |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    added |= ui.button(t!("add")).clicked();
+                });
+                ui.weak(t!("new-category-key", key = next_key));
+            });
+
+        if let Some((old, new)) = renamed {
+            self.editing_category = None;
+            self.rename_category(&old, &new);
+        }
+        if let Some(category) = removed {
+            self.remove_category(&category);
+        }
+        if added {
+            let name = std::mem::take(&mut self.edit_new_category);
+            self.add_category(&name);
+        }
+        if !open {
+            self.show_edit_categories = false;
+            self.editing_category = None;
+        }
+    }
+
+    fn draw_scan_error(&mut self, ctx: &egui::Context) {
+        let Some(message) = self.scan_error.clone() else {
+            return;
+        };
+        egui::Window::new(t!("cannot-read-folder"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(message);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.folder_rx.is_none(),
+                            egui::Button::new(t!("open-folder")),
+                        )
+                        .clicked()
+                    {
+                        self.choose_folder();
+                    }
+                    if ui.button(t!("try-again")).clicked() {
+                        self.start_background_loading(ctx);
+                    }
+                });
+            });
+    }
+
+    fn draw_move_all(&mut self, ctx: &egui::Context) {
+        let Some(idx) = self.move_all else {
+            return;
+        };
+        let Some(category) = self.categories.get(idx).cloned() else {
+            self.move_all = None;
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("move-all-remaining"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(t!(
+                    "move-all-confirm",
+                    count = self.images.len(),
+                    category = category.as_str()
+                ));
+                ui.weak(t!("move-all-undo"));
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button(t!("move")).clicked();
+                    cancelled |= ui.button(t!("cancel")).clicked();
+                });
+                confirmed |= ui.input(|i| i.key_pressed(egui::Key::Enter));
+                cancelled |= ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
+
+        if confirmed {
+            self.move_all = None;
+            // From the top, so nothing queued before the current image is left
+            if !self.images.is_empty() {
+                self.current_image = Some(0);
             }
+            self.redo_stack.clear();
+            // Not a size worth repeating with Shift
+            let last_batch = self.last_batch;
+            self.start_batch(idx, self.images.len());
+            self.last_batch = last_batch;
+        } else if cancelled {
+            self.move_all = None;
         }
     }
 
-    fn update_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        // Process completed texture loads
-        while let Ok((path, texture)) = self.texture_rx.try_recv() {
-            self.textures.insert(path.clone(), texture);
-            self.pending_loads.retain(|p| p != &path);
+    // New users may not expect a key press to move a file, so the first move
+    // of a session waits for this to be answered, unless turned off
+    fn hold_for_confirmation(&mut self, direction: usize, center_pos: egui::Pos2) -> bool {
+        let needed = self.config.confirm_first_move
+            && !self.moves_confirmed
+            && self.config.sort_mode == SortMode::Move
+            && !self.config.dry_run;
+        if needed && self.confirm_move.is_none() {
+            self.confirm_move = Some((direction, center_pos));
+        }
+        needed
+    }
 
-            if self.is_loading {
-                self.loading_progress =
-                    (self.textures.len() as f32) / (self.total_images_to_load as f32);
-                if self.textures.len() >= self.total_images_to_load {
-                    self.is_loading = false;
-                }
+    fn draw_confirm_move(&mut self, ctx: &egui::Context) {
+        let Some((idx, center_pos)) = self.confirm_move else {
+            return;
+        };
+        let Some(category) = self.categories.get(idx).cloned() else {
+            self.confirm_move = None;
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("confirm-move-title"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.label(t!(
+                    "confirm-move-explain",
+                    category = category.as_str(),
+                    folder = path_arg(&self.base_dir.join(&category))
+                ));
+                ui.weak(t!(
+                    "confirm-move-undo",
+                    undo = self.key_hint(ctx, Action::Undo)
+                ));
+                ui.checkbox(&mut self.dont_confirm_again, t!("dont-show-again"));
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button(t!("move")).clicked();
+                    cancelled |= ui.button(t!("cancel")).clicked();
+                });
+                confirmed |= ui.input(|i| i.key_pressed(egui::Key::Enter));
+                cancelled |= ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
+
+        if confirmed {
+            self.confirm_move = None;
+            self.moves_confirmed = true;
+            if self.dont_confirm_again {
+                self.config.confirm_first_move = false;
+                self.save_config();
             }
+            // A running batch picks up by itself
+            if !self.selected.is_empty() {
+                self.file_selected(idx, center_pos, ctx);
+            } else if self.batch.is_none() {
+                self.move_image(idx, center_pos, ctx);
+            }
+        } else if cancelled {
+            self.confirm_move = None;
+            self.batch = None;
         }
+    }
 
-        if self.is_loading {
-            ui.vertical_centered(|ui| {
-                ui.add_space(ui.available_height() / 2.0 - 20.0);
-                ui.add(egui::ProgressBar::new(self.loading_progress).show_percentage());
-                ui.label(format!(
-                    "Loading images... ({}/{})",
-                    self.textures.len(),
-                    self.total_images_to_load
+    // Both sides of the oldest held move, with the choices for it
+    fn draw_collision(&mut self, ctx: &egui::Context) {
+        let Some(collision) = self.collisions.front() else {
+            return;
+        };
+        let (from, existing) = (collision.from.clone(), collision.existing.clone());
+        let category = collision.category.clone();
+        let waiting = self.collisions.len() - 1;
+        let thumbnails = [
+            self.thumbnail_for(&from, ctx),
+            self.thumbnail_for(&existing, ctx),
+        ];
+
+        let mut answer = None;
+        egui::Window::new(t!("file-exists"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(t!(
+                    "file-exists-in",
+                    category = category.as_str(),
+                    name = existing.file_name().unwrap_or_default().to_string_lossy()
                 ));
+                ui.horizontal(|ui| {
+                    for ((heading, path), thumbnail) in
+                        [(t!("moving-file"), &from), (t!("already-there"), &existing)]
+                            .into_iter()
+                            .zip(&thumbnails)
+                    {
+                        ui.vertical(|ui| {
+                            ui.strong(heading);
+                            file_card(ui, path, thumbnail.as_ref(), false);
+                            let metadata = std::fs::metadata(path).ok();
+                            if let Some(metadata) = &metadata {
+                                ui.weak(format!(
+                                    "{:.1} MB",
+                                    metadata.len() as f64 / (1024.0 * 1024.0)
+                                ));
+                            }
+                            if let Some(modified) = metadata.and_then(|m| m.modified().ok()) {
+                                let modified = chrono::DateTime::<chrono::Local>::from(modified);
+                                ui.weak(modified.format("%Y-%m-%d %H:%M").to_string());
+                            }
+                        });
+                    }
+                });
+                if waiting > 0 {
+                    ui.weak(t!("more-waiting", count = waiting));
+                }
+                ui.checkbox(&mut self.collision_apply_all, t!("apply-to-all"));
+                ui.horizontal(|ui| {
+                    for choice in [
+                        CollisionPolicy::Overwrite,
+                        CollisionPolicy::Rename,
+                        CollisionPolicy::Skip,
+                    ] {
+                        if ui.button(choice.label()).clicked() {
+                            answer = Some(choice);
+                        }
+                    }
+                });
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    answer = Some(CollisionPolicy::Skip);
+                }
             });
+
+        let Some(answer) = answer else {
             return;
+        };
+        if self.collision_apply_all {
+            self.collision_apply_all = false;
+            self.collision_answer = Some(answer);
+            for collision in std::mem::take(&mut self.collisions) {
+                self.settle_collision(collision, answer, ctx);
+            }
+        } else if let Some(collision) = self.collisions.pop_front() {
+            self.settle_collision(collision, answer, ctx);
         }
+    }
 
-        let panel_size = ui.available_size();
-        let center = ui.available_rect_before_wrap().center();
+    fn draw_chord_picker(&self, ctx: &egui::Context) {
+        let letters = chord_letters(&self.categories);
+        let matches = match &self.input_mode {
+            InputMode::ChordSearch(filter) | InputMode::Palette { filter, .. } => {
+                self.chord_matches(filter)
+            }
+            _ => (0..self.categories.len()).collect(),
+        };
 
-        // Draw buckets first (background layer)
-        self.draw_buckets(ui, center, panel_size);
+        // Not interactable, so it never holds on to keyboard focus
+        egui::Window::new(t!("move-to"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .interactable(false)
+            .show(ctx, |ui| {
+                if let InputMode::ChordSearch(filter) | InputMode::Palette { filter, .. } =
+                    &self.input_mode
+                {
+                    ui.label(egui::RichText::new(format!("/{}", filter)).monospace());
+                    ui.separator();
+                }
+                egui::Grid::new("chord_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 4.0])
+                    .show(ui, |ui| {
+                        for (row, idx) in matches.into_iter().enumerate() {
+                            let category = &self.categories[idx];
+                            let color = self
+                                .category_buckets
+                                .get(category)
+                                .map_or(ui.visuals().text_color(), |bucket| bucket.color);
+                            ui.label(
+                                egui::RichText::new(letters[idx].to_ascii_uppercase().to_string())
+                                    .strong()
+                                    .monospace()
+                                    .color(color),
+                            );
+                            match self.input_mode {
+                                InputMode::Palette { selected, .. } => {
+                                    let _ = ui.selectable_label(row == selected, category);
+                                }
+                                _ => {
+                                    ui.label(category);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(5.0);
+                ui.weak(match self.input_mode {
+                    InputMode::ChordSearch(_) => t!("chord-search-hint"),
+                    InputMode::Palette { .. } => t!("palette-hint"),
+                    _ => t!("chord-hint"),
+                });
+            });
+    }
 
-        // Draw current image (middle layer) only if not animating
-        if self.animations.is_empty() {
-            if let Some(current_idx) = self.current_image {
-                if let Some(path) = self.images.get(current_idx) {
-                    if let Some(texture) = self.textures.get(path) {
-                        let image_size = {
-                            let aspect = texture.aspect_ratio();
-                            let height = panel_size.y * 0.4;
-                            egui::vec2(height * aspect, height)
-                        };
+    fn draw_help(&self, ctx: &egui::Context) {
+        let fill = ctx.style().visuals.window_fill().gamma_multiply(0.85);
 
-                        let image_rect = egui::Rect::from_center_size(center, image_size);
-                        ui.painter().image(
-                            texture.id(),
-                            image_rect,
-                            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                            egui::Color32::WHITE,
-                        );
+        egui::Window::new(t!("keyboard-shortcuts"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(fill))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("help_grid")
+                    .num_columns(2)
+                    .spacing([20.0, 6.0])
+                    .show(ui, |ui| {
+                        let gamepad = self.gamepad.connected();
+                        for action in self.config.key_bindings.actions() {
+                            let hint = match gamepad::glyph(action).filter(|_| gamepad) {
+                                Some(glyph) => {
+                                    format!("{} / 🎮 {}", self.key_hint(ctx, action), glyph)
+                                }
+                                None => self.key_hint(ctx, action),
+                            };
+                            let key = egui::RichText::new(hint).strong();
+                            let color = match action {
+                                Action::Category(idx) => self
+                                    .categories
+                                    .get(idx)
+                                    .and_then(|category| self.category_buckets.get(category))
+                                    .map(|bucket| bucket.color),
+                                _ => None,
+                            };
+                            match (action, color) {
+                                (_, Some(color)) => {
+                                    ui.label(key.color(color));
+                                    ui.label(self.action_label(action));
+                                }
+                                (Action::Category(_), None) => {
+                                    ui.label(key);
+                                    ui.weak(t!(
+                                        "unused-category",
+                                        label = self.action_label(action)
+                                    ));
+                                }
+                                _ => {
+                                    ui.label(key);
+                                    ui.label(self.action_label(action));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(5.0);
+                ui.weak(t!("help-close"));
+            });
+    }
 
-                        self.last_image_pos = Some(image_rect.center());
-                    }
+    fn draw_settings(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_settings;
+        let mut changed = false;
+
+        // Capture the next key press for the action being rebound
+        if let Some(action) = self.rebinding {
+            let pressed = ctx.input(|i| {
+                i.events.iter().find_map(|e| match e {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some(KeyBinding {
+                        key: *key,
+                        modifiers: *modifiers,
+                    }),
+                    _ => None,
+                })
+            });
+            let mouse = ctx.input(|i| {
+                if i.pointer.button_pressed(egui::PointerButton::Extra1) {
+                    Some(MouseInput::Back)
+                } else if i.pointer.button_pressed(egui::PointerButton::Extra2) {
+                    Some(MouseInput::Forward)
+                } else if i.scroll_delta.y > 0.0 {
+                    Some(MouseInput::WheelUp)
+                } else if i.scroll_delta.y < 0.0 {
+                    Some(MouseInput::WheelDown)
+                } else {
+                    None
+                }
+            });
+            if let Some(binding) = pressed {
+                if binding.key != egui::Key::Escape {
+                    self.config.key_bindings.set(action, binding);
+                    changed = true;
                 }
+                self.rebinding = None;
+            } else if let Some(input) = mouse {
+                self.config.key_bindings.set_mouse(action, input);
+                changed = true;
+                self.rebinding = None;
             }
         }
 
-        // Draw animations (top layer)
-        self.update_animations(ui, panel_size);
+        egui::Window::new(t!("settings"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading(t!("settings-sorting"));
+                ui.add_space(5.0);
 
-        // Handle keyboard input
-        let input = ui.input(|i| {
-            (
-                i.key_pressed(egui::Key::ArrowLeft),
-                i.key_pressed(egui::Key::ArrowRight),
-                i.key_pressed(egui::Key::ArrowUp),
-                i.key_pressed(egui::Key::ArrowDown),
-                i.key_pressed(egui::Key::Z) && i.modifiers.ctrl,
-            )
-        });
+                changed |= self.draw_sort_mode_picker(ui);
+                ui.horizontal(|ui| {
+                    let mut slideshow = self.config.slideshow_interval.is_some();
+                    if ui
+                        .checkbox(&mut slideshow, t!("slideshow"))
+                        .on_hover_text(t!("slideshow-hint"))
+                        .changed()
+                    {
+                        self.config.slideshow_interval = slideshow.then_some(3.0);
+                        changed = true;
+                    }
+                    if let Some(interval) = &mut self.config.slideshow_interval {
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(interval)
+                                    .clamp_range(0.5..=60.0)
+                                    .speed(0.1)
+                                    .suffix(" s"),
+                            )
+                            .changed();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let mut auto_pause = self.config.auto_pause_minutes.is_some();
+                    if ui
+                        .checkbox(&mut auto_pause, t!("auto-pause"))
+                        .on_hover_text(t!("auto-pause-hint"))
+                        .changed()
+                    {
+                        self.config.auto_pause_minutes = auto_pause.then_some(5.0);
+                        changed = true;
+                    }
+                    if let Some(minutes) = &mut self.config.auto_pause_minutes {
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(minutes)
+                                    .clamp_range(1.0..=120.0)
+                                    .speed(0.1)
+                                    .suffix(" min"),
+                            )
+                            .changed();
+                    }
+                });
+                if cfg!(feature = "gamepad") {
+                    changed |= ui
+                        .checkbox(&mut self.config.gamepad_stick_sort, t!("stick-sort"))
+                        .on_hover_text(t!("stick-sort-hint"))
+                        .changed();
+                }
+                ui.horizontal(|ui| {
+                    let mut rename = self.config.rename_template.is_some();
+                    if ui
+                        .checkbox(&mut rename, t!("rename-on-move"))
+                        .on_hover_text(t!("rename-tokens", tokens = rename::TOKENS))
+                        .changed()
+                    {
+                        self.config.rename_template =
+                            rename.then(|| "{category}_{index}_{original}".to_string());
+                        changed = true;
+                    }
+                    if let Some(template) = &mut self.config.rename_template {
+                        changed |= ui
+                            .text_edit_singleline(template)
+                            .on_hover_text(t!("rename-tokens", tokens = rename::TOKENS))
+                            .changed();
+                    }
+                });
+                egui::ComboBox::from_label(t!("when-name-taken"))
+                    .selected_text(self.config.collision_policy.label())
+                    .show_ui(ui, |ui| {
+                        for policy in CollisionPolicy::ALL {
+                            if ui
+                                .selectable_value(
+                                    &mut self.config.collision_policy,
+                                    policy,
+                                    policy.label(),
+                                )
+                                .changed()
+                            {
+                                // A new setting replaces the answer given mid-session
+                                self.collision_answer = None;
+                                changed = true;
+                            }
+                        }
+                    });
+                egui::ComboBox::from_label(t!("date-folders"))
+                    .selected_text(self.config.date_layout.label())
+                    .show_ui(ui, |ui| {
+                        for layout in DateLayout::ALL {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.date_layout,
+                                    layout,
+                                    layout.label(),
+                                )
+                                .changed();
+                        }
+                    });
+                changed |= ui
+                    .checkbox(&mut self.config.rate_with_numbers, t!("rate-with-numbers"))
+                    .on_hover_text(t!("rate-with-numbers-hint"))
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.config.dry_run, t!("dry-run"))
+                    .on_hover_text(t!("dry-run-hint"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.config.confirm_first_move,
+                        t!("confirm-first-move"),
+                    )
+                    .changed();
 
-        match input {
-            (true, _, _, _, _) if !self.categories.is_empty() => self.move_image(0, center, ctx),
-            (_, true, _, _, _) if self.categories.len() > 1 => self.move_image(1, center, ctx),
-            (_, _, true, _, _) if self.categories.len() > 2 => self.move_image(2, center, ctx),
-            (_, _, _, true, _) if self.categories.len() > 3 => self.move_image(3, center, ctx),
-            (_, _, _, _, true) => self.revert_last_move(),
-            _ => {}
-        }
+                ui.add_space(10.0);
+                ui.heading(t!("settings-appearance"));
+                ui.add_space(5.0);
 
-        // Request repaint if there are active animations
-        if !self.animations.is_empty() {
-            ctx.request_repaint();
-        }
-    }
+                let language = self.config.language.clone();
+                let languages = i18n::languages();
+                let name = |code: &Option<String>| match code {
+                    Some(code) => languages
+                        .iter()
+                        .find(|(known, _)| known == code)
+                        .map_or(code.clone(), |(_, name)| name.clone()),
+                    None => t!("language-system"),
+                };
+                egui::ComboBox::from_label(t!("language"))
+                    .selected_text(name(&self.config.language))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.config.language, None, name(&None));
+                        for (code, language) in &languages {
+                            ui.selectable_value(
+                                &mut self.config.language,
+                                Some(code.clone()),
+                                language,
+                            );
+                        }
+                    });
+                if self.config.language != language {
+                    i18n::set_language(self.config.language.as_deref());
+                    changed = true;
+                }
 
-    fn move_image(&mut self, direction: usize, center_pos: egui::Pos2, ctx: &egui::Context) {
-        if let Some(current_idx) = self.current_image {
-            if self.images.is_empty() || current_idx >= self.images.len() {
-                return;
-            }
+                let theme_before = (self.config.theme, self.config.background_color);
+                egui::ComboBox::from_label(t!("theme"))
+                    .selected_text(self.config.theme.label())
+                    .show_ui(ui, |ui| {
+                        for theme in Theme::ALL {
+                            ui.selectable_value(&mut self.config.theme, theme, theme.label());
+                        }
+                    });
+                if self.config.theme == Theme::Custom {
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_srgb(&mut self.config.background_color);
+                        ui.label(t!("background-color"));
+                    });
+                }
+                if (self.config.theme, self.config.background_color) != theme_before {
+                    self.apply_theme(ctx);
+                    changed = true;
+                }
 
-            if direction >= self.categories.len() {
-                return;
-            }
+                egui::ComboBox::from_label(t!("image-fit"))
+                    .selected_text(self.config.image_fit.label())
+                    .show_ui(ui, |ui| {
+                        for fit in ImageFit::ALL {
+                            changed |= ui
+                                .selectable_value(&mut self.config.image_fit, fit, fit.label())
+                                .changed();
+                        }
+                    });
 
-            let from = self.images[current_idx].clone();
-            let category = &self.categories[direction].clone();
-            let to = self.base_dir.join(category).join(from.file_name().unwrap());
+                self.draw_layout_picker(ui);
 
-            // Create animation BEFORE moving the file
-            if let Some(bucket) = self.category_buckets.get_mut(category) {
-                let start_pos = self.last_image_pos.unwrap_or(center_pos);
-                let end_pos = bucket.rect.center();
+                changed |= ui
+                    .checkbox(&mut self.config.checkerboard, t!("checkerboard"))
+                    .changed();
+                if ui
+                    .checkbox(&mut self.config.large_text, t!("large-text"))
+                    .on_hover_text(t!("large-text-hint"))
+                    .changed()
+                {
+                    accessibility::apply_text_size(ctx, self.config.large_text);
+                    changed = true;
+                }
 
-                println!(
-                    "Creating animation: start={:?}, end={:?}",
-                    start_pos, end_pos
-                );
+                ui.add_space(10.0);
+                ui.heading(t!("settings-animation"));
+                ui.add_space(5.0);
 
-                // Ensure we have the texture before creating the animation
-                if let Some(_texture) = self.textures.get(&from) {
-                    let animation = Animation {
-                        path: from.clone(),
-                        start_pos,
-                        end_pos,
-                        start_time: Instant::now(),
-                        duration: 0.5,    // Faster animation
-                        start_scale: 1.2, // Start larger
-                        end_scale: 0.6,   // End smaller
-                    };
-                    self.animations.push(animation);
+                changed |= ui
+                    .checkbox(&mut self.config.reduce_motion, t!("reduce-motion"))
+                    .on_hover_text(t!("reduce-motion-hint"))
+                    .changed();
 
-                    // Add to pending moves instead of moving immediately
-                    self.pending_moves.push(PendingMove {
-                        from: from.clone(),
-                        to: to.clone(),
-                    });
+                ui.add_enabled_ui(!self.config.reduce_motion, |ui| {
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(&mut self.config.animation_duration, 0.1..=2.0)
+                                .text(t!("duration"))
+                                .suffix(" s"),
+                        )
+                        .changed();
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(&mut self.config.crossfade_duration, 0.0..=0.5)
+                                .text(t!("crossfade"))
+                                .suffix(" s"),
+                        )
+                        .changed();
+
+                    egui::ComboBox::from_label(t!("easing"))
+                        .selected_text(self.config.easing.label())
+                        .show_ui(ui, |ui| {
+                            for easing in Easing::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.config.easing,
+                                        easing,
+                                        easing.label(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                });
 
-                    println!("Added animation and pending move");
+                if cfg!(feature = "sound") {
+                    ui.add_space(10.0);
+                    ui.heading(t!("settings-sound"));
+                    ui.add_space(5.0);
+
+                    let sound = SoundSettings::from_config(&self.config);
+                    let mut test = None;
+                    ui.checkbox(&mut self.config.sound, t!("sound-enabled"));
+                    ui.add_enabled_ui(self.config.sound, |ui| {
+                        ui.add(
+                            egui::Slider::new(&mut self.config.sound_volume, 0.0..=1.0)
+                                .text(t!("volume")),
+                        );
+                        egui::Grid::new("sound_files_grid")
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for cue in Cue::ALL {
+                                    ui.label(cue.label());
+                                    let mut file = self
+                                        .config
+                                        .sound_files
+                                        .get(&cue)
+                                        .map(|path| path.display().to_string())
+                                        .unwrap_or_default();
+                                    let edit = egui::TextEdit::singleline(&mut file)
+                                        .hint_text(t!("sound-built-in"));
+                                    if ui.add(edit).on_hover_text(t!("sound-file-hint")).changed() {
+                                        if file.trim().is_empty() {
+                                            self.config.sound_files.remove(&cue);
+                                        } else {
+                                            self.config
+                                                .sound_files
+                                                .insert(cue, PathBuf::from(file.trim()));
+                                        }
+                                    }
+                                    if ui
+                                        .small_button("▶")
+                                        .on_hover_text(t!("sound-test"))
+                                        .clicked()
+                                    {
+                                        test = Some(cue);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                    if SoundSettings::from_config(&self.config) != sound {
+                        self.sounds
+                            .configure(SoundSettings::from_config(&self.config));
+                        changed = true;
+                    }
+                    if let Some(cue) = test {
+                        self.sounds.play(cue);
+                    }
                 }
 
-                // Update bucket's next stack position
-                bucket.next_stack_position += bucket.stack_offset;
-                if bucket.next_stack_position > 15.0 {
-                    bucket.next_stack_position = 0.0;
+                ui.add_space(10.0);
+                ui.heading(t!("settings-loading"));
+                ui.add_space(5.0);
+
+                let window = ui
+                    .add(
+                        egui::Slider::new(&mut self.config.preload_ahead, 1..=50)
+                            .text(t!("preload-ahead")),
+                    )
+                    .changed()
+                    | ui.add(
+                        egui::Slider::new(&mut self.config.keep_behind, 0..=20)
+                            .text(t!("keep-behind")),
+                    )
+                    .changed();
+                // The preload window is filled again for the new size
+                if window {
+                    self.window_filled = None;
                 }
-            }
+                changed |= window;
 
-            // Move file in background
-            let from_clone = from.clone();
-            let to_clone = to.clone();
-            self.loader.runtime.spawn(async move {
-                if let Err(e) = tokio::fs::rename(&from_clone, &to_clone).await {
-                    eprintln!("Failed to move file: {}", e);
+                let preview = loader::Preview::from_config(&self.config);
+                ui.add(
+                    egui::Slider::new(&mut self.config.preview_size, 800..=4000)
+                        .text(t!("preview-size"))
+                        .suffix(" px"),
+                )
+                .on_hover_text(t!("preview-size-hint"));
+                egui::ComboBox::from_label(t!("resize-filter"))
+                    .selected_text(self.config.resize_filter.label())
+                    .show_ui(ui, |ui| {
+                        for filter in ResizeFilter::ALL {
+                            ui.selectable_value(
+                                &mut self.config.resize_filter,
+                                filter,
+                                filter.label(),
+                            );
+                        }
+                    });
+                if loader::Preview::from_config(&self.config) != preview {
+                    self.loader
+                        .set_preview(loader::Preview::from_config(&self.config));
+                    changed = true;
+                }
+                ui.weak(t!(
+                    "texture-memory-usage",
+                    size = format_bytes(self.texture_bytes()),
+                    count = self.textures.len()
+                ));
+                if ui
+                    .button(t!("reload-visible"))
+                    .on_hover_text(t!("reload-visible-hint"))
+                    .clicked()
+                {
+                    self.reload_visible(ctx);
                 }
-            });
 
-            // Record the move operation
-            self.moves.push(MoveOperation {
-                from: from.clone(),
-                to,
-                timestamp: Instant::now(),
-            });
+                ui.add_space(10.0);
+                ui.heading(t!("settings-key-bindings"));
+                ui.add_space(5.0);
 
-            // Remove from images list but keep texture until animation completes
-            self.images.remove(current_idx);
-            if !self.images.is_empty() {
-                self.current_image = Some(current_idx.min(self.images.len() - 1));
-            } else {
-                self.current_image = None;
-            }
-        }
-    }
+                egui::Grid::new("key_bindings_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for action in self.config.key_bindings.actions() {
+                            ui.label(self.action_label(action));
+                            let text = if self.rebinding == Some(action) {
+                                t!("press-a-key")
+                            } else {
+                                self.key_hint(ctx, action)
+                            };
+                            if ui.button(text).clicked() {
+                                self.rebinding = Some(action);
+                            }
+                            ui.end_row();
+                        }
+                    });
 
-    fn update_animations(&mut self, ui: &mut egui::Ui, panel_size: egui::Vec2) {
-        let mut completed_animations = Vec::new();
+                ui.horizontal(|ui| {
+                    ui.label(t!("category-keys"));
+                    for (name, keys) in [
+                        (t!("arrows"), KeyBindings::ARROWS),
+                        ("WASD".to_string(), KeyBindings::WASD),
+                        ("HJKL".to_string(), KeyBindings::HJKL),
+                    ] {
+                        if ui.button(name).clicked() {
+                            self.config.key_bindings.set_category_keys(keys);
+                            changed = true;
+                        }
+                    }
+                    if ui.button(t!("reset-all")).clicked() {
+                        self.config.key_bindings = KeyBindings::default();
+                        changed = true;
+                    }
+                });
 
-        self.animations.retain_mut(|anim| {
-            let elapsed = anim.start_time.elapsed().as_secs_f32();
-            let progress = (elapsed / anim.duration).min(1.0);
+                if self.setup_done {
+                    ui.add_space(10.0);
+                    ui.heading(t!("settings-categories"));
+                    ui.add_space(5.0);
 
-            // Smooth easing function
-            let eased_progress = 1.0 - (1.0 - progress).powi(3);
+                    let mut removed = None;
+                    let mut merged = None;
+                    let mut recolored = None;
+                    // These work on the folders directly, which don't match
+                    // the buckets while planned changes are outstanding
+                    let rehearsing = self.config.dry_run || !self.planned.is_empty();
+                    let reorganizing = self.reorganizing();
+                    for category in &self.categories {
+                        ui.horizontal(|ui| {
+                            if let Some(bucket) = self.category_buckets.get(category) {
+                                let mut color = bucket.color;
+                                if ui.color_edit_button_srgba(&mut color).changed() {
+                                    recolored = Some((category.clone(), color));
+                                }
+                                ui.label(t!(
+                                    "category-files",
+                                    category = category.as_str(),
+                                    count = bucket.files.len()
+                                ));
+                            }
+                            ui.set_enabled(!rehearsing && !reorganizing);
+                            egui::ComboBox::from_id_source(("merge_into", category))
+                                .selected_text(t!("merge-into"))
+                                .show_ui(ui, |ui| {
+                                    for target in &self.categories {
+                                        if target != category
+                                            && ui.selectable_label(false, target).clicked()
+                                        {
+                                            merged = Some((category.clone(), target.clone()));
+                                        }
+                                    }
+                                });
+                            if ui
+                                .button(t!("remove"))
+                                .on_hover_text(t!("remove-category-hint"))
+                                .on_disabled_hover_text(t!("dry-run-blocks"))
+                                .clicked()
+                            {
+                                removed = Some(category.clone());
+                            }
+                        });
+                    }
+                    if let Some((category, color)) = recolored {
+                        if let Some(bucket) = self.category_buckets.get_mut(&category) {
+                            bucket.color = color;
+                        }
+                        self.set_category_color(&category, color);
+                        changed = true;
+                    }
+                    if let Some(category) = removed {
+                        self.remove_category(&category);
+                    }
+                    if let Some((source, target)) = merged {
+                        self.merge_categories(&source, &target);
+                    }
+                }
 
-            // Calculate current position with Y animation
-            let current_pos = egui::Pos2 {
-                x: anim.start_pos.x + (anim.end_pos.x - anim.start_pos.x) * eased_progress,
-                y: anim.start_pos.y + (anim.end_pos.y - anim.start_pos.y) * eased_progress,
-            };
+                ui.add_space(10.0);
+                ui.heading(t!("settings-this-folder"));
+                ui.add_space(5.0);
 
-            let current_scale =
-                anim.start_scale + (anim.end_scale - anim.start_scale) * eased_progress;
+                ui.horizontal(|ui| {
+                    ui.label(t!("kept-in-place", count = self.session.kept.len()));
+                    if ui.button(t!("reset-kept")).clicked() {
+                        self.reset_kept();
+                    }
+                });
 
-            if let Some(texture) = self.textures.get(&anim.path) {
-                // Calculate size based on the original image aspect ratio
-                let aspect = texture.aspect_ratio();
-                let base_height = panel_size.y * 0.4;
-                let base_size = egui::vec2(base_height * aspect, base_height);
-                let size = base_size * current_scale;
+                ui.horizontal(|ui| {
+                    ui.label(t!("image-extensions"));
+                    if ui
+                        .text_edit_singleline(&mut self.extensions_text)
+                        .on_hover_text(t!("image-extensions-hint"))
+                        .changed()
+                    {
+                        self.config.image_extensions =
+                            scan::parse_extensions(&self.extensions_text);
+                        changed = true;
+                    }
+                });
+                changed |= ui
+                    .checkbox(&mut self.config.detect_by_content, t!("detect-by-content"))
+                    .on_hover_text(t!("detect-by-content-hint"))
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.max_megapixels, 10..=2000)
+                            .text(t!("max-megapixels"))
+                            .suffix(" MP")
+                            .logarithmic(true),
+                    )
+                    .on_hover_text(t!("max-megapixels-hint"))
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.big_folder_images, 100..=100_000)
+                            .text(t!("big-folder-images"))
+                            .logarithmic(true),
+                    )
+                    .on_hover_text(t!("big-folder-hint"))
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.big_file_mb, 10..=10_000)
+                            .text(t!("big-file-size"))
+                            .suffix(" MB")
+                            .logarithmic(true),
+                    )
+                    .on_hover_text(t!("big-folder-hint"))
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.low_space_margin_mb, 0..=100_000)
+                            .text(t!("low-space-margin"))
+                            .suffix(" MB")
+                            .logarithmic(true),
+                    )
+                    .on_hover_text(t!("low-space-margin-hint"))
+                    .changed();
 
-                // Draw shadow and image
-                let shadow_rect =
-                    egui::Rect::from_center_size(current_pos + egui::vec2(2.0, 2.0), size);
-                ui.painter().rect_filled(
-                    shadow_rect,
-                    3.0,
-                    egui::Color32::from_black_alpha((40.0 * (1.0 - progress)) as u8),
-                );
+                ui.add_space(10.0);
+                ui.heading(t!("settings-cache"));
+                ui.add_space(5.0);
 
-                let image_rect = egui::Rect::from_center_size(current_pos, size);
-                ui.painter().image(
-                    texture.id(),
-                    image_rect,
-                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                    egui::Color32::WHITE,
-                );
-            }
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.config.cache_max_mb, 100..=10_000)
+                            .text(t!("cache-size-limit"))
+                            .suffix(" MB")
+                            .logarithmic(true),
+                    )
+                    .changed()
+                {
+                    changed = true;
+                    if let Some(cache) = &self.loader.cache {
+                        cache.set_max_bytes(self.config.cache_max_mb * 1024 * 1024);
+                    }
+                }
 
-            if progress >= 1.0 {
-                completed_animations.push(anim.path.clone());
-            }
+                match &self.loader.cache {
+                    Some(cache) => {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("cache-usage", size = format_bytes(cache.total_bytes())));
+                            if ui.button(t!("clear-cache")).clicked() {
+                                let cache = cache.clone();
+                                self.loader.runtime.spawn_blocking(move || cache.clear());
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label(t!("no-cache"));
+                    }
+                }
+            });
 
-            progress < 1.0
-        });
+        self.show_settings = open;
+        if !open {
+            self.rebinding = None;
+        }
 
-        // Handle completed animations
-        for completed_path in completed_animations {
-            if let Some(idx) = self
-                .pending_moves
-                .iter()
-                .position(|pm| pm.from == completed_path)
-            {
-                let pending_move = self.pending_moves.remove(idx);
-                // Now move the texture
-                if let Some(texture) = self.textures.remove(&pending_move.from) {
-                    self.textures.insert(pending_move.to, texture);
-                }
-            }
+        if changed {
+            self.save_config();
         }
     }
 }
 
 impl eframe::App for ImageSorter {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if !self.wait_for_pending_moves(EXIT_WAIT) {
+            log::warn!(
+                "Quitting with {} file operations unfinished",
+                self.in_flight.count()
+            );
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.follow_system_theme(ctx, frame);
+        if self.handle_close(ctx) {
+            return;
+        }
+
         // Logo in top right
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(8.0);
             ui.horizontal(|ui| {
+                ui.menu_button(t!("menu-file"), |ui| self.draw_file_menu(ui));
+                if ui.button(t!("settings-button")).clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+                self.notifications.bell_button(ui);
+                if self.setup_done {
+                    let label = if self.paused {
+                        t!("resume-button")
+                    } else {
+                        t!("pause-button")
+                    };
+                    if ui
+                        .button(label)
+                        .on_hover_text(t!("pause-hint", key = self.key_hint(ctx, Action::Pause)))
+                        .clicked()
+                    {
+                        self.set_paused(!self.paused);
+                    }
+
+                    // The sorting keys as buttons, for switch access and
+                    // screen readers; each is announced by what it does
+                    for (icon, action) in [
+                        ("⟲", Action::Undo),
+                        ("⏴", Action::Previous),
+                        ("⏵", Action::Skip),
+                        ("🗑", Action::Delete),
+                    ] {
+                        let response = ui
+                            .add_enabled(!self.paused, egui::Button::new(icon))
+                            .on_hover_text(format!(
+                                "{} ({})",
+                                action.label(),
+                                self.key_hint(ctx, action)
+                            ));
+                        response.widget_info(|| {
+                            egui::WidgetInfo::labeled(egui::WidgetType::Button, action.label())
+                        });
+                        if response.clicked() {
+                            self.control_action = Some(action);
+                        }
+                    }
+                }
+                if self.setup_done && ui.button(t!("overview-button")).clicked() {
+                    self.show_overview = !self.show_overview;
+                }
+                if self.setup_done && ui.button(t!("categories-button")).clicked() {
+                    self.show_edit_categories = !self.show_edit_categories;
+                }
+                if self.setup_done {
+                    if ui
+                        .selectable_label(self.show_filter_bar, t!("filter-button"))
+                        .on_hover_text(self.key_hint(ctx, Action::Filter))
+                        .clicked()
+                    {
+                        self.toggle_filter_bar();
+                    }
+                    if !self.images.filter().is_empty() {
+                        ui.weak(t!(
+                            "filter-matching",
+                            count = self.images.len(),
+                            total = self.images.total()
+                        ));
+                    }
+                }
+                if self.config.dry_run || !self.planned.is_empty() {
+                    self.draw_planned_menu(ui);
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.heading("LeftRight");
                 });
@@ -679,19 +8280,64 @@ impl eframe::App for ImageSorter {
             ui.add_space(8.0);
         });
 
+        if self.setup_done && self.show_filter_bar {
+            self.draw_filter_bar(ctx);
+        }
+
+        // Newly found files wait until sorting resumes
+        if !self.paused {
+            self.poll_scan(ctx);
+        }
+        self.poll_date_sort();
+        self.poll_grouping();
+        self.poll_category_rename();
+        self.poll_category_merge();
+        self.poll_folder(ctx);
+        self.handle_dropped_files(ctx);
+        self.draw_scan_error(ctx);
+        self.draw_big_folder(ctx);
+        self.draw_occupied_folders(ctx);
+        self.poll_ratings(ctx);
+        self.poll_pages(ctx);
+        self.poll_folder_changes(ctx);
+        self.poll_moves_done(ctx);
+        self.poll_link_commit(ctx);
+        self.poll_disk_space(ctx);
+        self.update_window_state(ctx);
+        let fullscreen = self.config.key_bindings.fullscreen.shortcut();
+        if self.rebinding.is_none() && ctx.input_mut(|i| i.consume_shortcut(&fullscreen)) {
+            self.toggle_fullscreen(ctx);
+        }
+        self.gamepad_action = self.gamepad.poll(ctx, self.config.gamepad_stick_sort);
+        self.auto_pause(ctx);
+
+        if self.setup_done {
+            self.draw_link_commit(ctx);
+            self.draw_low_space(ctx);
+            self.draw_status_bar(ctx);
+            if self.show_history {
+                self.draw_history(ctx);
+            }
+            if self.show_stats {
+                self.draw_stats(ctx);
+            }
+        }
+
         // Main content
         if !self.setup_done {
             // Start loading images in background while setting up categories
-            if !self.is_loading {
+            if !self.scan_started {
+                self.scan_started = true;
                 self.start_background_loading(ctx);
             }
 
-            egui::CentralPanel::default().show(ctx, |ui| {
+            let frame = self.central_frame(ctx);
+            egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
                 // Category setup window in center
-                let window_size = egui::vec2(400.0, 200.0);
+                let window_size = egui::vec2(400.0, 320.0);
                 let window_pos = ui.available_rect_before_wrap().center() - (window_size / 2.0);
 
-                egui::Window::new("Setup Categories")
+                egui::Window::new(t!("setup-title"))
                     .fixed_pos(window_pos)
                     .fixed_size(window_size)
                     .collapsible(false)
@@ -699,49 +8345,153 @@ impl eframe::App for ImageSorter {
                     .show(ctx, |ui| {
                         ui.vertical_centered(|ui| {
                             ui.add_space(20.0);
-                            ui.heading("Enter Categories");
+                            ui.heading(t!("setup-heading"));
                             ui.add_space(10.0);
-                            ui.label("Separate with commas (1-4 categories)");
+                            ui.label(t!("setup-hint", max = MAX_CHORD_CATEGORIES));
                             ui.add_space(10.0);
                             let response = ui.text_edit_singleline(&mut self.input_categories);
+                            if response.changed() {
+                                self.setup_error = None;
+                            }
+
+                            // Counted as they're typed, so going over the limit
+                            // shows straight away rather than dropping names
+                            let names: Vec<String> = self
+                                .input_categories
+                                .split(',')
+                                .map(|s| s.trim().to_string())
+                                .filter(|s| !s.is_empty())
+                                .collect();
+                            let error_color = ui.visuals().error_fg_color;
+                            if let Some(error) = &self.setup_error {
+                                ui.colored_label(error_color, error);
+                            } else if names.len() > MAX_CHORD_CATEGORIES {
+                                ui.colored_label(
+                                    error_color,
+                                    t!(
+                                        "too-many-categories",
+                                        count = names.len(),
+                                        max = MAX_CHORD_CATEGORIES
+                                    ),
+                                );
+                            } else if !names.is_empty() {
+                                ui.weak(t!(
+                                    "setup-count",
+                                    count = names.len(),
+                                    max = MAX_CHORD_CATEGORIES
+                                ));
+                            }
+
+                            // Color pickers for the categories typed so far
+                            let names: Vec<String> =
+                                names.into_iter().take(MAX_CHORD_CATEGORIES).collect();
+                            let colors = self.category_colors(&names);
+                            ui.add_space(10.0);
+                            egui::ScrollArea::vertical()
+                                .max_height(120.0)
+                                .show(ui, |ui| {
+                                    for (i, (name, mut color)) in
+                                        names.iter().zip(colors).enumerate()
+                                    {
+                                        let key = self.category_hint(ctx, i, &names);
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(120.0);
+                                            if ui.color_edit_button_srgba(&mut color).changed() {
+                                                self.set_category_color(name, color);
+                                                self.save_config();
+                                            }
+                                            ui.label(
+                                                egui::RichText::new(key).strong().color(color),
+                                            );
+                                            ui.label(name);
+                                        });
+                                    }
+                                });
+
+                            ui.add_space(10.0);
+                            self.draw_layout_picker(ui);
+                            if self.draw_sort_mode_picker(ui) {
+                                self.save_config();
+                            }
 
-                            if response.lost_focus()
-                                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                            ui.add_space(10.0);
+                            let valid = self.setup_error.is_none()
+                                && self.occupied.is_none()
+                                && category_name::parse(&self.input_categories)
+                                    .is_ok_and(|names| names.len() <= MAX_CHORD_CATEGORIES);
+                            let start = ui.add_enabled(valid, egui::Button::new(t!("start")));
+                            if start.clicked()
+                                || response.lost_focus()
+                                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
                             {
-                                self.categories = self
-                                    .input_categories
-                                    .split(',')
-                                    .map(|s| s.trim().to_string())
-                                    .take(4)
-                                    .collect();
-                                if !self.categories.is_empty() {
-                                    self.setup_categories(ctx);
-                                    self.setup_done = true;
+                                match category_name::parse(&self.input_categories) {
+                                    Ok(names) if names.len() > MAX_CHORD_CATEGORIES => {
+                                        self.setup_error = Some(t!(
+                                            "too-many-categories",
+                                            count = names.len(),
+                                            max = MAX_CHORD_CATEGORIES
+                                        ));
+                                    }
+                                    Ok(names) => {
+                                        self.categories = names;
+                                        let folders = self.occupied_folders();
+                                        if folders.is_empty() {
+                                            self.setup_done = self.setup_categories(ctx);
+                                        } else {
+                                            self.occupied = Some(OccupiedFolders {
+                                                folders,
+                                                emptying: None,
+                                            });
+                                        }
+                                    }
+                                    Err(error) => self.setup_error = Some(error),
                                 }
                             }
                         });
                     });
 
                 // Shortcuts help box on the right
-                egui::Window::new("Shortcuts")
-                    .fixed_pos([ui.available_rect_before_wrap().right() - 200.0, 50.0])
-                    .fixed_size([180.0, 200.0])
+                // Sized by its contents, so translated labels fit; the longest
+                // wrap rather than push it over the setup window
+                egui::Window::new(t!("shortcuts"))
+                    .id(egui::Id::new("setup_shortcuts"))
+                    .anchor(egui::Align2::RIGHT_TOP, [-20.0, 50.0])
+                    .max_width(240.0)
                     .collapsible(false)
                     .resizable(false)
                     .show(ctx, |ui| {
-                        ui.vertical(|ui| {
-                            ui.label("← Left category");
-                            ui.label("→ Right category");
-                            ui.label("↑ Up category");
-                            ui.label("↓ Down category");
-                            ui.add_space(5.0);
-                            ui.label("Ctrl+Z Undo last move");
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            for action in self.config.key_bindings.actions() {
+                                if action == Action::Undo {
+                                    ui.add_space(5.0);
+                                }
+                                ui.label(format!(
+                                    "{} {}",
+                                    self.key_hint(ctx, action),
+                                    self.action_label(action)
+                                ));
+                            }
                         });
                     });
 
-                // Loading progress in bottom right
-                if self.is_loading {
-                    let progress_width = 200.0;
+                // Scan spinner / loading progress in bottom right
+                if self.scan_rx.is_some() {
+                    let spinner_pos =
+                        ui.available_rect_before_wrap().right_bottom() - egui::vec2(280.0, 60.0);
+
+                    egui::Window::new("Scanning")
+                        .fixed_pos(spinner_pos)
+                        .fixed_size([260.0, 40.0])
+                        .title_bar(false)
+                        .frame(egui::Frame::none())
+                        .show(ctx, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label(t!("found-images", count = self.images.len()));
+                            });
+                        });
+                } else if self.is_loading {
+                    let progress_width = 260.0;
                     let progress_height = 40.0;
                     let progress_pos = ui.available_rect_before_wrap().right_bottom()
                         - egui::vec2(progress_width + 20.0, progress_height + 20.0);
@@ -757,20 +8507,28 @@ impl eframe::App for ImageSorter {
                                     .show_percentage()
                                     .animate(true),
                             );
-                            ui.label(format!(
-                                "Loading images... ({}/{})",
-                                self.textures.len(),
-                                self.total_images_to_load
+                            ui.label(t!(
+                                "loading-images",
+                                loaded = self.textures.len(),
+                                total = self.total_images_to_load
                             ));
                         });
                 }
             });
         } else {
-            egui::CentralPanel::default().show(ctx, |ui| {
+            let frame = self.central_frame(ctx);
+            egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
                 self.update_ui(ui, ctx);
             });
         }
 
+        if self.show_settings {
+            self.draw_settings(ctx);
+        }
+
+        self.notifications.show(ctx);
+        self.draw_drop_target(ctx);
+
         if !self.animations.is_empty() {
             ctx.request_repaint();
         }
@@ -778,39 +8536,106 @@ impl eframe::App for ImageSorter {
 }
 
 fn main() -> eframe::Result<()> {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("warn,leftright=info"),
+    )
+    .init();
+    i18n::set_language(Config::load().language.as_deref());
     let args = Args::parse();
+    let invalid = |message: String| -> ! {
+        Args::command()
+            .error(clap::error::ErrorKind::ValueValidation, message)
+            .exit()
+    };
+
+    let categories: Vec<String> = args
+        .categories
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if let Some(error) = categories
+        .iter()
+        .find_map(|name| category_name::check(name).err())
+    {
+        invalid(error);
+    }
+    if categories.len() > MAX_CHORD_CATEGORIES {
+        invalid(t!(
+            "too-many-categories",
+            count = categories.len(),
+            max = MAX_CHORD_CATEGORIES
+        ));
+    }
+
+    // Listed paths are relative to where we were started, not the destination
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let list = if let Some(file) = &args.from_list {
+        match std::fs::read_to_string(file) {
+            Ok(text) => Some(scan::parse_list(&text, &cwd)),
+            Err(e) => invalid(format!("could not read '{}': {}", file.display(), e)),
+        }
+    } else if args.from_stdin {
+        let mut text = String::new();
+        match std::io::Read::read_to_string(&mut std::io::stdin(), &mut text) {
+            Ok(_) => Some(scan::parse_list(&text, &cwd)),
+            Err(e) => invalid(format!("could not read stdin: {}", e)),
+        }
+    } else {
+        None
+    };
+    if let (Some(_), Some(dest)) = (&list, &args.dest) {
+        if let Err(e) = std::fs::create_dir_all(dest) {
+            invalid(format!("could not create '{}': {}", dest.display(), e));
+        }
+    }
 
     // Get the directory to sort
-    let dir = args.dir.unwrap_or_else(|| {
+    let dir = args.dest.filter(|_| list.is_some()).or(args.dir);
+    let dir = dir.unwrap_or_else(|| {
         let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        println!(
+        log::info!(
             "No directory specified, using current directory: {}",
             current_dir.display()
         );
         current_dir
     });
 
-    // Ensure directory exists
-    if !dir.exists() {
-        eprintln!("Error: Directory '{}' does not exist", dir.display());
-        std::process::exit(1);
-    }
+    // Ensure directory exists, and make it absolute so paths stay valid after
+    // changing into it below
+    let dir = match dir.canonicalize() {
+        Ok(dir) => dir,
+        Err(_) => invalid(format!("directory '{}' does not exist", dir.display())),
+    };
 
     // Change to the directory
     if let Err(e) = std::env::set_current_dir(&dir) {
-        eprintln!(
-            "Error: Could not change to directory '{}': {}",
+        invalid(format!(
+            "could not change to directory '{}': {}",
             dir.display(),
             e
-        );
-        std::process::exit(1);
+        ));
     }
 
+    // The window comes back as it was left; eframe shrinks it to the largest
+    // monitor, and the first frame moves it if its monitor is gone
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([800.0, 600.0])
+        .with_min_inner_size([400.0, 300.0]);
+    if let Some(window) = Config::load().window {
+        viewport = viewport
+            .with_inner_size(window.size)
+            .with_maximized(window.maximized)
+            .with_fullscreen(window.fullscreen);
+        if let Some(position) = window.position {
+            viewport = viewport.with_position(position);
+        }
+    }
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([800.0, 600.0])
-            .with_min_inner_size([400.0, 300.0]),
+        viewport,
         vsync: true,
+        // Done by the app, so a theme picked in Settings isn't overridden
+        follow_system_theme: false,
         multisampling: 4,
         ..Default::default()
     };
@@ -818,6 +8643,17 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         &format!("LeftRight - {}", dir.display()),
         options,
-        Box::new(|_cc| Box::new(ImageSorter::new(dir))),
+        Box::new(|cc| {
+            let mut app = ImageSorter::new(dir, &cc.egui_ctx);
+            app.source_list = list;
+            app.taskbar = Taskbar::new(cc.raw_window_handle(), &app.loader.runtime);
+            app.system_theme = cc.integration_info.system_theme;
+            app.apply_theme(&cc.egui_ctx);
+            accessibility::apply_text_size(&cc.egui_ctx, app.config.large_text);
+            if !categories.is_empty() {
+                app.start_with_categories(categories, &cc.egui_ctx);
+            }
+            Box::new(app)
+        }),
     )
 }
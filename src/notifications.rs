@@ -0,0 +1,247 @@
+use crate::i18n::t;
+use eframe::egui;
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+const HISTORY_LEN: usize = 200;
+const MAX_TOASTS: usize = 5;
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn icon(self) -> &'static str {
+        match self {
+            Level::Info => "ℹ",
+            Level::Warning => "⚠",
+            Level::Error => "❌",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Level::Info => visuals.text_color(),
+            Level::Warning => visuals.warn_fg_color,
+            Level::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+    pub timestamp: Instant,
+    dismissed: bool,
+}
+
+// Cloneable handle for reporting from background tasks; messages are also
+// logged so they reach the terminal when one is attached
+#[derive(Clone)]
+pub struct Notifier {
+    tx: Sender<(Level, String)>,
+    ctx: egui::Context,
+}
+
+impl Notifier {
+    pub fn info(&self, message: impl Into<String>) {
+        self.send(Level::Info, message.into());
+    }
+
+    pub fn warn(&self, message: impl Into<String>) {
+        self.send(Level::Warning, message.into());
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.send(Level::Error, message.into());
+    }
+
+    fn send(&self, level: Level, message: String) {
+        match level {
+            Level::Info => log::info!("{}", message),
+            Level::Warning => log::warn!("{}", message),
+            Level::Error => log::error!("{}", message),
+        }
+        let _ = self.tx.send((level, message));
+        self.ctx.request_repaint();
+    }
+}
+
+// Recent messages, shown as toasts in the bottom right and kept in a
+// scrollable history behind the bell in the top panel
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    rx: Receiver<(Level, String)>,
+    notifier: Notifier,
+    show_history: bool,
+}
+
+impl Notifications {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let (tx, rx) = channel();
+        Self {
+            history: VecDeque::new(),
+            rx,
+            notifier: Notifier {
+                tx,
+                ctx: ctx.clone(),
+            },
+            show_history: false,
+        }
+    }
+
+    pub fn notifier(&self) -> Notifier {
+        self.notifier.clone()
+    }
+
+    pub fn info(&self, message: impl Into<String>) {
+        self.notifier.info(message);
+    }
+
+    pub fn warn(&self, message: impl Into<String>) {
+        self.notifier.warn(message);
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.notifier.error(message);
+    }
+
+    fn poll(&mut self) {
+        while let Ok((level, message)) = self.rx.try_recv() {
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back(Notification {
+                level,
+                message,
+                timestamp: Instant::now(),
+                dismissed: false,
+            });
+        }
+    }
+
+    // Errors stay up until clicked; everything else fades out on its own
+    fn is_visible(notification: &Notification) -> bool {
+        !notification.dismissed
+            && (notification.level == Level::Error
+                || notification.timestamp.elapsed() < TOAST_DURATION)
+    }
+
+    pub fn bell_button(&mut self, ui: &mut egui::Ui) {
+        let errors = self
+            .history
+            .iter()
+            .filter(|n| n.level == Level::Error && !n.dismissed)
+            .count();
+        let label = if errors > 0 {
+            egui::RichText::new(format!("🔔 {}", errors)).color(ui.visuals().error_fg_color)
+        } else {
+            egui::RichText::new("🔔")
+        };
+        if ui
+            .button(label)
+            .on_hover_text(t!("notification-history"))
+            .clicked()
+        {
+            self.show_history = !self.show_history;
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.poll();
+
+        let mut dismissed = Vec::new();
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -40.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let visible: Vec<usize> = (0..self.history.len())
+                    .rev()
+                    .filter(|idx| Self::is_visible(&self.history[*idx]))
+                    .take(MAX_TOASTS)
+                    .collect();
+                for idx in visible.into_iter().rev() {
+                    let notification = &self.history[idx];
+                    let color = notification.level.color(ui.visuals());
+                    let response = egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(1.0, color))
+                        .show(ui, |ui| {
+                            ui.set_max_width(320.0);
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(
+                                    egui::RichText::new(notification.level.icon()).color(color),
+                                );
+                                ui.label(&notification.message);
+                            });
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.on_hover_text(t!("click-to-dismiss")).clicked() {
+                        dismissed.push(idx);
+                    }
+                }
+            });
+        for idx in dismissed {
+            self.history[idx].dismissed = true;
+        }
+
+        // Wake up again when the next toast is due to fade out
+        if let Some(remaining) = self
+            .history
+            .iter()
+            .filter(|n| n.level != Level::Error && Self::is_visible(n))
+            .map(|n| TOAST_DURATION.saturating_sub(n.timestamp.elapsed()))
+            .min()
+        {
+            ctx.request_repaint_after(remaining);
+        }
+
+        if self.show_history {
+            self.draw_history(ctx);
+        }
+    }
+
+    fn draw_history(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_history;
+        egui::Window::new(t!("notifications"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_size([400.0, 300.0])
+            .show(ctx, |ui| {
+                if self.history.is_empty() {
+                    ui.weak(t!("nothing-to-report"));
+                    return;
+                }
+                if ui.button(t!("clear")).clicked() {
+                    self.history.clear();
+                    return;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for notification in &mut self.history {
+                            let color = notification.level.color(ui.visuals());
+                            ui.horizontal_wrapped(|ui| {
+                                ui.weak(t!(
+                                    "seconds-ago",
+                                    seconds = notification.timestamp.elapsed().as_secs()
+                                ));
+                                ui.label(
+                                    egui::RichText::new(notification.level.icon()).color(color),
+                                );
+                                ui.label(&notification.message);
+                            });
+                            // Reading an error here counts as acknowledging it
+                            notification.dismissed = true;
+                        }
+                    });
+            });
+        self.show_history = open;
+    }
+}
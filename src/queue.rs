@@ -0,0 +1,222 @@
+use crate::scan::{FileInfo, QueueFilter};
+use std::collections::HashMap;
+use std::ops::Index;
+use std::path::{Path, PathBuf};
+
+// The files waiting to be sorted. Everything queued stays in `all`; the
+// filter bar only changes `view`, the positions in `all` it lets through, so
+// clearing the filter brings hidden files back where they were. Indices
+// passed in and returned are positions in the view
+#[derive(Default)]
+pub struct Queue {
+    all: Vec<PathBuf>,
+    view: Vec<usize>,
+    filter: QueueFilter,
+    info: HashMap<PathBuf, FileInfo>,
+}
+
+impl Queue {
+    pub fn len(&self) -> usize {
+        self.view.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.view.is_empty()
+    }
+
+    // Files the filter is hiding
+    pub fn hidden(&self) -> usize {
+        self.all.len() - self.view.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.all.len()
+    }
+
+    // Every queued file, shown or not
+    pub fn all(&self) -> &[PathBuf] {
+        &self.all
+    }
+
+    pub fn filter(&self) -> &QueueFilter {
+        &self.filter
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&PathBuf> {
+        self.view.get(idx).map(|&pos| &self.all[pos])
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &PathBuf> + ExactSizeIterator + Clone + '_ {
+        self.view.iter().map(|&pos| &self.all[pos])
+    }
+
+    pub fn to_vec(&self) -> Vec<PathBuf> {
+        self.iter().cloned().collect()
+    }
+
+    pub fn position(&self, path: &Path) -> Option<usize> {
+        self.iter().position(|queued| queued == path)
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.iter().any(|queued| queued == path)
+    }
+
+    // Whether `path` is queued at all, even if the filter hides it
+    pub fn is_queued(&self, path: &Path) -> bool {
+        self.all.iter().any(|queued| queued == path)
+    }
+
+    pub fn clear(&mut self) {
+        self.all.clear();
+        self.view.clear();
+        self.info.clear();
+    }
+
+    // Queue a file the scan found, shown if it passes the filter. Returns
+    // whether it's shown
+    pub fn push_found(&mut self, path: PathBuf, info: FileInfo) -> bool {
+        let shown = self.filter.is_empty() || self.filter.matches(&path, Some(&info));
+        self.info.insert(path.clone(), info);
+        self.all.push(path);
+        if shown {
+            self.view.push(self.all.len() - 1);
+        }
+        shown
+    }
+
+    // Queue a file at the end, shown whatever the filter says, as when an
+    // undo puts it back
+    pub fn push(&mut self, path: PathBuf) {
+        self.all.push(path);
+        self.view.push(self.all.len() - 1);
+    }
+
+    // Queue a file shown at `idx`, before whatever was shown there
+    pub fn insert(&mut self, idx: usize, path: PathBuf) {
+        let pos = self.view.get(idx).copied().unwrap_or(self.all.len());
+        self.all.insert(pos, path);
+        for later in &mut self.view[idx..] {
+            *later += 1;
+        }
+        self.view.insert(idx, pos);
+    }
+
+    pub fn remove(&mut self, idx: usize) -> PathBuf {
+        let pos = self.view.remove(idx);
+        for later in &mut self.view[idx..] {
+            *later -= 1;
+        }
+        let path = self.all.remove(pos);
+        self.info.remove(&path);
+        path
+    }
+
+    // Drop every queued file, shown or hidden, that `keep` says no to
+    pub fn retain(&mut self, mut keep: impl FnMut(&PathBuf) -> bool) {
+        let mut shown = vec![false; self.all.len()];
+        for &pos in &self.view {
+            shown[pos] = true;
+        }
+        let mut all = Vec::with_capacity(self.all.len());
+        self.view.clear();
+        for (path, shown) in std::mem::take(&mut self.all).into_iter().zip(shown) {
+            if !keep(&path) {
+                self.info.remove(&path);
+                continue;
+            }
+            if shown {
+                self.view.push(all.len());
+            }
+            all.push(path);
+        }
+        self.all = all;
+    }
+
+    // Put the shown files in the order of `order`; shown files it leaves out
+    // follow in their old order. Hidden files keep their places
+    pub fn reorder(&mut self, order: &[PathBuf]) {
+        let rank: HashMap<&PathBuf, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(rank, path)| (path, rank))
+            .collect();
+        let mut shown: Vec<PathBuf> = self.iter().cloned().collect();
+        shown.sort_by_key(|path| rank.get(path).copied().unwrap_or(usize::MAX));
+        for (&pos, path) in self.view.iter().zip(shown) {
+            self.all[pos] = path;
+        }
+    }
+
+    // Show only the queued files `filter` lets through
+    pub fn set_filter(&mut self, filter: QueueFilter) {
+        self.filter = filter;
+        let filter = &self.filter;
+        self.view = self
+            .all
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| filter.is_empty() || filter.matches(path, self.info.get(*path)))
+            .map(|(pos, _)| pos)
+            .collect();
+    }
+}
+
+impl Index<usize> for Queue {
+    type Output = PathBuf;
+
+    fn index(&self, idx: usize) -> &PathBuf {
+        &self.all[self.view[idx]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Queue;
+    use crate::scan::{FileInfo, NameFilter, QueueFilter};
+    use std::path::PathBuf;
+
+    fn names(queue: &Queue) -> Vec<&str> {
+        queue.iter().map(|path| path.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn filter_hides_files_without_losing_their_place() {
+        let mut queue = Queue::default();
+        for (name, size) in [("a.jpg", 10), ("b.png", 2_000_000), ("c.jpg", 3_000_000)] {
+            let info = FileInfo {
+                size,
+                modified: None,
+            };
+            queue.push_found(PathBuf::from(name), info);
+        }
+
+        queue.set_filter(QueueFilter {
+            min_bytes: Some(1_000_000),
+            ..QueueFilter::default()
+        });
+        assert_eq!(names(&queue), ["b.png", "c.jpg"]);
+        assert_eq!(queue.hidden(), 1);
+
+        // Edits through the view leave the hidden file alone
+        queue.insert(1, PathBuf::from("d.jpg"));
+        assert_eq!(queue.remove(0), PathBuf::from("b.png"));
+        assert_eq!(names(&queue), ["d.jpg", "c.jpg"]);
+        queue.reorder(&[PathBuf::from("c.jpg")]);
+        assert_eq!(names(&queue), ["c.jpg", "d.jpg"]);
+
+        queue.set_filter(QueueFilter::default());
+        assert_eq!(names(&queue), ["a.jpg", "c.jpg", "d.jpg"]);
+
+        queue.set_filter(QueueFilter {
+            name: NameFilter::new("*.jpg"),
+            ..QueueFilter::default()
+        });
+        queue.retain(|path| path.to_str() != Some("a.jpg"));
+        assert_eq!(names(&queue), ["c.jpg", "d.jpg"]);
+        assert_eq!(queue[1], PathBuf::from("d.jpg"));
+        assert_eq!(queue.total(), 2);
+    }
+}
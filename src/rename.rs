@@ -0,0 +1,42 @@
+use chrono::NaiveDate;
+use std::path::Path;
+
+pub const TOKENS: &str = "{original}, {category}, {index}, {date}";
+
+// Values available to a rename template for one file
+pub struct RenameContext<'a> {
+    pub category: &'a str,
+    // Position of the file in its category, counting from 1
+    pub index: usize,
+    pub date: Option<NaiveDate>,
+}
+
+pub fn needs_date(template: &str) -> bool {
+    template.contains("{date}")
+}
+
+// File name for `original` under `template`; the extension is always kept
+pub fn expand(template: &str, original: &Path, context: &RenameContext) -> String {
+    let stem = original.file_stem().unwrap_or_default().to_string_lossy();
+    let date = context
+        .date
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let name = template
+        .replace("{original}", &stem)
+        .replace("{category}", context.category)
+        .replace("{index}", &format!("{:04}", context.index))
+        .replace("{date}", &date);
+    // Tokens can't be allowed to reach into other folders
+    let name = name.replace(['/', '\\'], "_");
+    let name = if name.trim().is_empty() {
+        stem.to_string()
+    } else {
+        name
+    };
+
+    match original.extension() {
+        Some(ext) => format!("{}.{}", name, ext.to_string_lossy()),
+        None => name,
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// One sorted image in the session report
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    // None when the image was kept in place
+    pub category: Option<String>,
+    // Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub undone: bool,
+}
+
+pub fn to_json(entries: &[Entry]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(entries)
+}
+
+pub fn to_csv(entries: &[Entry]) -> String {
+    let mut csv = String::from("from,to,category,timestamp,undone\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&entry.from.to_string_lossy()),
+            csv_field(&entry.to.to_string_lossy()),
+            csv_field(entry.category.as_deref().unwrap_or_default()),
+            entry.timestamp,
+            entry.undone
+        ));
+    }
+    csv
+}
+
+pub fn csv_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+// JSON for a `.json` path, CSV for anything else
+pub fn write(path: &Path, entries: &[Entry]) -> io::Result<()> {
+    let json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let contents = if json {
+        to_json(entries)?
+    } else {
+        to_csv(entries)
+    };
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let entries = vec![
+            Entry {
+                from: PathBuf::from("/photos/a \"quoted\".jpg"),
+                to: PathBuf::from("/photos/cats/a \"quoted\".jpg"),
+                category: Some("cats".to_string()),
+                timestamp: 1_700_000_000,
+                undone: false,
+            },
+            Entry {
+                from: PathBuf::from("/photos/b.png"),
+                to: PathBuf::from("/photos/b.png"),
+                category: None,
+                timestamp: 1_700_000_042,
+                undone: true,
+            },
+        ];
+
+        let json = to_json(&entries).unwrap();
+        let parsed: Vec<Entry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, entries);
+    }
+}
@@ -0,0 +1,548 @@
+use crate::i18n::t;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio_util::sync::CancellationToken;
+
+pub const DEFAULT_EXTENSIONS: [&str; 10] = [
+    "jpg", "jpeg", "jpe", "jfif", "png", "gif", "webp", "bmp", "tif", "tiff",
+];
+
+// The default list plus the formats optional features add
+pub fn default_extensions() -> Vec<String> {
+    let extensions = DEFAULT_EXTENSIONS.iter();
+    #[cfg(feature = "heif")]
+    let extensions = extensions.chain(&["heic", "heif"]);
+    #[cfg(feature = "video")]
+    let extensions = extensions.chain(&crate::video::EXTENSIONS);
+    extensions.map(|ext| ext.to_string()).collect()
+}
+
+// Case-insensitive match against the configured extensions
+pub fn has_image_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+// "JPG, .png,webp" -> ["jpg", "png", "webp"]
+pub fn parse_extensions(text: &str) -> Vec<String> {
+    text.split([',', ' '])
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+pub struct ScanOptions {
+    pub extensions: Vec<String>,
+    // Check the first bytes of files the extensions don't match
+    pub detect_by_content: bool,
+}
+
+// Files a scan passed over on purpose, for the summary
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Skipped {
+    pub hidden: usize,
+    pub empty: usize,
+    // Listed paths that don't exist, when sorting from a list
+    pub missing: usize,
+    // Listed paths that aren't images
+    pub not_images: usize,
+}
+
+impl Skipped {
+    // "2 hidden and 1 empty files", or None when nothing was skipped
+    pub fn describe(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            (self.hidden, t!("skipped-hidden", count = self.hidden)),
+            (self.empty, t!("skipped-empty", count = self.empty)),
+            (self.missing, t!("skipped-missing", count = self.missing)),
+            (
+                self.not_images,
+                t!("skipped-not-images", count = self.not_images),
+            ),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(_, part)| part)
+        .collect();
+        let (last, rest) = parts.split_last()?;
+        if rest.is_empty() {
+            Some(t!("skipped-one", kinds = last.as_str()))
+        } else {
+            Some(t!(
+                "skipped-many",
+                rest = rest.join(", "),
+                last = last.as_str()
+            ))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    Image,
+    Hidden,
+    Empty,
+    Other,
+}
+
+fn classify(path: &Path, options: &ScanOptions) -> Verdict {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    if hidden {
+        return Verdict::Hidden;
+    }
+    let by_extension = has_image_extension(path, &options.extensions);
+    if !by_extension && !options.detect_by_content {
+        return Verdict::Other;
+    }
+    match std::fs::metadata(path) {
+        Ok(metadata) if !metadata.is_file() => return Verdict::Other,
+        Ok(metadata) if metadata.len() == 0 => return Verdict::Empty,
+        Ok(_) => {}
+        Err(_) => return Verdict::Other,
+    }
+    if by_extension || looks_like_image(path) {
+        Verdict::Image
+    } else {
+        Verdict::Other
+    }
+}
+
+// Whether the file starts with the magic number of a format we can decode
+fn looks_like_image(path: &Path) -> bool {
+    let mut header = [0; 32];
+    let Ok(read) = File::open(path).and_then(|mut file| file.read(&mut header)) else {
+        return false;
+    };
+    let header = &header[..read];
+    #[cfg(feature = "heif")]
+    if crate::heif::is_heif(header) {
+        return true;
+    }
+    image::guess_format(header).is_ok_and(|format| format.can_read())
+}
+
+// Report each image in `dir` to `found` until it returns false or the scan
+// is cancelled
+pub fn scan(
+    dir: &Path,
+    options: &ScanOptions,
+    cancel: &CancellationToken,
+    mut found: impl FnMut(PathBuf) -> bool,
+) -> io::Result<Skipped> {
+    let mut skipped = Skipped::default();
+    for entry in std::fs::read_dir(dir)?.filter_map(Result::ok) {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let path = entry.path();
+        match classify(&path, options) {
+            Verdict::Image => {
+                if !found(path) {
+                    break;
+                }
+            }
+            Verdict::Hidden => skipped.hidden += 1,
+            Verdict::Empty => skipped.empty += 1,
+            Verdict::Other => {}
+        }
+    }
+    Ok(skipped)
+}
+
+// The images with one of `extensions` directly in `dir`, with their sizes,
+// newest first. Each file is looked at once
+pub fn list_folder(dir: &Path, extensions: &[String]) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| has_image_extension(path, extensions))
+        .map(|path| {
+            // Through links, which the link sort modes leave here
+            let metadata = std::fs::metadata(&path).ok();
+            let size = metadata.as_ref().map_or(0, |metadata| metadata.len());
+            let modified = metadata
+                .and_then(|metadata| metadata.modified().ok())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (path, size, modified)
+        })
+        .collect();
+    files.sort_by_key(|(_, _, modified)| std::cmp::Reverse(*modified));
+    files
+        .into_iter()
+        .map(|(path, size, _)| (path, size))
+        .collect()
+}
+
+// Paths from a list, one per line; relative ones are taken from `cwd`
+pub fn parse_list(text: &str, cwd: &Path) -> Vec<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| cwd.join(line))
+        .collect()
+}
+
+// Like `scan`, but for an explicit list of files that may live anywhere
+pub fn scan_list(
+    paths: &[PathBuf],
+    options: &ScanOptions,
+    cancel: &CancellationToken,
+    mut found: impl FnMut(PathBuf) -> bool,
+) -> Skipped {
+    let mut skipped = Skipped::default();
+    let mut seen = HashSet::new();
+    for path in paths {
+        if cancel.is_cancelled() {
+            break;
+        }
+        if !seen.insert(path) {
+            continue;
+        }
+        if !path.exists() {
+            skipped.missing += 1;
+            continue;
+        }
+        match classify(path, options) {
+            Verdict::Image => {
+                if !found(path.clone()) {
+                    break;
+                }
+            }
+            Verdict::Hidden => skipped.hidden += 1,
+            Verdict::Empty => skipped.empty += 1,
+            Verdict::Other => skipped.not_images += 1,
+        }
+    }
+    skipped
+}
+
+// Which queued files to show: a glob such as `IMG_*` when the text has
+// wildcards, otherwise a substring, matched against the file name ignoring case
+#[derive(Default)]
+pub struct NameFilter {
+    glob: Option<glob::Pattern>,
+    needle: String,
+}
+
+impl NameFilter {
+    pub fn new(text: &str) -> Self {
+        let text = text.trim();
+        let glob = text
+            .contains(['*', '?', '['])
+            .then(|| glob::Pattern::new(text).ok())
+            .flatten();
+        Self {
+            glob,
+            needle: text.to_lowercase(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.needle.is_empty()
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        match &self.glob {
+            Some(glob) => glob.matches_with(
+                &name,
+                glob::MatchOptions {
+                    case_sensitive: false,
+                    ..glob::MatchOptions::new()
+                },
+            ),
+            None => name.to_lowercase().contains(&self.needle),
+        }
+    }
+}
+
+// Size and modified time of a queued file, read once while scanning so the
+// filter bar never has to look at the disk
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FileInfo {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileInfo {
+    pub fn of(path: &Path) -> Self {
+        std::fs::metadata(path)
+            .map(|metadata| Self {
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            })
+            .unwrap_or_default()
+    }
+}
+
+// The filter bar: the name filter plus an optional range of modified dates
+// and file sizes, all of which a file has to match to stay queued
+#[derive(Default)]
+pub struct QueueFilter {
+    pub name: NameFilter,
+    pub modified_from: Option<NaiveDate>,
+    pub modified_to: Option<NaiveDate>,
+    pub min_bytes: Option<u64>,
+    pub max_bytes: Option<u64>,
+}
+
+impl QueueFilter {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && !self.needs_metadata()
+    }
+
+    fn needs_metadata(&self) -> bool {
+        self.modified_from.is_some()
+            || self.modified_to.is_some()
+            || self.min_bytes.is_some()
+            || self.max_bytes.is_some()
+    }
+
+    // Whether `path` passes, going by `info` from the scan. A file the scan
+    // didn't see, such as one put back by an undo, passes on size and date
+    pub fn matches(&self, path: &Path, info: Option<&FileInfo>) -> bool {
+        if !self.name.is_empty() && !self.name.matches(path) {
+            return false;
+        }
+        let Some(info) = info.filter(|_| self.needs_metadata()) else {
+            return true;
+        };
+        if self.min_bytes.is_some_and(|min| info.size < min)
+            || self.max_bytes.is_some_and(|max| info.size > max)
+        {
+            return false;
+        }
+        if self.modified_from.is_none() && self.modified_to.is_none() {
+            return true;
+        }
+        let Some(modified) = info.modified else {
+            return false;
+        };
+        let day = DateTime::<Local>::from(modified).date_naive();
+        self.modified_from.is_none_or(|from| day >= from)
+            && self.modified_to.is_none_or(|to| day <= to)
+    }
+}
+
+// A day typed as YYYY-MM-DD
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()
+}
+
+// A size typed in megabytes, fractions allowed
+pub fn parse_megabytes(text: &str) -> Option<u64> {
+    let megabytes: f64 = text.trim().parse().ok()?;
+    (megabytes >= 0.0).then_some((megabytes * 1024.0 * 1024.0) as u64)
+}
+
+// The shortest tail of each path that no other path shares: just the file
+// name, unless two folders both have an IMG_0001.jpg
+pub fn short_names(paths: &[PathBuf]) -> HashMap<PathBuf, String> {
+    let tail = |path: &Path, depth: usize| -> PathBuf {
+        let components: Vec<_> = path.components().collect();
+        components[components.len().saturating_sub(depth)..]
+            .iter()
+            .collect()
+    };
+    let mut names = HashMap::new();
+    let mut pending: Vec<&PathBuf> = paths.iter().collect();
+    let mut depth = 1;
+    while !pending.is_empty() {
+        let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+        for path in &pending {
+            *counts.entry(tail(path, depth)).or_default() += 1;
+        }
+        let deepest = pending
+            .iter()
+            .all(|path| path.components().count() <= depth);
+        pending.retain(|path| {
+            let short = tail(path, depth);
+            if counts[&short] > 1 && !deepest {
+                return true;
+            }
+            names.insert((*path).clone(), short.display().to_string());
+            false
+        });
+        depth += 1;
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScratchDir;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG: &[u8] = b"\xff\xd8\xff\xe0\0\x10JFIF\0";
+
+    // A folder of oddly named files, removed again when dropped
+    struct Fixture(ScratchDir);
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let dir = ScratchDir::new(&format!("scan-{}", name));
+            for (file, contents) in [
+                ("upper.JPG", JPEG),
+                ("mixed.JpEg", JPEG),
+                ("old.jfif", JPEG),
+                ("short.jpe", JPEG),
+                ("plain.png", PNG),
+                ("scan", PNG),
+                ("photo.JPG.bak", JPEG),
+                (".hidden.png", PNG),
+                ("empty.png", b""),
+                ("notes.txt", b"not an image"),
+            ] {
+                std::fs::write(dir.join(file), contents).unwrap();
+            }
+            std::fs::create_dir(dir.join("folder.png")).unwrap();
+            Self(dir)
+        }
+
+        fn scan(&self, detect_by_content: bool) -> (Vec<String>, Skipped) {
+            let options = ScanOptions {
+                extensions: default_extensions(),
+                detect_by_content,
+            };
+            let mut names = Vec::new();
+            let skipped = scan(&self.0, &options, &CancellationToken::new(), |path| {
+                names.push(path.file_name().unwrap().to_string_lossy().into_owned());
+                true
+            })
+            .unwrap();
+            names.sort();
+            (names, skipped)
+        }
+    }
+
+    #[test]
+    fn extensions_match_any_case() {
+        let (names, skipped) = Fixture::new("extensions").scan(false);
+        assert_eq!(
+            names,
+            [
+                "mixed.JpEg",
+                "old.jfif",
+                "plain.png",
+                "short.jpe",
+                "upper.JPG"
+            ]
+        );
+        assert_eq!(
+            skipped,
+            Skipped {
+                hidden: 1,
+                empty: 1,
+                ..Skipped::default()
+            }
+        );
+    }
+
+    #[test]
+    fn content_detection_finds_misnamed_images() {
+        let (names, skipped) = Fixture::new("content").scan(true);
+        assert_eq!(
+            names,
+            [
+                "mixed.JpEg",
+                "old.jfif",
+                "photo.JPG.bak",
+                "plain.png",
+                "scan",
+                "short.jpe",
+                "upper.JPG"
+            ]
+        );
+        assert_eq!(
+            skipped,
+            Skipped {
+                hidden: 1,
+                empty: 1,
+                ..Skipped::default()
+            }
+        );
+    }
+
+    #[test]
+    fn filter_takes_globs_and_substrings() {
+        let names = [
+            "IMG_0001.JPG",
+            "img_0002.png",
+            "Screenshot 2024.png",
+            "DSC_01.jpg",
+        ];
+        let matching = |filter: &str| -> Vec<&str> {
+            let filter = NameFilter::new(filter);
+            names
+                .into_iter()
+                .filter(|name| filter.matches(&Path::new("/photos").join(name)))
+                .collect()
+        };
+        assert_eq!(matching("IMG_*"), ["IMG_0001.JPG", "img_0002.png"]);
+        assert_eq!(matching("screenshot"), ["Screenshot 2024.png"]);
+        assert_eq!(matching("*.jpg"), ["IMG_0001.JPG", "DSC_01.jpg"]);
+        // An unclosed bracket isn't a valid glob, so it's looked for as is
+        assert!(matching("[").is_empty());
+        assert!(NameFilter::new("  ").is_empty());
+    }
+
+    #[test]
+    fn queue_filter_checks_size_and_date() {
+        let fixture = Fixture::new("queue-filter");
+        let small = fixture.0.join("plain.png");
+        let big = fixture.0.join("big.png");
+        std::fs::write(&big, vec![0; 2 * 1024 * 1024]).unwrap();
+        let today = Local::now().date_naive();
+
+        let (small_info, big_info) = (FileInfo::of(&small), FileInfo::of(&big));
+        let filter = QueueFilter {
+            min_bytes: parse_megabytes("1.5"),
+            ..QueueFilter::default()
+        };
+        assert!(filter.matches(&big, Some(&big_info)));
+        assert!(!filter.matches(&small, Some(&small_info)));
+        // Nothing known about it, so only the name counts
+        assert!(filter.matches(&small, None));
+
+        let filter = QueueFilter {
+            name: NameFilter::new("*.png"),
+            modified_from: Some(today),
+            max_bytes: parse_megabytes("1"),
+            ..QueueFilter::default()
+        };
+        assert!(filter.matches(&small, Some(&small_info)));
+        assert!(!filter.matches(&big, Some(&big_info)));
+
+        let filter = QueueFilter {
+            modified_to: today.pred_opt(),
+            ..QueueFilter::default()
+        };
+        assert!(!filter.matches(&small, Some(&small_info)));
+
+        assert_eq!(
+            parse_date(" 2024-05-01 "),
+            NaiveDate::from_ymd_opt(2024, 5, 1)
+        );
+        assert_eq!(parse_date("May 1"), None);
+        assert_eq!(parse_megabytes("-1"), None);
+    }
+
+    #[test]
+    fn parses_edited_extension_list() {
+        assert_eq!(parse_extensions("JPG, .png,,webp "), ["jpg", "png", "webp"]);
+    }
+}
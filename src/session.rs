@@ -0,0 +1,51 @@
+use crate::layout::BucketLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = ".leftright-session.json";
+
+// Per-folder progress, saved next to the images so reopening the folder
+// picks up where the last session left off
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    // Files marked as fine where they are: relative to the folder, or
+    // absolute for files from elsewhere when sorting from a list
+    pub kept: BTreeSet<PathBuf>,
+    // Bucket layout picked for this folder, if any
+    pub layout: Option<BucketLayout>,
+    // Starred files, recorded like `kept` and following them into categories
+    pub favorites: BTreeSet<PathBuf>,
+}
+
+impl Session {
+    pub fn load(base_dir: &Path) -> Self {
+        let path = base_dir.join(FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid session file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, base_dir: &Path) -> std::io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(base_dir.join(FILE_NAME), contents)
+    }
+
+    // How `path` is recorded in `kept`
+    pub fn entry(base_dir: &Path, path: &Path) -> PathBuf {
+        path.strip_prefix(base_dir).unwrap_or(path).to_path_buf()
+    }
+
+    pub fn is_kept(&self, base_dir: &Path, path: &Path) -> bool {
+        self.kept.contains(&Self::entry(base_dir, path))
+    }
+
+    pub fn is_favorite(&self, base_dir: &Path, path: &Path) -> bool {
+        self.favorites.contains(&Self::entry(base_dir, path))
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::NaiveDateTime;
+use image::DynamicImage;
+use std::path::PathBuf;
+
+// Images this many hash bits apart or fewer look alike
+const MAX_DISTANCE: u32 = 10;
+// and were taken at most this many seconds apart
+const MAX_GAP_SECS: i64 = 10;
+
+// 64-bit difference hash: whether each pixel of a 9x8 grayscale copy is
+// brighter than its right-hand neighbour
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// What grouping needs to know about one queued image
+pub struct Fingerprint {
+    pub path: PathBuf,
+    pub hash: Option<u64>,
+    pub time: Option<NaiveDateTime>,
+}
+
+// Split images, given in queue order, into groups of lookalikes taken close
+// together. Groups come out in the order of their first member in the queue,
+// each ordered by capture time
+pub fn group(images: Vec<Fingerprint>) -> Vec<Vec<PathBuf>> {
+    let mut by_time: Vec<usize> = (0..images.len()).collect();
+    by_time.sort_by_key(|idx| images[*idx].time);
+
+    // Neighbours in time that look alike share a group
+    let mut group_of = vec![0; images.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<usize> = None;
+    for idx in by_time {
+        let joins = previous.is_some_and(|prev| similar(&images[prev], &images[idx]));
+        if joins {
+            let group = group_of[previous.unwrap_or_default()];
+            groups[group].push(idx);
+            group_of[idx] = group;
+        } else {
+            group_of[idx] = groups.len();
+            groups.push(vec![idx]);
+        }
+        previous = Some(idx);
+    }
+
+    groups.sort_by_key(|members| members.iter().min().copied());
+    let mut paths: Vec<Option<PathBuf>> =
+        images.into_iter().map(|image| Some(image.path)).collect();
+    groups
+        .into_iter()
+        .map(|members| {
+            members
+                .into_iter()
+                .filter_map(|idx| paths[idx].take())
+                .collect()
+        })
+        .collect()
+}
+
+fn similar(a: &Fingerprint, b: &Fingerprint) -> bool {
+    let (Some(hash_a), Some(hash_b), Some(time_a), Some(time_b)) = (a.hash, b.hash, a.time, b.time)
+    else {
+        return false;
+    };
+    distance(hash_a, hash_b) <= MAX_DISTANCE
+        && (time_a - time_b).num_seconds().abs() <= MAX_GAP_SECS
+}
@@ -0,0 +1,402 @@
+use crate::category_name;
+use crate::config::{CollisionPolicy, SortMode};
+use crate::i18n::t;
+use crate::scan::{self, ScanOptions, Skipped};
+use crate::xmp;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+// Rename a file, falling back to copy-then-delete when the destination is on
+// another filesystem (e.g. a category folder on an external drive)
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = std::fs::copy(from, to) {
+                // Nor half a copy if it didn't finish
+                let _ = std::fs::remove_file(to);
+                return Err(e);
+            }
+            if let Err(e) = std::fs::remove_file(from) {
+                // Don't leave two copies behind if the source can't be removed
+                let _ = std::fs::remove_file(to);
+                return Err(e);
+            }
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+// Point `to` at `from` without touching the original, for the link sort modes
+pub fn link_file(from: &Path, to: &Path, mode: SortMode) -> io::Result<()> {
+    if mode == SortMode::Hardlink {
+        match std::fs::hard_link(from, to) {
+            // Hard links can't cross filesystems and some (FAT, many network
+            // shares) have none, so a symlink stands in
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::CrossesDevices | io::ErrorKind::Unsupported
+                ) =>
+            {
+                log::info!("Symlinking {} instead: {}", to.display(), e);
+            }
+            result => return result,
+        }
+    }
+    // Absolute, so the link survives being moved to another category
+    let target = std::fs::canonicalize(from)?;
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(target, to);
+    #[cfg(windows)]
+    return match std::os::windows::fs::symlink_file(target, to) {
+        // ERROR_PRIVILEGE_NOT_HELD: symlinks need Developer Mode or admin rights
+        Err(e) if e.raw_os_error() == Some(1314) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            t!("symlink-not-permitted"),
+        )),
+        result => result,
+    };
+}
+
+// `dir/name`, or `dir/stem (n).ext` if that's already on disk or in `taken`
+pub fn unique_destination(dir: &Path, name: &OsStr, taken: &HashSet<PathBuf>) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() && !taken.contains(&candidate) {
+        return candidate;
+    }
+
+    let name = Path::new(name);
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = name
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|candidate| !candidate.exists() && !taken.contains(candidate))
+        .unwrap()
+}
+
+// What to do with a file about to be sorted into a folder
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+    // Sort it to this path, which under `Overwrite` may be taken
+    To(PathBuf),
+    // Leave it where it is, as `Skip` says for a taken name
+    Skip(PathBuf),
+    // Someone has to choose, as `Ask` says for a taken name
+    Ask(PathBuf),
+}
+
+// Where a file called `name` goes in `dir` under `policy`. `taken` holds the
+// destinations of moves that haven't reached the disk yet; those only ever
+// lead to a numbered name
+pub fn resolve(
+    dir: &Path,
+    name: &OsStr,
+    policy: CollisionPolicy,
+    taken: &HashSet<PathBuf>,
+) -> Resolution {
+    let existing = dir.join(name);
+    if !existing.exists() {
+        return Resolution::To(unique_destination(dir, name, taken));
+    }
+    match policy {
+        CollisionPolicy::Rename => Resolution::To(unique_destination(dir, name, taken)),
+        CollisionPolicy::Overwrite => Resolution::To(existing),
+        CollisionPolicy::Skip => Resolution::Skip(existing),
+        CollisionPolicy::Ask => Resolution::Ask(existing),
+    }
+}
+
+// Carry out `sort` on disk
+pub fn apply(sort: &Move) -> io::Result<()> {
+    match sort.mode {
+        SortMode::Move => move_file(&sort.from, &sort.to),
+        SortMode::Tag => xmp::add_tag(&sort.from, &sort.category),
+        mode => {
+            // Only an `Overwrite` answer gets here with the name taken, and a
+            // link can't be made over an existing file
+            if sort.to.symlink_metadata().is_ok() {
+                std::fs::remove_file(&sort.to)?;
+            }
+            link_file(&sort.from, &sort.to, mode)
+        }
+    }
+}
+
+// Put things back the way they were before `sort`
+pub fn revert(sort: &Move) -> io::Result<()> {
+    match sort.mode {
+        SortMode::Move if sort.from.exists() => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            t!("already-exists", path = sort.from.display().to_string()),
+        )),
+        SortMode::Move => move_file(&sort.to, &sort.from),
+        SortMode::Tag => xmp::remove_tag(&sort.from, &sort.category),
+        SortMode::Symlink | SortMode::Hardlink => std::fs::remove_file(&sort.to),
+    }
+}
+
+// A file sorted by a `Sorter`, or by the window
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    pub from: PathBuf,
+    // The same as `from` when the file was tagged in place
+    pub to: PathBuf,
+    pub category: String,
+    pub mode: SortMode,
+}
+
+// Sorting a folder without a window: the queue, the category folders and
+// the undo history. Every file operation happens before the call returns.
+// The window keeps a queue of its own, since it's filtered, reordered and
+// sorted in the background, but files, collisions and undo go through the
+// same `resolve`, `apply` and `revert`
+pub struct Sorter {
+    dir: PathBuf,
+    categories: Vec<String>,
+    mode: SortMode,
+    collision_policy: CollisionPolicy,
+    options: ScanOptions,
+    queue: Vec<PathBuf>,
+    // Index into `queue` of the image to sort next
+    current: usize,
+    history: Vec<Move>,
+}
+
+impl Sorter {
+    // Sort the images in `dir` into folders named after `categories`, which
+    // are made as files are moved into them
+    pub fn new(dir: impl Into<PathBuf>, categories: Vec<String>) -> io::Result<Self> {
+        for category in &categories {
+            category_name::check(category)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+        Ok(Self {
+            dir: dir.into(),
+            categories,
+            mode: SortMode::Move,
+            collision_policy: CollisionPolicy::Rename,
+            options: ScanOptions {
+                extensions: scan::default_extensions(),
+                detect_by_content: false,
+            },
+            queue: Vec::new(),
+            current: 0,
+            history: Vec::new(),
+        })
+    }
+
+    pub fn with_mode(mut self, mode: SortMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // `Ask` has no one to ask, so a taken name fails the move instead
+    pub fn with_collision_policy(mut self, policy: CollisionPolicy) -> Self {
+        self.collision_policy = policy;
+        self
+    }
+
+    pub fn with_scan_options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // Queue the images in the folder, by name, replacing what was queued
+    pub fn scan(&mut self) -> io::Result<Skipped> {
+        let mut found = Vec::new();
+        let skipped = scan::scan(
+            &self.dir,
+            &self.options,
+            &CancellationToken::new(),
+            |path| {
+                found.push(path);
+                true
+            },
+        )?;
+        found.sort();
+        self.queue = found;
+        self.current = 0;
+        Ok(skipped)
+    }
+
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    // Images still to sort, in queue order
+    pub fn queue(&self) -> &[PathBuf] {
+        &self.queue
+    }
+
+    pub fn current(&self) -> Option<&Path> {
+        self.queue.get(self.current).map(PathBuf::as_path)
+    }
+
+    // Moves in the order they were made, undone from the end
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    // Move on to the next image, wrapping around at the end of the queue
+    pub fn skip(&mut self) {
+        if !self.queue.is_empty() {
+            self.current = (self.current + 1) % self.queue.len();
+        }
+    }
+
+    // File the current image under `category`. None when the collision
+    // policy skipped it, in which case it stays current
+    pub fn move_current(&mut self, category: &str) -> io::Result<Option<Move>> {
+        if !self.categories.iter().any(|c| c == category) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                t!("unknown-category", name = category),
+            ));
+        }
+        let Some(from) = self.queue.get(self.current).cloned() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                t!("nothing-to-sort"),
+            ));
+        };
+
+        let to = if self.mode.uses_folders() {
+            let dir = self.dir.join(category);
+            std::fs::create_dir_all(&dir)?;
+            let name = from.file_name().unwrap_or_default();
+            match resolve(&dir, name, self.collision_policy, &HashSet::new()) {
+                Resolution::To(to) => to,
+                Resolution::Skip(_) => return Ok(None),
+                Resolution::Ask(existing) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        t!("already-exists", path = existing.display().to_string()),
+                    ))
+                }
+            }
+        } else {
+            from.clone()
+        };
+
+        let done = Move {
+            from,
+            to,
+            category: category.to_string(),
+            mode: self.mode,
+        };
+        apply(&done)?;
+
+        self.queue.remove(self.current);
+        if self.current >= self.queue.len() {
+            self.current = 0;
+        }
+        self.history.push(done.clone());
+        Ok(Some(done))
+    }
+
+    // Take back the last move; the file becomes the current image again.
+    // None when there was nothing to undo
+    pub fn undo(&mut self) -> io::Result<Option<Move>> {
+        let Some(last) = self.history.last() else {
+            return Ok(None);
+        };
+        revert(last)?;
+
+        let undone = self.history.pop().unwrap();
+        self.current = self.current.min(self.queue.len());
+        self.queue.insert(self.current, undone.from.clone());
+        Ok(Some(undone))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, Resolution, Sorter};
+    use crate::config::CollisionPolicy;
+    use crate::ScratchDir;
+    use std::collections::HashSet;
+
+    fn folder(name: &str, files: &[&str]) -> ScratchDir {
+        let dir = ScratchDir::new(&format!("sorter-{}", name));
+        for file in files {
+            std::fs::write(dir.join(file), file).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn moves_and_undoes_in_order() {
+        let dir = folder("undo", &["a.png", "b.png", "notes.txt"]);
+        let mut sorter = Sorter::new(&*dir, vec!["keep".into(), "trash".into()]).unwrap();
+        sorter.scan().unwrap();
+        assert_eq!(sorter.queue(), [dir.join("a.png"), dir.join("b.png")]);
+
+        let moved = sorter.move_current("keep").unwrap().unwrap();
+        assert_eq!(moved.to, dir.join("keep").join("a.png"));
+        assert!(moved.to.exists() && !moved.from.exists());
+        assert_eq!(sorter.current(), Some(dir.join("b.png").as_path()));
+
+        sorter.move_current("trash").unwrap();
+        assert!(sorter.current().is_none());
+
+        assert_eq!(sorter.undo().unwrap().unwrap().category, "trash");
+        assert_eq!(sorter.current(), Some(dir.join("b.png").as_path()));
+        sorter.undo().unwrap();
+        assert_eq!(sorter.queue(), [dir.join("a.png"), dir.join("b.png")]);
+        assert!(dir.join("a.png").exists() && dir.join("b.png").exists());
+        assert!(sorter.undo().unwrap().is_none());
+    }
+
+    #[test]
+    fn taken_names_follow_the_collision_policy() {
+        let dir = folder("collisions", &["a.png"]);
+        std::fs::create_dir_all(dir.join("keep")).unwrap();
+        std::fs::write(dir.join("keep").join("a.png"), "older").unwrap();
+
+        let mut sorter = Sorter::new(&*dir, vec!["keep".into()])
+            .unwrap()
+            .with_collision_policy(CollisionPolicy::Skip);
+        sorter.scan().unwrap();
+        assert_eq!(sorter.move_current("keep").unwrap(), None);
+        assert_eq!(sorter.queue().len(), 1);
+
+        let mut sorter = sorter.with_collision_policy(CollisionPolicy::Ask);
+        assert!(sorter.move_current("keep").is_err());
+
+        let mut sorter = sorter.with_collision_policy(CollisionPolicy::Rename);
+        let moved = sorter.move_current("keep").unwrap().unwrap();
+        assert_eq!(moved.to, dir.join("keep").join("a (1).png"));
+        assert!(sorter.move_current("nowhere").is_err());
+    }
+
+    #[test]
+    fn names_promised_to_moves_in_flight_are_numbered() {
+        let dir = folder("resolve", &["a.png"]);
+        let taken = HashSet::from([dir.join("b.png")]);
+        let resolve = |name: &str, policy| resolve(&dir, name.as_ref(), policy, &taken);
+        // Not on disk yet, so no policy applies, but it can't be reused either
+        assert_eq!(
+            resolve("b.png", CollisionPolicy::Overwrite),
+            Resolution::To(dir.join("b (1).png"))
+        );
+        assert_eq!(
+            resolve("a.png", CollisionPolicy::Overwrite),
+            Resolution::To(dir.join("a.png"))
+        );
+        assert_eq!(
+            resolve("a.png", CollisionPolicy::Ask),
+            Resolution::Ask(dir.join("a.png"))
+        );
+        assert_eq!(
+            resolve("c.png", CollisionPolicy::Skip),
+            Resolution::To(dir.join("c.png"))
+        );
+    }
+}
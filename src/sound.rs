@@ -0,0 +1,188 @@
+use crate::config::Config;
+use crate::i18n::t;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// What a sound is played for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cue {
+    // A card flying to a bucket
+    Sort,
+    Undo,
+    // A file operation that failed
+    Error,
+    // The last image of the queue sorted
+    Done,
+}
+
+impl Cue {
+    pub const ALL: [Cue; 4] = [Cue::Sort, Cue::Undo, Cue::Error, Cue::Done];
+
+    pub fn label(self) -> String {
+        match self {
+            Cue::Sort => t!("sound-sort"),
+            Cue::Undo => t!("sound-undo"),
+            Cue::Error => t!("sound-error"),
+            Cue::Done => t!("sound-done"),
+        }
+    }
+
+    // The sound built into the binary, used unless a custom file is set
+    #[cfg(any(feature = "sound", test))]
+    fn embedded(self) -> &'static [u8] {
+        match self {
+            Cue::Sort => include_bytes!("../sounds/sort.wav"),
+            Cue::Undo => include_bytes!("../sounds/undo.wav"),
+            Cue::Error => include_bytes!("../sounds/error.wav"),
+            Cue::Done => include_bytes!("../sounds/done.wav"),
+        }
+    }
+}
+
+// The sound settings, as the audio thread sees them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    // 0 to 1
+    pub volume: f32,
+    // Files played instead of the built-in sounds
+    pub files: HashMap<Cue, PathBuf>,
+}
+
+impl SoundSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.sound,
+            volume: config.sound_volume,
+            files: config.sound_files.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "sound")]
+enum Command {
+    Configure(SoundSettings),
+    Play(Cue),
+}
+
+// Audio cues, when built with the `sound` feature. Sounds are played on a
+// thread of their own, so decoding or a slow audio device never holds up a
+// frame; without an output device this does nothing
+#[derive(Clone, Default)]
+pub struct Sounds {
+    #[cfg(feature = "sound")]
+    tx: Option<std::sync::mpsc::Sender<Command>>,
+}
+
+#[cfg(feature = "sound")]
+impl Sounds {
+    pub fn new(settings: SoundSettings) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("leftright-sound".into())
+            .spawn(move || play_loop(settings, rx));
+        if let Err(e) = &spawned {
+            log::warn!("Sound unavailable: {}", e);
+        }
+        Self {
+            tx: spawned.ok().map(|_| tx),
+        }
+    }
+
+    pub fn configure(&self, settings: SoundSettings) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Command::Configure(settings));
+        }
+    }
+
+    // Ignored while sound is off, and while the same cue is still playing so
+    // fast sorting doesn't pile up whooshes
+    pub fn play(&self, cue: Cue) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Command::Play(cue));
+        }
+    }
+}
+
+#[cfg(feature = "sound")]
+fn play_loop(mut settings: SoundSettings, rx: std::sync::mpsc::Receiver<Command>) {
+    // The stream has to stay alive for anything to be heard
+    let (_stream, handle) = match rodio::OutputStream::try_default() {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("No audio output: {}", e);
+            return;
+        }
+    };
+    let mut playing: HashMap<Cue, rodio::Sink> = HashMap::new();
+    let mut loaded: HashMap<Cue, Vec<u8>> = HashMap::new();
+    for command in rx {
+        let cue = match command {
+            Command::Configure(new) => {
+                if new.files != settings.files {
+                    loaded.clear();
+                }
+                for sink in playing.values() {
+                    sink.set_volume(new.volume);
+                }
+                settings = new;
+                continue;
+            }
+            Command::Play(cue) => cue,
+        };
+        if !settings.enabled || playing.get(&cue).is_some_and(|sink| !sink.empty()) {
+            continue;
+        }
+
+        let bytes = loaded
+            .entry(cue)
+            .or_insert_with(|| match settings.files.get(&cue) {
+                Some(path) => std::fs::read(path).unwrap_or_else(|e| {
+                    log::warn!("Cannot read sound {}: {}", path.display(), e);
+                    cue.embedded().to_vec()
+                }),
+                None => cue.embedded().to_vec(),
+            });
+        let source = match rodio::Decoder::new(std::io::Cursor::new(bytes.clone())) {
+            Ok(source) => source,
+            Err(e) => {
+                log::warn!("Cannot play the {:?} sound: {}", cue, e);
+                continue;
+            }
+        };
+        match rodio::Sink::try_new(&handle) {
+            Ok(sink) => {
+                sink.set_volume(settings.volume);
+                sink.append(source);
+                playing.insert(cue, sink);
+            }
+            Err(e) => log::warn!("Cannot play the {:?} sound: {}", cue, e),
+        }
+    }
+}
+
+#[cfg(not(feature = "sound"))]
+impl Sounds {
+    pub fn new(_settings: SoundSettings) -> Self {
+        Self::default()
+    }
+
+    pub fn configure(&self, _settings: SoundSettings) {}
+
+    pub fn play(&self, _cue: Cue) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cue;
+
+    #[test]
+    fn built_in_sounds_are_wav() {
+        for cue in Cue::ALL {
+            let bytes = cue.embedded();
+            assert_eq!(&bytes[..4], b"RIFF", "{:?}", cue);
+            assert_eq!(&bytes[8..12], b"WAVE", "{:?}", cue);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::i18n::t;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// Steps an image goes through from disk to screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Read,
+    Decode,
+    Resize,
+    Convert,
+    Upload,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Read,
+        Stage::Decode,
+        Stage::Resize,
+        Stage::Convert,
+        Stage::Upload,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            Stage::Read => t!("stage-read"),
+            Stage::Decode => t!("stage-decode"),
+            Stage::Resize => t!("stage-resize"),
+            Stage::Convert => t!("stage-convert"),
+            Stage::Upload => t!("stage-upload"),
+        }
+    }
+}
+
+// Running totals of where image loading spends its time, shared between the
+// loader tasks and the UI. Recording is a couple of relaxed atomic adds, so it
+// stays on whether or not anyone is looking
+pub struct LoadStats {
+    epoch: Instant,
+    nanos: [AtomicU64; 5],
+    counts: [AtomicU64; 5],
+    loaded: AtomicU64,
+    failed: AtomicU64,
+    // Nanoseconds after `epoch` of the first decode started and the last
+    // image finished; u64::MAX and 0 until then
+    first_start: AtomicU64,
+    last_finish: AtomicU64,
+}
+
+impl Default for LoadStats {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            nanos: Default::default(),
+            counts: Default::default(),
+            loaded: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            first_start: AtomicU64::new(u64::MAX),
+            last_finish: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LoadStats {
+    // Run `f`, adding the time it took to `stage`
+    pub fn time<T>(&self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.add(stage, started.elapsed());
+        result
+    }
+
+    pub fn add(&self, stage: Stage, elapsed: Duration) {
+        self.nanos[stage as usize].fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.counts[stage as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn started(&self) {
+        let now = self.epoch.elapsed().as_nanos() as u64;
+        self.first_start.fetch_min(now, Ordering::Relaxed);
+    }
+
+    pub fn finished(&self) {
+        let now = self.epoch.elapsed().as_nanos() as u64;
+        self.last_finish.fetch_max(now, Ordering::Relaxed);
+        self.loaded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failed(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    // Start counting afresh, e.g. for another folder
+    pub fn reset(&self) {
+        for (nanos, count) in self.nanos.iter().zip(&self.counts) {
+            nanos.store(0, Ordering::Relaxed);
+            count.store(0, Ordering::Relaxed);
+        }
+        self.loaded.store(0, Ordering::Relaxed);
+        self.failed.store(0, Ordering::Relaxed);
+        self.first_start.store(u64::MAX, Ordering::Relaxed);
+        self.last_finish.store(0, Ordering::Relaxed);
+    }
+
+    // Average milliseconds per image spent in `stage`
+    pub fn average_ms(&self, stage: Stage) -> f64 {
+        let count = self.counts[stage as usize].load(Ordering::Relaxed);
+        if count == 0 {
+            return 0.0;
+        }
+        self.nanos[stage as usize].load(Ordering::Relaxed) as f64 / count as f64 / 1e6
+    }
+
+    pub fn loaded(&self) -> u64 {
+        self.loaded.load(Ordering::Relaxed)
+    }
+
+    // From the first decode starting to the last one finishing
+    pub fn elapsed(&self) -> Duration {
+        let first = self.first_start.load(Ordering::Relaxed);
+        let last = self.last_finish.load(Ordering::Relaxed);
+        Duration::from_nanos(last.saturating_sub(first))
+    }
+
+    pub fn images_per_second(&self) -> f64 {
+        let seconds = self.elapsed().as_secs_f64();
+        if seconds > 0.0 {
+            self.loaded() as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    // "read 1.2 ms, decode 35.0 ms, ..."
+    pub fn stage_summary(&self) -> String {
+        Stage::ALL
+            .iter()
+            .map(|stage| format!("{} {:.1} ms", stage.label(), self.average_ms(*stage)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_are_per_stage() {
+        let stats = LoadStats::default();
+        stats.add(Stage::Decode, Duration::from_millis(30));
+        stats.add(Stage::Decode, Duration::from_millis(10));
+        stats.add(Stage::Upload, Duration::from_millis(2));
+        assert_eq!(stats.average_ms(Stage::Decode), 20.0);
+        assert_eq!(stats.average_ms(Stage::Upload), 2.0);
+        assert_eq!(stats.average_ms(Stage::Read), 0.0);
+
+        stats.reset();
+        assert_eq!(stats.average_ms(Stage::Decode), 0.0);
+    }
+}
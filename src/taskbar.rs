@@ -0,0 +1,182 @@
+use raw_window_handle::RawWindowHandle;
+use tokio::runtime::Runtime;
+
+// Sorting progress on the taskbar button or launcher icon, on platforms that
+// show one. Unchanged values aren't sent again
+pub struct Taskbar {
+    progress: Option<platform::Progress>,
+    // Last value sent, in tenths of a percent
+    last: Option<Option<u32>>,
+}
+
+impl Taskbar {
+    pub fn new(window: RawWindowHandle, runtime: &Runtime) -> Self {
+        Self {
+            progress: platform::Progress::new(window, runtime),
+            last: None,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            progress: None,
+            last: None,
+        }
+    }
+
+    // `None` hides the progress bar
+    pub fn set_progress(&mut self, fraction: Option<f32>) {
+        let value = fraction.map(|fraction| (fraction.clamp(0.0, 1.0) * 1000.0).round() as u32);
+        if self.last == Some(value) {
+            return;
+        }
+        self.last = Some(value);
+        if let Some(progress) = &self.progress {
+            progress.set(value);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use raw_window_handle::RawWindowHandle;
+    use std::ptr;
+    use tokio::runtime::Runtime;
+    use winapi::shared::windef::HWND;
+    use winapi::shared::winerror::SUCCEEDED;
+    use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
+    use winapi::um::combaseapi::{CoCreateInstance, CoInitializeEx};
+    use winapi::um::objbase::COINIT_APARTMENTTHREADED;
+    use winapi::um::shobjidl_core::{
+        CLSID_TaskbarList, ITaskbarList3, TBPF_NOPROGRESS, TBPF_NORMAL,
+    };
+    use winapi::Interface;
+
+    // ITaskbarList3 for the main window
+    pub struct Progress {
+        list: *mut ITaskbarList3,
+        hwnd: HWND,
+    }
+
+    impl Progress {
+        pub fn new(window: RawWindowHandle, _runtime: &Runtime) -> Option<Self> {
+            let RawWindowHandle::Win32(handle) = window else {
+                return None;
+            };
+            let hwnd = handle.hwnd as HWND;
+            let mut list: *mut ITaskbarList3 = ptr::null_mut();
+            unsafe {
+                // Fails harmlessly if the windowing library already set COM up
+                CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+                let hr = CoCreateInstance(
+                    &CLSID_TaskbarList,
+                    ptr::null_mut(),
+                    CLSCTX_INPROC_SERVER,
+                    &ITaskbarList3::uuidof(),
+                    &mut list as *mut *mut ITaskbarList3 as *mut _,
+                );
+                if !SUCCEEDED(hr) || list.is_null() {
+                    return None;
+                }
+                if !SUCCEEDED((*list).HrInit()) {
+                    (*list).Release();
+                    return None;
+                }
+            }
+            Some(Self { list, hwnd })
+        }
+
+        pub fn set(&self, permille: Option<u32>) {
+            unsafe {
+                match permille {
+                    Some(permille) => {
+                        (*self.list).SetProgressState(self.hwnd, TBPF_NORMAL);
+                        (*self.list).SetProgressValue(self.hwnd, permille.into(), 1000);
+                    }
+                    None => {
+                        (*self.list).SetProgressState(self.hwnd, TBPF_NOPROGRESS);
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for Progress {
+        fn drop(&mut self) {
+            unsafe {
+                (*self.list).Release();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use raw_window_handle::RawWindowHandle;
+    use std::collections::HashMap;
+    use tokio::runtime::Runtime;
+    use tokio::sync::watch;
+    use zbus::zvariant::Value;
+
+    // Matches the installed .desktop file, which is how launchers find the icon
+    const APP_URI: &str = "application://leftright.desktop";
+
+    // Unity LauncherEntry signals, understood by Unity, Plasma and Dash to Dock
+    pub struct Progress {
+        tx: watch::Sender<Option<u32>>,
+    }
+
+    impl Progress {
+        pub fn new(_window: RawWindowHandle, runtime: &Runtime) -> Option<Self> {
+            let (tx, mut rx) = watch::channel(None);
+            runtime.spawn(async move {
+                let Ok(connection) = zbus::Connection::session().await else {
+                    return;
+                };
+                while rx.changed().await.is_ok() {
+                    let permille = *rx.borrow_and_update();
+                    let properties = HashMap::from([
+                        (
+                            "progress",
+                            Value::from(permille.unwrap_or(0) as f64 / 1000.0),
+                        ),
+                        ("progress-visible", Value::from(permille.is_some())),
+                    ]);
+                    let result = connection
+                        .emit_signal(
+                            None::<zbus::names::BusName>,
+                            "/com/canonical/unity/launcherentry/leftright",
+                            "com.canonical.Unity.LauncherEntry",
+                            "Update",
+                            &(APP_URI, properties),
+                        )
+                        .await;
+                    if let Err(e) = result {
+                        log::debug!("Failed to update launcher progress: {}", e);
+                    }
+                }
+            });
+            Some(Self { tx })
+        }
+
+        pub fn set(&self, permille: Option<u32>) {
+            let _ = self.tx.send(permille);
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use raw_window_handle::RawWindowHandle;
+    use tokio::runtime::Runtime;
+
+    pub struct Progress;
+
+    impl Progress {
+        pub fn new(_window: RawWindowHandle, _runtime: &Runtime) -> Option<Self> {
+            None
+        }
+
+        pub fn set(&self, _permille: Option<u32>) {}
+    }
+}
@@ -0,0 +1,139 @@
+use crate::i18n::t;
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+
+// Mid-saturation colors that stay distinguishable on both light and dark panels
+pub const CATEGORY_PALETTE: [Color32; 8] = [
+    Color32::from_rgb(230, 85, 75),
+    Color32::from_rgb(65, 140, 230),
+    Color32::from_rgb(80, 180, 95),
+    Color32::from_rgb(240, 170, 40),
+    Color32::from_rgb(170, 95, 220),
+    Color32::from_rgb(40, 180, 180),
+    Color32::from_rgb(235, 110, 170),
+    Color32::from_rgb(150, 150, 60),
+];
+
+// First palette color not already in use, cycling once the palette runs out
+pub fn next_palette_color(taken: &[Color32]) -> Color32 {
+    CATEGORY_PALETTE
+        .iter()
+        .find(|color| !taken.contains(color))
+        .copied()
+        .unwrap_or(CATEGORY_PALETTE[taken.len() % CATEGORY_PALETTE.len()])
+}
+
+// Black or white, whichever reads better on top of `background`
+pub fn contrast_text(background: Color32) -> Color32 {
+    let linear = egui::Rgba::from(background);
+    let luminance = 0.2126 * linear.r() + 0.7152 * linear.g() + 0.0722 * linear.b();
+    if luminance > 0.179 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    // Dark or light, whichever the OS is set to
+    #[default]
+    System,
+    Dark,
+    Light,
+    Custom,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::System, Theme::Dark, Theme::Light, Theme::Custom];
+
+    pub fn label(self) -> String {
+        match self {
+            Theme::System => t!("theme-system"),
+            Theme::Dark => t!("theme-dark"),
+            Theme::Light => t!("theme-light"),
+            Theme::Custom => t!("theme-custom"),
+        }
+    }
+
+    // The theme actually shown; System becomes Dark when the OS doesn't say
+    pub fn resolve(self, system: Option<eframe::Theme>) -> Theme {
+        match (self, system) {
+            (Theme::System, Some(eframe::Theme::Light)) => Theme::Light,
+            (Theme::System, _) => Theme::Dark,
+            (theme, _) => theme,
+        }
+    }
+}
+
+// Colors for everything drawn directly with the painter, so it stays visible
+// on whichever background the user picked
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub background: Color32,
+    pub bucket_fill: Color32,
+    pub shadow: Color32,
+    pub checker_light: Color32,
+    pub checker_dark: Color32,
+}
+
+impl Palette {
+    pub fn new(theme: Theme, custom_background: Color32) -> Self {
+        let background = match theme {
+            Theme::System | Theme::Dark => egui::Visuals::dark().panel_fill,
+            Theme::Light => egui::Visuals::light().panel_fill,
+            Theme::Custom => custom_background,
+        };
+
+        if is_light(background) {
+            Self {
+                background,
+                bucket_fill: background.linear_multiply(0.85).to_opaque(),
+                shadow: Color32::from_black_alpha(40),
+                checker_light: Color32::from_gray(255),
+                checker_dark: Color32::from_gray(220),
+            }
+        } else {
+            Self {
+                background,
+                bucket_fill: Color32::from_gray(40),
+                shadow: Color32::from_black_alpha(90),
+                checker_light: Color32::from_gray(110),
+                checker_dark: Color32::from_gray(80),
+            }
+        }
+    }
+}
+
+fn is_light(color: Color32) -> bool {
+    contrast_text(color) == Color32::BLACK
+}
+
+pub fn apply(ctx: &egui::Context, theme: Theme, custom_background: Color32) {
+    let light = match theme {
+        Theme::System | Theme::Dark => false,
+        Theme::Light => true,
+        Theme::Custom => is_light(custom_background),
+    };
+    ctx.set_visuals(if light {
+        egui::Visuals::light()
+    } else {
+        egui::Visuals::dark()
+    });
+}
+
+// Checkerboard backdrop shown behind images with transparency
+pub fn paint_checkerboard(painter: &egui::Painter, rect: egui::Rect, palette: &Palette) {
+    let cell = 12.0;
+    painter.rect_filled(rect, 0.0, palette.checker_light);
+
+    let columns = (rect.width() / cell).ceil() as usize;
+    let rows = (rect.height() / cell).ceil() as usize;
+    for row in 0..rows {
+        for column in (row % 2..columns).step_by(2) {
+            let min = rect.min + egui::vec2(column as f32 * cell, row as f32 * cell);
+            let cell_rect = egui::Rect::from_min_size(min, egui::vec2(cell, cell)).intersect(rect);
+            painter.rect_filled(cell_rect, 0.0, palette.checker_dark);
+        }
+    }
+}
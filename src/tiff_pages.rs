@@ -0,0 +1,100 @@
+use image::{DynamicImage, ImageBuffer};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::ColorType;
+
+// The image crate only ever decodes the first page of a TIFF, so the others
+// are read with the tiff crate directly
+
+fn is_tiff(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tif") || ext.eq_ignore_ascii_case("tiff"))
+}
+
+fn open(path: &Path) -> Option<Decoder<BufReader<File>>> {
+    Decoder::new(BufReader::new(File::open(path).ok()?)).ok()
+}
+
+// Number of pages in the file; 1 for anything that isn't a multi-page TIFF
+pub fn page_count(path: &Path) -> usize {
+    if !is_tiff(path) {
+        return 1;
+    }
+    let Some(mut decoder) = open(path) else {
+        return 1;
+    };
+    let mut count = 1;
+    while decoder.more_images() && decoder.next_image().is_ok() {
+        count += 1;
+    }
+    count
+}
+
+// One page, counting from 0. Only 8 and 16-bit gray and RGB(A) pages are
+// understood, which covers scans and most exported documents
+pub fn decode_page(path: &Path, page: usize) -> Option<DynamicImage> {
+    let mut decoder = open(path)?;
+    decoder.seek_to_image(page).ok()?;
+    let (width, height) = decoder.dimensions().ok()?;
+    let color = decoder.colortype().ok()?;
+    match (color, decoder.read_image().ok()?) {
+        (ColorType::Gray(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma8)
+        }
+        (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA8)
+        }
+        (ColorType::RGB(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+        }
+        (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+        }
+        (ColorType::Gray(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma16)
+        }
+        (ColorType::GrayA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA16)
+        }
+        (ColorType::RGB(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb16)
+        }
+        (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba16)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScratchDir;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    #[test]
+    fn pages_are_counted_and_decoded_one_by_one() {
+        let dir = ScratchDir::new("pages");
+        let path = dir.join("pages.tiff");
+        let mut encoder = TiffEncoder::new(File::create(&path).unwrap()).unwrap();
+        encoder
+            .write_image::<colortype::RGB8>(2, 1, &[255, 0, 0, 255, 0, 0])
+            .unwrap();
+        encoder
+            .write_image::<colortype::Gray8>(1, 3, &[10, 20, 30])
+            .unwrap();
+        drop(encoder);
+
+        assert_eq!(page_count(&path), 2);
+        let first = decode_page(&path, 0).unwrap().to_rgb8();
+        assert_eq!(first.dimensions(), (2, 1));
+        assert_eq!(first.get_pixel(1, 0).0, [255, 0, 0]);
+        let second = decode_page(&path, 1).unwrap().to_luma8();
+        assert_eq!(second.dimensions(), (1, 3));
+        assert_eq!(second.get_pixel(0, 2).0, [30]);
+        assert!(decode_page(&path, 2).is_none());
+    }
+}
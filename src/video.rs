@@ -0,0 +1,115 @@
+use crate::loader::DecodeError;
+use image::{DynamicImage, Rgb, RgbImage};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Video clips sorted like images, shown by a frame that ffmpeg picks, in
+// builds with the `video` feature
+
+pub const EXTENSIONS: [&str; 3] = ["mp4", "mov", "webm"];
+
+static FFMPEG_MISSING: AtomicBool = AtomicBool::new(false);
+static MISSING_REPORTED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+// A representative frame from near the start of the clip: ffmpeg's thumbnail
+// filter skips black and blurry frames among the first hundred or so. Without
+// ffmpeg every video gets the same placeholder. A clip with more than
+// `max_pixels` to the frame is refused before ffmpeg decodes any of it
+pub fn frame(path: &Path, max_pixels: Option<u64>) -> Result<DynamicImage, DecodeError> {
+    if let Some((width, height)) = max_pixels.and_then(|_| dimensions(path)) {
+        let pixels = width as u64 * height as u64;
+        if max_pixels.is_some_and(|max_pixels| pixels > max_pixels) {
+            return Err(DecodeError::TooLarge(pixels));
+        }
+    }
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-i"])
+        .arg(path)
+        .args(["-vf", "thumbnail", "-frames:v", "1"])
+        .args(["-f", "image2pipe", "-vcodec", "png", "-"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            image::load_from_memory(&output.stdout).map_err(|_| DecodeError::Unreadable)
+        }
+        Ok(_) => Err(DecodeError::Unreadable),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            FFMPEG_MISSING.store(true, Ordering::Relaxed);
+            Ok(placeholder())
+        }
+        Err(e) => {
+            log::warn!("Can't run ffmpeg for {}: {}", path.display(), e);
+            Err(DecodeError::Unreadable)
+        }
+    }
+}
+
+// Width and height of the clip's first video stream, as ffprobe, which comes
+// with ffmpeg, reads them from the container
+fn dimensions(path: &Path) -> Option<(u32, u32)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=width,height", "-of", "csv=p=0:s=x"])
+        .arg(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_dimensions(&String::from_utf8_lossy(&output.stdout))
+}
+
+// "1920x1080"
+fn parse_dimensions(text: &str) -> Option<(u32, u32)> {
+    let (width, height) = text.lines().next()?.trim().split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+// Placeholders aren't worth caching, as ffmpeg may be installed later
+pub fn ffmpeg_missing() -> bool {
+    FFMPEG_MISSING.load(Ordering::Relaxed)
+}
+
+// True once, after the first video that couldn't be opened for lack of ffmpeg
+pub fn take_missing_ffmpeg() -> bool {
+    ffmpeg_missing() && !MISSING_REPORTED.swap(true, Ordering::Relaxed)
+}
+
+// A dark 16:9 card with a play triangle
+fn placeholder() -> DynamicImage {
+    let (width, height) = (320, 180);
+    let center = (width as f32 / 2.0, height as f32 / 2.0);
+    let size = height as f32 / 4.0;
+    DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        let (dx, dy) = (x as f32 - center.0 + size / 3.0, y as f32 - center.1);
+        // Pointing right: a left edge and two slopes meeting at the tip
+        let inside = dx >= 0.0 && dy.abs() <= size * (1.0 - dx / (size * 1.5));
+        if inside {
+            Rgb([200, 200, 200])
+        } else {
+            Rgb([40, 40, 40])
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_dimensions;
+
+    #[test]
+    fn reads_ffprobe_dimensions() {
+        assert_eq!(parse_dimensions("3840x2160\n"), Some((3840, 2160)));
+        assert_eq!(parse_dimensions("1080x1920x\n"), None);
+        assert_eq!(parse_dimensions(""), None);
+    }
+}
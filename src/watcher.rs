@@ -0,0 +1,170 @@
+use crate::scan;
+use eframe::egui;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+// How long after the app's own operation on a path the watcher keeps leaving
+// that path alone, for events that arrive late
+const OWN_CHANGE_GRACE: Duration = Duration::from_secs(5);
+
+// Watches the category folders so their buckets can follow files that other
+// programs add or remove. Only the folder a change happened in is reported;
+// working out what changed is left to a re-read of it
+pub struct FolderWatcher {
+    watcher: RecommendedWatcher,
+    watched: HashSet<PathBuf>,
+}
+
+impl FolderWatcher {
+    pub fn new(tx: Sender<PathBuf>, ctx: egui::Context) -> Option<Self> {
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            for dir in event.paths.iter().filter_map(|path| path.parent()) {
+                let _ = tx.send(dir.to_path_buf());
+            }
+            ctx.request_repaint();
+        })
+        .map_err(|e| log::warn!("Can't watch category folders: {}", e))
+        .ok()?;
+        Some(Self {
+            watcher,
+            watched: HashSet::new(),
+        })
+    }
+
+    // Watch exactly those of `dirs` that exist, not their subfolders
+    pub fn set_dirs(&mut self, dirs: impl IntoIterator<Item = PathBuf>) {
+        let dirs: HashSet<PathBuf> = dirs.into_iter().filter(|dir| dir.is_dir()).collect();
+        for dir in self.watched.difference(&dirs) {
+            let _ = self.watcher.unwatch(dir);
+        }
+        self.watched.retain(|dir| dirs.contains(dir));
+        for dir in dirs {
+            if !self.watched.contains(&dir) {
+                match self.watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                    Ok(()) => {
+                        self.watched.insert(dir);
+                    }
+                    Err(e) => log::warn!("Can't watch {}: {}", dir.display(), e),
+                }
+            }
+        }
+    }
+}
+
+// Paths the app's own file operations are changing or just changed. A bucket
+// already follows those itself, so re-reading a folder leaves them alone
+#[derive(Clone, Default)]
+pub struct OwnChanges(Arc<Mutex<HashMap<PathBuf, (usize, Instant)>>>);
+
+impl OwnChanges {
+    pub fn start<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) {
+        let mut changes = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        for path in paths {
+            changes
+                .entry(path.to_path_buf())
+                .or_insert((0, Instant::now()))
+                .0 += 1;
+        }
+    }
+
+    pub fn finish<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) {
+        let mut changes = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        for path in paths {
+            if let Some((running, finished)) = changes.get_mut(path) {
+                *running = running.saturating_sub(1);
+                *finished = Instant::now();
+            }
+        }
+        changes
+            .retain(|_, (running, finished)| *running > 0 || finished.elapsed() < OWN_CHANGE_GRACE);
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        let changes = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        changes.get(path).is_some_and(|(running, finished)| {
+            *running > 0 || finished.elapsed() < OWN_CHANGE_GRACE
+        })
+    }
+}
+
+// What other programs changed in a folder since it was last read
+#[derive(Debug, Default, PartialEq)]
+pub struct FolderDiff {
+    pub gone: Vec<PathBuf>,
+    // With their sizes, newest first
+    pub added: Vec<(PathBuf, u64)>,
+}
+
+impl FolderDiff {
+    pub fn is_empty(&self) -> bool {
+        self.gone.is_empty() && self.added.is_empty()
+    }
+}
+
+// Read `dir` and compare it with `known`, the files its bucket holds. Blocks,
+// so run it off the UI thread
+pub fn diff_folder(
+    dir: &Path,
+    extensions: &[String],
+    known: &HashSet<PathBuf>,
+    own: &OwnChanges,
+) -> FolderDiff {
+    let on_disk = scan::list_folder(dir, extensions);
+    let present: HashSet<&PathBuf> = on_disk.iter().map(|(path, _)| path).collect();
+    let gone = known
+        .iter()
+        .filter(|path| !present.contains(path) && !own.contains(path))
+        .cloned()
+        .collect();
+    let added = on_disk
+        .into_iter()
+        .filter(|(path, _)| !known.contains(path) && !own.contains(path))
+        .collect();
+    FolderDiff { gone, added }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_folder, FolderDiff, OwnChanges};
+    use crate::ScratchDir;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn diff_skips_the_apps_own_changes() {
+        let dir = ScratchDir::new("watcher");
+        for name in ["kept.jpg", "theirs.jpg", "ours.jpg", "notes.txt"] {
+            std::fs::write(dir.join(name), b"1234").unwrap();
+        }
+        let extensions = vec!["jpg".to_string()];
+        let known: HashSet<PathBuf> = ["kept.jpg", "deleted.jpg", "moving.jpg"]
+            .iter()
+            .map(|name| dir.join(name))
+            .collect();
+
+        let own = OwnChanges::default();
+        let (ours, moving) = (dir.join("ours.jpg"), dir.join("moving.jpg"));
+        own.start([ours.as_path()]);
+        own.finish([ours.as_path()]);
+        own.start([moving.as_path()]);
+
+        let diff = diff_folder(&dir, &extensions, &known, &own);
+        assert_eq!(
+            diff,
+            FolderDiff {
+                gone: vec![dir.join("deleted.jpg")],
+                added: vec![(dir.join("theirs.jpg"), 4)],
+            }
+        );
+
+        own.finish([moving.as_path()]);
+        assert!(own.contains(&moving));
+    }
+}
@@ -0,0 +1,529 @@
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::name::{Namespace, QName, ResolveResult};
+use quick_xml::reader::NsReader;
+use quick_xml::Writer;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Elements and attributes are told apart by namespace, not prefix: other
+// software may bind these to any prefix it likes
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XMP_NS: &str = "http://ns.adobe.com/xap/1.0/";
+
+const EMPTY_PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>
+"#;
+
+// Sidecar writes are read-modify-write, so run them one at a time
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+// `photo.jpg` -> `photo.jpg.xmp`, which can't collide with `photo.png`'s sidecar
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".xmp");
+    PathBuf::from(name)
+}
+
+pub fn add_tag(path: &Path, tag: &str) -> io::Result<()> {
+    update(path, tag, true)
+}
+
+pub fn remove_tag(path: &Path, tag: &str) -> io::Result<()> {
+    update(path, tag, false)
+}
+
+fn update(path: &Path, tag: &str, add: bool) -> io::Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let sidecar = sidecar_path(path);
+
+    let existing = match std::fs::read_to_string(&sidecar) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if !add {
+                return Ok(());
+            }
+            EMPTY_PACKET.to_string()
+        }
+        Err(e) => return Err(e),
+    };
+
+    let updated = rewrite(&existing, tag, add)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    write_sidecar(&sidecar, updated)
+}
+
+// Write next to the original and rename, so other software never reads a
+// half-written sidecar
+fn write_sidecar(sidecar: &Path, contents: String) -> io::Result<()> {
+    let mut partial = sidecar.as_os_str().to_owned();
+    partial.push(".partial");
+    std::fs::write(&partial, contents)?;
+    std::fs::rename(&partial, sidecar)
+}
+
+// Star rating (1-5) from the sidecar's xmp:Rating, if it has one
+pub fn rating(path: &Path) -> Option<u8> {
+    read_rating(&std::fs::read_to_string(sidecar_path(path)).ok()?)
+}
+
+fn read_rating(xml: &str) -> Option<u8> {
+    let mut reader = NsReader::from_str(xml);
+    let mut in_rating = false;
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) | Event::Empty(e)
+                if is_element(&reader, e.name(), RDF_NS, "Description") =>
+            {
+                let value = e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| is_attribute(&reader, attr.key, XMP_NS, "Rating"))
+                    .and_then(|attr| attr.unescape_value().ok()?.trim().parse().ok());
+                if let Some(rating) = value {
+                    return valid_rating(rating);
+                }
+            }
+            Event::Start(e) if is_element(&reader, e.name(), XMP_NS, "Rating") => in_rating = true,
+            Event::Text(text) if in_rating => {
+                return valid_rating(text.unescape().ok()?.trim().parse().ok()?);
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+// 0 is "unrated" and negative values mean rejected
+fn valid_rating(rating: i32) -> Option<u8> {
+    (1..=5).contains(&rating).then_some(rating as u8)
+}
+
+// Set or, with None, clear the rating, keeping everything else in the sidecar
+pub fn set_rating(path: &Path, rating: Option<u8>) -> io::Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let sidecar = sidecar_path(path);
+
+    let existing = match std::fs::read_to_string(&sidecar) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if rating.is_none() {
+                return Ok(());
+            }
+            EMPTY_PACKET.to_string()
+        }
+        Err(e) => return Err(e),
+    };
+
+    let updated = rewrite_rating(&existing, rating)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    write_sidecar(&sidecar, updated)
+}
+
+// Stream the packet through, dropping any xmp:Rating and putting the new one
+// on the first rdf:Description as an attribute
+fn rewrite_rating(xml: &str, rating: Option<u8>) -> quick_xml::Result<String> {
+    let mut reader = NsReader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    let mut descriptions = 0;
+    // Nesting depth inside an xmp:Rating element being dropped
+    let mut skipping = 0;
+
+    loop {
+        let event = reader.read_event()?;
+        if skipping > 0 {
+            match event {
+                Event::Start(_) => skipping += 1,
+                Event::End(_) => skipping -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) if is_element(&reader, e.name(), XMP_NS, "Rating") => skipping = 1,
+            Event::Empty(e) if is_element(&reader, e.name(), XMP_NS, "Rating") => {}
+            Event::Start(e) if is_element(&reader, e.name(), RDF_NS, "Description") => {
+                descriptions += 1;
+                let e = if descriptions == 1 {
+                    with_rating(&reader, e, rating)
+                } else {
+                    e
+                };
+                writer.write_event(Event::Start(e))?;
+            }
+            Event::Empty(e) if is_element(&reader, e.name(), RDF_NS, "Description") => {
+                descriptions += 1;
+                let e = if descriptions == 1 {
+                    with_rating(&reader, e, rating)
+                } else {
+                    e
+                };
+                writer.write_event(Event::Empty(e))?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    if rating.is_some() && descriptions == 0 {
+        return Err(quick_xml::Error::UnexpectedEof(
+            "no rdf:Description to hold the rating".to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+fn with_rating(reader: &NsReader<&[u8]>, e: BytesStart, rating: Option<u8>) -> BytesStart<'static> {
+    let mut updated = BytesStart::new(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+    for attr in e.attributes().flatten() {
+        if !is_attribute(reader, attr.key, XMP_NS, "Rating") {
+            updated.push_attribute(attr);
+        }
+    }
+    if let Some(rating) = rating {
+        let (xmp, declare) = match declared_prefix(&e, XMP_NS) {
+            Some(prefix) => (prefix, false),
+            None => prefix_for(reader, XMP_NS, "xmp"),
+        };
+        if declare {
+            updated.push_attribute((format!("xmlns:{}", xmp).as_str(), XMP_NS));
+        }
+        let name = format!("{}:Rating", xmp);
+        updated.push_attribute((name.as_str(), rating.to_string().as_str()));
+    }
+    updated
+}
+
+// Stream the packet through unchanged, adding or dropping `tag` in the
+// dc:subject bag (created in the first rdf:Description if missing)
+fn rewrite(xml: &str, tag: &str, add: bool) -> quick_xml::Result<String> {
+    let has_subject = has_element(xml, DC_NS, "subject")?;
+    let mut reader = NsReader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+
+    let mut in_subject = false;
+    let mut descriptions = 0;
+    let mut present = false;
+    // Prefixes a new dc:subject in the first rdf:Description is written with
+    let mut new_subject = None;
+    // Events of the rdf:li currently being read, held back until we know
+    // whether it's the tag being removed
+    let mut item: Option<Vec<Event>> = None;
+
+    loop {
+        let event = reader.read_event()?;
+
+        if let Some(events) = &mut item {
+            let end =
+                matches!(&event, Event::End(e) if is_element(&reader, e.name(), RDF_NS, "li"));
+            events.push(event.into_owned());
+            if end {
+                let events = item.take().unwrap_or_default();
+                let mut text = String::new();
+                for event in &events {
+                    if let Event::Text(t) = event {
+                        text.push_str(&t.unescape()?);
+                    }
+                }
+                if text.trim() == tag {
+                    present = true;
+                    if !add {
+                        continue;
+                    }
+                }
+                for event in events {
+                    writer.write_event(event)?;
+                }
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) if in_subject && is_element(&reader, e.name(), RDF_NS, "li") => {
+                item = Some(vec![Event::Start(e.into_owned())]);
+            }
+            Event::Start(e) if is_element(&reader, e.name(), DC_NS, "subject") => {
+                in_subject = true;
+                writer.write_event(Event::Start(e))?;
+            }
+            Event::End(e) if is_element(&reader, e.name(), DC_NS, "subject") => {
+                in_subject = false;
+                writer.write_event(Event::End(e))?;
+            }
+            Event::Empty(e) if is_element(&reader, e.name(), DC_NS, "subject") => {
+                if add {
+                    let (rdf, declare) = prefix_for(&reader, RDF_NS, "rdf");
+                    let mut subject = e.into_owned();
+                    if declare {
+                        subject.push_attribute((format!("xmlns:{}", rdf).as_str(), RDF_NS));
+                    }
+                    write_subject(&mut writer, subject, &rdf, tag)?;
+                } else {
+                    writer.write_event(Event::Empty(e))?;
+                }
+            }
+            Event::End(e)
+                if in_subject
+                    && add
+                    && !present
+                    && (is_element(&reader, e.name(), RDF_NS, "Bag")
+                        || is_element(&reader, e.name(), RDF_NS, "Seq")) =>
+            {
+                write_item(&mut writer, &prefix_of(e.name()), tag)?;
+                present = true;
+                writer.write_event(Event::End(e))?;
+            }
+            Event::Start(e) if is_element(&reader, e.name(), RDF_NS, "Description") => {
+                descriptions += 1;
+                let e = if descriptions == 1 && add && !has_subject {
+                    let (e, dc) = with_dc_namespace(&reader, e);
+                    new_subject = Some((dc, prefix_of(e.name())));
+                    e
+                } else {
+                    e
+                };
+                writer.write_event(Event::Start(e))?;
+            }
+            Event::End(e)
+                if is_element(&reader, e.name(), RDF_NS, "Description")
+                    && descriptions == 1
+                    && !present =>
+            {
+                if let Some((dc, rdf)) = new_subject.take() {
+                    let subject = BytesStart::new(prefixed(&dc, "subject"));
+                    write_subject(&mut writer, subject, &rdf, tag)?;
+                    present = true;
+                }
+                writer.write_event(Event::End(e))?;
+            }
+            Event::Empty(e) if is_element(&reader, e.name(), RDF_NS, "Description") => {
+                descriptions += 1;
+                if descriptions == 1 && add && !has_subject {
+                    let (e, dc) = with_dc_namespace(&reader, e);
+                    let rdf = prefix_of(e.name());
+                    let end = e.to_end().into_owned();
+                    writer.write_event(Event::Start(e))?;
+                    let subject = BytesStart::new(prefixed(&dc, "subject"));
+                    write_subject(&mut writer, subject, &rdf, tag)?;
+                    writer.write_event(Event::End(end))?;
+                    present = true;
+                } else {
+                    writer.write_event(Event::Empty(e))?;
+                }
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    if add && !present {
+        return Err(quick_xml::Error::UnexpectedEof(
+            "no rdf:Description to hold the tag".to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+// Whether the packet has an element `local` in namespace `ns` anywhere
+fn has_element(xml: &str, ns: &str, local: &str) -> quick_xml::Result<bool> {
+    let mut reader = NsReader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if is_element(&reader, e.name(), ns, local) => {
+                return Ok(true)
+            }
+            Event::Eof => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+// Whether `name`, of the element just read, is `local` in namespace `ns`
+fn is_element(reader: &NsReader<&[u8]>, name: QName, ns: &str, local: &str) -> bool {
+    let (resolved, name) = reader.resolve_element(name);
+    name.as_ref() == local.as_bytes() && resolved == ResolveResult::Bound(Namespace(ns.as_bytes()))
+}
+
+// `is_element` for an attribute of the element just read
+fn is_attribute(reader: &NsReader<&[u8]>, name: QName, ns: &str, local: &str) -> bool {
+    let (resolved, name) = reader.resolve_attribute(name);
+    name.as_ref() == local.as_bytes() && resolved == ResolveResult::Bound(Namespace(ns.as_bytes()))
+}
+
+// A prefix for `ns` inside the element just read: `preferred` if it's bound
+// to `ns` there, else it or a numbered variant that isn't bound to anything,
+// in which case it still has to be declared. Whether it does
+fn prefix_for(reader: &NsReader<&[u8]>, ns: &str, preferred: &str) -> (String, bool) {
+    let mut prefix = preferred.to_string();
+    for n in 1.. {
+        let name = format!("{}:name", prefix);
+        match reader.resolve_attribute(QName(name.as_bytes())).0 {
+            ResolveResult::Bound(Namespace(bound)) if bound == ns.as_bytes() => break,
+            ResolveResult::Bound(_) => prefix = format!("{}{}", preferred, n),
+            _ => return (prefix, true),
+        }
+    }
+    (prefix, false)
+}
+
+// A prefix the element declares for `ns`, whatever it's called
+fn declared_prefix(e: &BytesStart, ns: &str) -> Option<String> {
+    e.attributes().flatten().find_map(|attr| {
+        let prefix = attr.key.as_ref().strip_prefix(b"xmlns:")?;
+        (attr.value.as_ref() == ns.as_bytes()).then(|| String::from_utf8_lossy(prefix).into_owned())
+    })
+}
+
+// The prefix `name` is written with, empty for the default namespace
+fn prefix_of(name: QName) -> String {
+    name.prefix()
+        .map(|prefix| String::from_utf8_lossy(prefix.as_ref()).into_owned())
+        .unwrap_or_default()
+}
+
+fn prefixed(prefix: &str, local: &str) -> String {
+    if prefix.is_empty() {
+        local.to_string()
+    } else {
+        format!("{}:{}", prefix, local)
+    }
+}
+
+// The rdf:Description, declaring a prefix for Dublin Core on it if none is
+// in scope, and that prefix
+fn with_dc_namespace<'a>(reader: &NsReader<&[u8]>, e: BytesStart<'a>) -> (BytesStart<'a>, String) {
+    let (dc, declare) = match declared_prefix(&e, DC_NS) {
+        Some(prefix) => (prefix, false),
+        None => prefix_for(reader, DC_NS, "dc"),
+    };
+    let mut e = e;
+    if declare {
+        e.push_attribute((format!("xmlns:{}", dc).as_str(), DC_NS));
+    }
+    (e, dc)
+}
+
+// `subject` holding a bag with just `tag`, `rdf` being the prefix for RDF
+fn write_subject(
+    writer: &mut Writer<Vec<u8>>,
+    subject: BytesStart,
+    rdf: &str,
+    tag: &str,
+) -> quick_xml::Result<()> {
+    let end = subject.to_end().into_owned();
+    let bag = prefixed(rdf, "Bag");
+    writer.write_event(Event::Start(subject))?;
+    writer.write_event(Event::Start(BytesStart::new(bag.as_str())))?;
+    write_item(writer, rdf, tag)?;
+    writer.write_event(Event::End(BytesEnd::new(bag.as_str())))?;
+    writer.write_event(Event::End(end))
+}
+
+fn write_item(writer: &mut Writer<Vec<u8>>, rdf: &str, tag: &str) -> quick_xml::Result<()> {
+    let li = prefixed(rdf, "li");
+    writer.write_event(Event::Start(BytesStart::new(li.as_str())))?;
+    writer.write_event(Event::Text(BytesText::new(tag)))?;
+    writer.write_event(Event::End(BytesEnd::new(li.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A sidecar as another program might write it: its own prefixes for RDF,
+    // Dublin Core and XMP, properties we don't know, and tags already set
+    const FOREIGN: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <R:RDF xmlns:R="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <R:Description R:about="" xmlns:purl="http://purl.org/dc/elements/1.1/"
+    xmlns:ap="http://ns.adobe.com/xap/1.0/" xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+    photoshop:City="Berlin" ap:Rating="3">
+   <purl:creator><R:Seq><R:li>Someone</R:li></R:Seq></purl:creator>
+   <purl:subject><R:Bag><R:li>holiday</R:li><R:li>beach</R:li></R:Bag></purl:subject>
+  </R:Description>
+ </R:RDF>
+</x:xmpmeta>"#;
+
+    // The dc:subject tags, by namespace
+    fn tags(xml: &str) -> Vec<String> {
+        let mut reader = NsReader::from_str(xml);
+        let (mut in_subject, mut in_item) = (false, false);
+        let mut tags = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(e) if is_element(&reader, e.name(), DC_NS, "subject") => {
+                    in_subject = true
+                }
+                Event::End(e) if is_element(&reader, e.name(), DC_NS, "subject") => {
+                    in_subject = false
+                }
+                Event::Start(e) if in_subject && is_element(&reader, e.name(), RDF_NS, "li") => {
+                    in_item = true
+                }
+                Event::End(e) if is_element(&reader, e.name(), RDF_NS, "li") => in_item = false,
+                Event::Text(text) if in_item => tags.push(text.unescape().unwrap().into_owned()),
+                Event::Eof => return tags,
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn foreign_sidecars_keep_their_prefixes_and_properties() {
+        let added = rewrite(FOREIGN, "family", true).unwrap();
+        assert_eq!(tags(&added), ["holiday", "beach", "family"]);
+        assert!(added.contains("<R:li>family</R:li></R:Bag></purl:subject>"));
+        assert!(!added.contains("dc:"));
+        assert!(added.contains(r#"photoshop:City="Berlin""#));
+        assert!(added.contains("<R:li>Someone</R:li>"));
+        // Adding a tag that's there changes nothing, and removing one takes
+        // back exactly what adding it put in
+        assert_eq!(rewrite(&added, "family", true).unwrap(), added);
+        assert_eq!(rewrite(&added, "family", false).unwrap(), FOREIGN);
+
+        let removed = rewrite(FOREIGN, "holiday", false).unwrap();
+        assert_eq!(tags(&removed), ["beach"]);
+        assert_eq!(read_rating(&removed), Some(3));
+
+        let rated = rewrite_rating(FOREIGN, Some(5)).unwrap();
+        assert_eq!(read_rating(&rated), Some(5));
+        assert!(rated.contains(r#"ap:Rating="5""#));
+        assert!(!rated.contains("xmlns:xmp"));
+        assert_eq!(tags(&rated), ["holiday", "beach"]);
+    }
+
+    #[test]
+    fn a_prefix_bound_to_something_else_is_left_alone() {
+        let xml = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+ <rdf:Description rdf:about="" xmlns:dc="urn:example:not-dublin-core">
+  <dc:subject>not a tag</dc:subject>
+ </rdf:Description>
+</rdf:RDF>"#;
+        let added = rewrite(xml, "family", true).unwrap();
+        assert_eq!(tags(&added), ["family"]);
+        assert!(added.contains(r#"xmlns:dc1="http://purl.org/dc/elements/1.1/""#));
+        assert!(added.contains("<dc:subject>not a tag</dc:subject>"));
+        assert_eq!(
+            rewrite(&added, "family", false)
+                .unwrap()
+                .matches("dc1:")
+                .count(),
+            2
+        );
+
+        let empty = rewrite(EMPTY_PACKET, "family", true).unwrap();
+        assert_eq!(tags(&empty), ["family"]);
+        assert_eq!(
+            tags(&rewrite(&empty, "family", false).unwrap()),
+            [] as [&str; 0]
+        );
+    }
+}